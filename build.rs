@@ -1,3 +1,5 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // `sqlx::migrate!` embeds the migrations, so rebuild when they change.
+    println!("cargo:rerun-if-changed=migrations");
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- Deployments that predate migrations created these tables by hand, hence IF NOT EXISTS.
+CREATE TABLE IF NOT EXISTS tokens (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL UNIQUE,
+    creator_address TEXT,
+    created_at TIMESTAMPTZ DEFAULT now(),
+    symbol TEXT,
+    bonding_address TEXT
+);
+
+CREATE TABLE IF NOT EXISTS token_metrics (
+    id BIGSERIAL PRIMARY KEY,
+    token_id BIGINT NOT NULL REFERENCES tokens (id) ON DELETE CASCADE,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    bonding_percent DOUBLE PRECISION NOT NULL,
+    ilv DOUBLE PRECISION NOT NULL,
+    social_replies INTEGER NOT NULL,
+    safety_score DOUBLE PRECISION NOT NULL,
+    liquidity DOUBLE PRECISION NOT NULL,
+    holders INTEGER NOT NULL,
+    volume NUMERIC,
+    buy_volume NUMERIC,
+    sell_volume NUMERIC
+);
+
+CREATE INDEX IF NOT EXISTS token_metrics_token_id_idx ON token_metrics (token_id, recorded_at);
@@ -0,0 +1,20 @@
+-- One row per pump.fun trade event. Amounts are raw base units
+-- (lamports for SOL, 10^-6 for curve tokens).
+CREATE TABLE trades (
+    id BIGSERIAL PRIMARY KEY,
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    mint_address TEXT NOT NULL,
+    trader TEXT NOT NULL,
+    is_buy BOOLEAN NOT NULL,
+    sol_amount BIGINT NOT NULL,
+    token_amount BIGINT NOT NULL,
+    virtual_sol_reserves BIGINT NOT NULL,
+    virtual_token_reserves BIGINT NOT NULL,
+    traded_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (signature, event_index)
+);
+
+CREATE INDEX trades_mint_time_idx ON trades (mint_address, traded_at);
+CREATE INDEX trades_trader_idx ON trades (trader);
@@ -0,0 +1,17 @@
+ALTER TABLE tokens
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'detected'
+        CHECK (status IN ('detected', 'monitoring', 'graduated', 'abandoned', 'rugged', 'filtered')),
+    ADD COLUMN status_changed_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX tokens_status_idx ON tokens (status, status_changed_at);
+
+CREATE TABLE token_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX token_status_history_mint_idx ON token_status_history (mint_address, changed_at);
@@ -0,0 +1,28 @@
+-- Verdicts deliberately don't reference `tokens` so the audit trail
+-- survives token pruning.
+CREATE TABLE rug_verdicts (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL,
+    checked_at TIMESTAMPTZ NOT NULL,
+    status TEXT NOT NULL,
+    confidence DOUBLE PRECISION NOT NULL,
+    risk_factors JSONB NOT NULL,
+    failures JSONB NOT NULL
+);
+
+CREATE INDEX rug_verdicts_mint_idx ON rug_verdicts (mint_address, checked_at);
+CREATE INDEX rug_verdicts_checked_at_idx ON rug_verdicts (checked_at);
+
+CREATE TABLE provider_responses (
+    id BIGSERIAL PRIMARY KEY,
+    verdict_id BIGINT NOT NULL REFERENCES rug_verdicts (id) ON DELETE CASCADE,
+    mint_address TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    http_status INTEGER,
+    latency_ms INTEGER NOT NULL,
+    body JSONB,
+    error TEXT,
+    fetched_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX provider_responses_verdict_idx ON provider_responses (verdict_id);
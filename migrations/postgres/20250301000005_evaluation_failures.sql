@@ -0,0 +1,9 @@
+CREATE TABLE evaluation_failures (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL,
+    stage TEXT NOT NULL,
+    error TEXT NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX evaluation_failures_mint_idx ON evaluation_failures (mint_address, failed_at);
@@ -0,0 +1,5 @@
+ALTER TABLE token_metrics
+    ADD COLUMN sampled_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN token_age_secs BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX token_metrics_sampled_idx ON token_metrics (token_id, sampled_at);
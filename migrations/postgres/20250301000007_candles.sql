@@ -0,0 +1,18 @@
+ALTER TABLE trades ADD COLUMN venue TEXT NOT NULL DEFAULT 'curve'
+    CHECK (venue IN ('curve', 'amm'));
+
+-- One row per token, resolution and bucket. Prices are SOL per whole token,
+-- volumes raw base units like trades.
+CREATE TABLE candles (
+    mint_address TEXT NOT NULL,
+    resolution TEXT NOT NULL CHECK (resolution IN ('1s', '5s', '1m', '5m', '1h')),
+    bucket_start TIMESTAMPTZ NOT NULL,
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    sol_volume BIGINT NOT NULL,
+    token_volume BIGINT NOT NULL,
+    trade_count INTEGER NOT NULL,
+    PRIMARY KEY (mint_address, resolution, bucket_start)
+);
@@ -0,0 +1,20 @@
+-- One feature vector per token and age; recomputing an age replaces it.
+CREATE TABLE token_analysis (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    age_secs BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    bonding_curve_progress REAL NOT NULL,
+    ilv REAL NOT NULL,
+    social_replies INTEGER NOT NULL,
+    metadata_score REAL NOT NULL,
+    creator_age BIGINT NOT NULL,
+    sell_pressure REAL NOT NULL,
+    holder_count INTEGER NOT NULL,
+    top10_holder_share REAL NOT NULL,
+    temporal_features REAL[] NOT NULL,
+    risk_score REAL NOT NULL,
+    UNIQUE (mint_address, age_secs)
+);
+
+CREATE INDEX tokens_creator_idx ON tokens (creator_address, created_at);
@@ -0,0 +1,18 @@
+-- Labelled feature snapshots. Like rug_verdicts, rows don't reference tokens
+-- so the dataset survives retention.
+CREATE TABLE model_training_data (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address TEXT NOT NULL,
+    age_secs BIGINT NOT NULL,
+    schema_version INTEGER NOT NULL,
+    features REAL[] NOT NULL,
+    outcome REAL NOT NULL,
+    outcome_label TEXT NOT NULL
+        CHECK (outcome_label IN ('graduated', 'peak_multiple', 'rugged', 'abandoned')),
+    peak_multiple REAL NOT NULL,
+    max_drawdown REAL NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (mint_address, age_secs, schema_version)
+);
+
+CREATE INDEX model_training_data_schema_idx ON model_training_data (schema_version, id);
@@ -0,0 +1,12 @@
+-- The funding graph: where each traced wallet got its first SOL, one edge
+-- per row. Wallets whose history was too long to trace, or whose first
+-- transaction didn't credit them, are kept with a NULL funder so they aren't
+-- looked up again. Like creator_launches, rows outlive retention.
+CREATE TABLE wallet_funding (
+    wallet TEXT PRIMARY KEY,
+    funder TEXT,
+    funded_at TIMESTAMPTZ,
+    checked_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX wallet_funding_funder_idx ON wallet_funding (funder);
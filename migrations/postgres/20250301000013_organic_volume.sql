@@ -0,0 +1,5 @@
+-- Volume with wash trading taken out, and the share of volume traded by the
+-- busiest wallets. NULL on samples taken before the analyzer existed.
+ALTER TABLE token_metrics
+    ADD COLUMN organic_volume NUMERIC,
+    ADD COLUMN top_trader_share DOUBLE PRECISION;
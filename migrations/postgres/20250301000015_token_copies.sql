@@ -0,0 +1,14 @@
+-- Perceptual hash of each token's metadata image, for matching re-uploads
+-- of the same picture under a different URL.
+ALTER TABLE token_metadata ADD COLUMN image_hash BIGINT;
+
+-- Launches that copy an earlier one's name, symbol or image. original_mint
+-- is the first launch in the copy chain and may since have been deleted.
+CREATE TABLE token_copies (
+    mint_address TEXT PRIMARY KEY REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    original_mint TEXT NOT NULL,
+    matched_on TEXT NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX token_copies_original_idx ON token_copies (original_mint);
@@ -0,0 +1,3 @@
+-- Nothing ever collected reply counts; every stored value is 0.
+ALTER TABLE token_metrics DROP COLUMN social_replies;
+ALTER TABLE token_analysis DROP COLUMN social_replies;
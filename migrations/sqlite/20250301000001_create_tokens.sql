@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint_address TEXT NOT NULL UNIQUE,
+    creator_address TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    symbol TEXT,
+    bonding_address TEXT
+);
+
+-- NUMERIC volumes are stored as decimal strings; SQLite has no arbitrary-precision type.
+CREATE TABLE IF NOT EXISTS token_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_id INTEGER NOT NULL REFERENCES tokens (id) ON DELETE CASCADE,
+    recorded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    bonding_percent REAL NOT NULL,
+    ilv REAL NOT NULL,
+    social_replies INTEGER NOT NULL,
+    safety_score REAL NOT NULL,
+    liquidity REAL NOT NULL,
+    holders INTEGER NOT NULL,
+    volume TEXT,
+    buy_volume TEXT,
+    sell_volume TEXT
+);
+
+CREATE INDEX IF NOT EXISTS token_metrics_token_id_idx ON token_metrics (token_id, recorded_at);
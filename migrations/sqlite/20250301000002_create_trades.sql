@@ -0,0 +1,18 @@
+CREATE TABLE trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    mint_address TEXT NOT NULL,
+    trader TEXT NOT NULL,
+    is_buy BOOLEAN NOT NULL,
+    sol_amount INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    virtual_sol_reserves INTEGER NOT NULL,
+    virtual_token_reserves INTEGER NOT NULL,
+    traded_at TEXT NOT NULL,
+    UNIQUE (signature, event_index)
+);
+
+CREATE INDEX trades_mint_time_idx ON trades (mint_address, traded_at);
+CREATE INDEX trades_trader_idx ON trades (trader);
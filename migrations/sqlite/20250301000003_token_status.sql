@@ -0,0 +1,18 @@
+ALTER TABLE tokens ADD COLUMN status TEXT NOT NULL DEFAULT 'detected'
+    CHECK (status IN ('detected', 'monitoring', 'graduated', 'abandoned', 'rugged', 'filtered'));
+-- SQLite can't add a column with a non-constant default; the store always
+-- sets status_changed_at explicitly.
+ALTER TABLE tokens ADD COLUMN status_changed_at TEXT NOT NULL DEFAULT '1970-01-01T00:00:00+00:00';
+
+CREATE INDEX tokens_status_idx ON tokens (status, status_changed_at);
+
+CREATE TABLE token_status_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint_address TEXT NOT NULL REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX token_status_history_mint_idx ON token_status_history (mint_address, changed_at);
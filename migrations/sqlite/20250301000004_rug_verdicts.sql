@@ -0,0 +1,26 @@
+CREATE TABLE rug_verdicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint_address TEXT NOT NULL,
+    checked_at TEXT NOT NULL,
+    status TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    risk_factors TEXT NOT NULL,
+    failures TEXT NOT NULL
+);
+
+CREATE INDEX rug_verdicts_mint_idx ON rug_verdicts (mint_address, checked_at);
+CREATE INDEX rug_verdicts_checked_at_idx ON rug_verdicts (checked_at);
+
+CREATE TABLE provider_responses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    verdict_id INTEGER NOT NULL REFERENCES rug_verdicts (id) ON DELETE CASCADE,
+    mint_address TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    http_status INTEGER,
+    latency_ms INTEGER NOT NULL,
+    body TEXT,
+    error TEXT,
+    fetched_at TEXT NOT NULL
+);
+
+CREATE INDEX provider_responses_verdict_idx ON provider_responses (verdict_id);
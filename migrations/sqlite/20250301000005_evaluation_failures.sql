@@ -0,0 +1,9 @@
+CREATE TABLE evaluation_failures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint_address TEXT NOT NULL,
+    stage TEXT NOT NULL,
+    error TEXT NOT NULL,
+    failed_at TEXT NOT NULL
+);
+
+CREATE INDEX evaluation_failures_mint_idx ON evaluation_failures (mint_address, failed_at);
@@ -0,0 +1,5 @@
+-- ADD COLUMN needs a constant default; the store always binds sampled_at.
+ALTER TABLE token_metrics ADD COLUMN sampled_at TEXT NOT NULL DEFAULT '1970-01-01T00:00:00+00:00';
+ALTER TABLE token_metrics ADD COLUMN token_age_secs INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX token_metrics_sampled_idx ON token_metrics (token_id, sampled_at);
@@ -0,0 +1,16 @@
+ALTER TABLE trades ADD COLUMN venue TEXT NOT NULL DEFAULT 'curve'
+    CHECK (venue IN ('curve', 'amm'));
+
+CREATE TABLE candles (
+    mint_address TEXT NOT NULL,
+    resolution TEXT NOT NULL CHECK (resolution IN ('1s', '5s', '1m', '5m', '1h')),
+    bucket_start TEXT NOT NULL,
+    open REAL NOT NULL,
+    high REAL NOT NULL,
+    low REAL NOT NULL,
+    close REAL NOT NULL,
+    sol_volume INTEGER NOT NULL,
+    token_volume INTEGER NOT NULL,
+    trade_count INTEGER NOT NULL,
+    PRIMARY KEY (mint_address, resolution, bucket_start)
+);
@@ -0,0 +1,2 @@
+-- Retention rolls up and deletes trades by age across all mints.
+CREATE INDEX trades_traded_at_idx ON trades (traded_at);
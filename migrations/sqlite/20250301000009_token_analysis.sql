@@ -0,0 +1,21 @@
+-- One feature vector per token and age; recomputing an age replaces it.
+-- temporal_features is a JSON array.
+CREATE TABLE token_analysis (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mint_address TEXT NOT NULL REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    age_secs INTEGER NOT NULL,
+    computed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    bonding_curve_progress REAL NOT NULL,
+    ilv REAL NOT NULL,
+    social_replies INTEGER NOT NULL,
+    metadata_score REAL NOT NULL,
+    creator_age INTEGER NOT NULL,
+    sell_pressure REAL NOT NULL,
+    holder_count INTEGER NOT NULL,
+    top10_holder_share REAL NOT NULL,
+    temporal_features TEXT NOT NULL,
+    risk_score REAL NOT NULL,
+    UNIQUE (mint_address, age_secs)
+);
+
+CREATE INDEX tokens_creator_idx ON tokens (creator_address, created_at);
@@ -0,0 +1,40 @@
+-- One row per launch, kept after the token itself is pruned so a creator's
+-- track record outlives retention. Like rug_verdicts, rows don't reference
+-- tokens.
+CREATE TABLE creator_launches (
+    mint_address TEXT PRIMARY KEY,
+    creator_address TEXT NOT NULL,
+    launched_at TEXT NOT NULL,
+    -- graduated, rugged or abandoned once the token reaches one of them.
+    outcome TEXT CHECK (outcome IN ('graduated', 'rugged', 'abandoned')),
+    ended_at TEXT,
+    -- The creator's first sell of the token.
+    first_sell_at TEXT,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX creator_launches_creator_idx ON creator_launches (creator_address, launched_at);
+
+-- Reputation aggregated from creator_launches, plus where the wallet got its
+-- first SOL.
+CREATE TABLE creators (
+    address TEXT PRIMARY KEY,
+    launch_count INTEGER NOT NULL,
+    graduated_count INTEGER NOT NULL,
+    rugged_count INTEGER NOT NULL,
+    abandoned_count INTEGER NOT NULL,
+    -- Over launches with an outcome.
+    avg_lifetime_secs REAL,
+    -- Launches the creator sold into, and how soon after launch on average.
+    sold_count INTEGER NOT NULL,
+    avg_first_sell_secs REAL,
+    first_launch_at TEXT NOT NULL,
+    last_launch_at TEXT NOT NULL,
+    funding_source TEXT,
+    funded_at TEXT,
+    -- Set once the funding lookup ran, whether or not it found a source.
+    funding_checked_at TEXT,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX creators_funding_idx ON creators (funding_checked_at);
@@ -0,0 +1,4 @@
+-- Volume with wash trading taken out, and the share of volume traded by the
+-- busiest wallets. NULL on samples taken before the analyzer existed.
+ALTER TABLE token_metrics ADD COLUMN organic_volume TEXT;
+ALTER TABLE token_metrics ADD COLUMN top_trader_share REAL;
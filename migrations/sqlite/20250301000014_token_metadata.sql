@@ -0,0 +1,23 @@
+-- Off-chain metadata fetched from the URI in each token's create
+-- instruction. A fetch that failed on every gateway is kept with
+-- fetch_error set, so the token still has a score.
+CREATE TABLE token_metadata (
+    mint_address TEXT PRIMARY KEY REFERENCES tokens (mint_address) ON DELETE CASCADE,
+    uri TEXT NOT NULL,
+    name TEXT,
+    symbol TEXT,
+    description TEXT,
+    -- Hash of the normalized description, for spotting reused ones.
+    description_hash INTEGER,
+    image TEXT,
+    twitter TEXT,
+    telegram TEXT,
+    website TEXT,
+    -- 0-1, from completeness, less penalties for a reused image or description.
+    metadata_score REAL NOT NULL,
+    fetch_error TEXT,
+    fetched_at TEXT NOT NULL
+);
+
+CREATE INDEX token_metadata_image_idx ON token_metadata (image);
+CREATE INDEX token_metadata_description_idx ON token_metadata (description_hash);
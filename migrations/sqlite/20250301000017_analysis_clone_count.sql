@@ -0,0 +1,3 @@
+-- Copies of a token's original, or of the token itself, detected by the
+-- age the row describes. A feature, so the copycat signal reaches scoring.
+ALTER TABLE token_analysis ADD COLUMN clone_count INTEGER NOT NULL DEFAULT 0;
@@ -1,8 +1,10 @@
 mod models;
 mod managers;
+mod providers;
+mod scripts;
 mod utils;
 use {
-    anyhow::Result, dotenv::dotenv, managers::{db_manager::DbManager, grpc_manager::GrpcStreamManager, swqos_manager::SwqosRpcClient}, models::token, solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig}, solana_sdk::{commitment_config::CommitmentConfig, pubkey::{self, Pubkey}, signature::Signature}, solana_transaction_status::UiTransactionEncoding, std::{collections::HashMap, env, str::FromStr, sync::Arc, thread::sleep, time::Duration}, tokio::sync::mpsc, yellowstone_grpc_proto::{
+    anyhow::Result, chrono::{DateTime, Utc}, dotenv::dotenv, managers::{analysis_manager::{feature_names, load_risk_model, FEATURE_SCHEMA_VERSION}, candle_manager::{CandleAggregator, CandleAggregatorConfig}, copycat_manager::CopycatDetector, creator_manager::{CreatorConfig, CreatorTracker}, db_manager::DbManager, dev_sell_manager::{log_signals, sell_dumped, DevSellConfig, DevSellDetector}, funding_manager::{FundingConfig, FundingGraph}, grpc_manager::GrpcStreamManager, labelling_manager::{LabellingConfig, LabellingJob}, launch_manager::{LaunchPipeline, LaunchPipelineConfig}, metadata_manager::MetadataCollector, metrics_manager::{MetricsScheduler, MetricsSchedulerConfig}, retention_manager::{RetentionConfig, RetentionJob}, swqos_manager::{ConfirmationTracker, SenderConfig, SwqosRpcClient, TransactionSender}, trade_manager::TradeRecorder}, providers::RiskAggregator, scripts::swap::PumpSwapper, solana_client::{nonblocking::rpc_client::RpcClient as AsyncRpcClient, rpc_client::RpcClient, rpc_config::RpcTransactionConfig}, solana_sdk::{commitment_config::CommitmentConfig, signature::{read_keypair_file, Signature, Signer}, system_instruction}, solana_transaction_status::UiTransactionEncoding, std::{collections::HashMap, env, path::Path, str::FromStr, sync::Arc}, tokio::sync::mpsc, utils::{copycat::CopycatConfig, http_client::{HttpClient, HttpClientConfig}, metadata_fetch::{MetadataConfig, MetadataFetcher}, pump_fun::PUMP_AMM_PROGRAM_ID, risk_model::{RiskModel, TrainingConfig}}, yellowstone_grpc_proto::{
         geyser::{
             SubscribeRequest, SubscribeRequestFilterTransactions
         },
@@ -40,16 +42,127 @@ async fn _lookup_transaction(
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<()> {
     dotenv().ok();
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("export-dataset") {
+        let database_uri = env::var("DATABASE_URL").expect("Missing DB_URL environment variable");
+        let db_manager = DbManager::new(&database_uri).await?;
+        let path = args.get(2).map(String::as_str).unwrap_or("dataset.csv.gz");
+        let rows = LabellingJob::new(db_manager, LabellingConfig::from_env())
+            .export_dataset(Path::new(path))
+            .await?;
+        println!("Exported {rows} training rows to {path}");
+        return Ok(());
+    }
+    if args.get(1).map(String::as_str) == Some("audit") {
+        let database_uri = env::var("DATABASE_URL").expect("Missing DB_URL environment variable");
+        let db_manager = DbManager::new(&database_uri).await?;
+        match (args.get(2), args.get(3)) {
+            (Some(from), Some(to)) => {
+                let from = DateTime::parse_from_rfc3339(from)?.with_timezone(&Utc);
+                let to = DateTime::parse_from_rfc3339(to)?.with_timezone(&Utc);
+                scripts::audit::audit_verdicts(&db_manager, from, to).await?;
+            }
+            (Some(mint_address), None) => scripts::audit::audit_token(&db_manager, mint_address).await?,
+            _ => eprintln!("Usage: audit <mint> | audit <from> <to> (RFC 3339)"),
+        }
+        return Ok(());
+    }
+    if args.get(1).map(String::as_str) == Some("send-test") {
+        // A 1-lamport self-transfer through the sender, e.g. against
+        // solana-test-validator.
+        let rpc_endpoint = env::var("RPC_ENDPOINT").expect("Missing RPC_ENDPOINT");
+        let path = env::var("SWAP_KEYPAIR_PATH").expect("Missing SWAP_KEYPAIR_PATH");
+        let payer = read_keypair_file(&path).map_err(|e| anyhow::anyhow!("Failed to read keypair {path}: {e}"))?;
+        let sender = TransactionSender::new(
+            &SwqosRpcClient::new(&rpc_endpoint),
+            Arc::new(ConfirmationTracker::default()),
+            SenderConfig::from_env()?,
+        );
+        let transfer = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+        let report = sender.send(&[transfer], &payer).await?;
+        println!(
+            "{}: {:?} after {} broadcasts at {} micro-lamports per CU",
+            report.signature, report.status, report.broadcasts, report.compute_unit_price
+        );
+        return Ok(());
+    }
+    if args.get(1).map(String::as_str) == Some("train") {
+        let database_uri = env::var("DATABASE_URL").expect("Missing DB_URL environment variable");
+        let db_manager = DbManager::new(&database_uri).await?;
+        let default_path = env::var("RISK_MODEL_PATH").unwrap_or_else(|_| "risk_model.json".to_string());
+        let path = args.get(2).unwrap_or(&default_path);
+        let rows = db_manager.get_training_data(FEATURE_SCHEMA_VERSION).await?;
+        let model = RiskModel::train(&rows, FEATURE_SCHEMA_VERSION, &feature_names(), &TrainingConfig::from_env())?;
+        match &model.evaluation {
+            Some(e) => println!(
+                "Trained on {} rows; held out {} ({} positive): precision {:.3}, recall {:.3}, AUC {:.3} at threshold {:.3}",
+                rows.len(),
+                e.samples,
+                e.positives,
+                e.precision,
+                e.recall,
+                e.auc,
+                model.threshold
+            ),
+            None => println!("Trained on {} rows; nothing held out for evaluation", rows.len()),
+        }
+        model.save(Path::new(path))?;
+        println!("Saved risk model to {path}");
+        return Ok(());
+    }
+
     let grpc_endpoint = env::var("GRPC_ENDPOINT").expect("Missing GRPC Endpoint variable");
     let database_uri = env::var("DATABASE_URL").expect("Missing DB_URL environment variable");
     let rpc_endpoint = env::var("RPC_ENDPOINT").expect("Missing RPC_ENDPOINT");
     let rpc_manager = Arc::new(SwqosRpcClient::new(&rpc_endpoint));
     let db_manager = DbManager::new(&database_uri).await?;
-    let (tx, mut rx) = mpsc::channel::<models::token::TokenInfo>(100);
+    let http_client = HttpClient::new(HttpClientConfig::from_env())?;
+    tokio::spawn(http_client.clone().log_metrics());
+    // Signature history lookups are slow, so the funding graph gets a client
+    // without the short timeout used on the launch path.
+    let funding_rpc = Arc::new(AsyncRpcClient::new(rpc_endpoint.clone()));
+    let funding_graph = Arc::new(FundingGraph::new(db_manager.clone(), funding_rpc, FundingConfig::from_env()));
+    let aggregator = Arc::new(RiskAggregator::from_env(
+        rpc_manager.rpc(),
+        http_client,
+        db_manager.clone(),
+        funding_graph.clone(),
+    ));
+    let metadata_collector = Arc::new(MetadataCollector::new(db_manager.clone(), MetadataFetcher::new(MetadataConfig::from_env())?));
+    let copycat = Arc::new(CopycatDetector::new(db_manager.clone(), CopycatConfig::from_env()));
+    // Before any launch is tagged, so the index stays in launch order.
+    match copycat.resume().await {
+        Ok(loaded) => println!("Loaded {loaded} recent launches into the copycat index"),
+        Err(e) => eprintln!("Failed to load recent launches for copycat detection: {:?}", e),
+    }
+    // Fed by the Geyser stream so our own transactions confirm as soon as
+    // they show up on it.
+    let confirmations = Arc::new(ConfirmationTracker::default());
+    let sender = Arc::new(TransactionSender::new(&rpc_manager, confirmations.clone(), SenderConfig::from_env()?));
+    let swapper = PumpSwapper::from_env(rpc_manager.clone(), sender)?.map(Arc::new);
+    if let Some(swapper) = &swapper {
+        println!("Buying identified tokens from {}", swapper.payer());
+    }
+    let risk_model = load_risk_model();
+    let metrics_scheduler = MetricsScheduler::new(
+        db_manager.clone(),
+        rpc_manager.clone(),
+        risk_model.clone(),
+        MetricsSchedulerConfig::from_env()?,
+    );
+    let (tx, rx) = mpsc::channel::<models::token::TokenInfo>(100);
+    let (trade_tx, trade_rx) = mpsc::channel::<models::trade::Trade>(10_000);
+    let dev_sells = Arc::new(DevSellDetector::new(DevSellConfig::from_env()));
+    if let Some(swapper) = swapper.clone().filter(|swapper| swapper.sells_on_dev_dump()) {
+        tokio::spawn(sell_dumped(swapper, dev_sells.subscribe()));
+    }
     let manager = GrpcStreamManager::new(
         &grpc_endpoint,
         "",
-        tx
+        tx,
+        trade_tx,
+        dev_sells.clone(),
+        confirmations,
     ).await?;
 
     // Create subscription request for token program transactions
@@ -64,6 +177,17 @@ async fn main() -> Result<()> {
                 account_exclude: vec![],
                 account_required: vec!["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P".to_string()],
             },
+        ), (
+            // Swaps on the AMM that completed curves migrate to.
+            "pump_amm".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                signature: None,
+                account_include: vec![],
+                account_exclude: vec![],
+                account_required: vec![PUMP_AMM_PROGRAM_ID.to_string()],
+            },
         )]),
         commitment: Some(CommitmentLevel::Confirmed as i32),
         ..Default::default()
@@ -72,40 +196,36 @@ async fn main() -> Result<()> {
    
 
 
-    // let token_monitor = {
-    //     let db_manager_clone = db_manager.clone();
-    //     tokio::spawn(async move {
-    //         println!("Monitoring the database for tokens");
-    //         loop {
-    //             if let Err(_) = db_manager_clone.process_all_tokens().await {
-    //                 println!("Error processing tokens");
-    //             }
-    //             sleep(Duration::from_secs(5));
-    //         }
-    //     })
-    // };
-
-    let db_consumer = {
-        let db_manager = db_manager.clone();
-        let rpc_manager_clone = rpc_manager.clone();
-        tokio::spawn(async move {
-            while let Some(token_info) = rx.recv().await {
-                let pubkey = Pubkey::from_str(&token_info.mint_address).unwrap();
-                sleep(Duration::from_secs(20));
-                match rpc_manager_clone.validate_token(&pubkey).await {
-                    Ok(v) => {
-                        if v {
-                            println!("Buy identified: {:?}", &pubkey.to_string());
-                        }
-                    },
-                    Err(e) => {
-                        // eprintln!("Error validating token: {e:?}")
-                    }
-                }
-            }
-        })
+    let launch_pipeline = {
+        let pipeline = LaunchPipeline::new(
+            db_manager.clone(),
+            rpc_manager.clone(),
+            aggregator.clone(),
+            metrics_scheduler.clone(),
+            dev_sells.clone(),
+            funding_graph.clone(),
+            metadata_collector,
+            copycat,
+            swapper,
+            risk_model,
+            LaunchPipelineConfig::from_env(),
+        );
+        tokio::spawn(pipeline.run(rx))
     };
 
+    let candle_aggregator = CandleAggregator::new(db_manager.clone(), CandleAggregatorConfig::from_env());
+    let candle_flusher = tokio::spawn(candle_aggregator.clone().run());
+    let signal_log = tokio::spawn(log_signals(dev_sells.subscribe()));
+    let trade_recorder = tokio::spawn(TradeRecorder::new(db_manager.clone(), candle_aggregator, dev_sells).run(trade_rx));
+    let labelling_job = tokio::spawn(LabellingJob::new(db_manager.clone(), LabellingConfig::from_env()).run());
+    let retention_job = tokio::spawn(RetentionJob::new(db_manager.clone(), RetentionConfig::from_env()).run());
+    let creator_tracker = tokio::spawn(CreatorTracker::new(db_manager.clone(), funding_graph, CreatorConfig::from_env()).run());
+
+    match metrics_scheduler.resume().await {
+        Ok(resumed) => println!("Resumed metrics sampling for {resumed} tokens"),
+        Err(e) => eprintln!("Failed to resume metrics sampling: {:?}", e),
+    }
+
 
 
     let pump_fun_listener = {
@@ -119,6 +239,16 @@ async fn main() -> Result<()> {
     };
 
     
-    tokio::join!(db_consumer, pump_fun_listener);
+    tokio::select! {
+        _ = async {
+            tokio::join!(launch_pipeline, trade_recorder, candle_flusher, labelling_job, retention_job, creator_tracker, signal_log, pump_fun_listener)
+        } => {}
+        _ = tokio::signal::ctrl_c() => {
+            // Launches, trades and samples still in the write buffer would
+            // otherwise be lost.
+            println!("Shutting down; writing buffered rows");
+            db_manager.flush().await?;
+        }
+    }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,446 @@
+use {
+    super::db_manager::DbManager,
+    crate::{
+        models::{
+            token::{TokenAnalysis, TokenRecord},
+            trade::{Trade, TradeVenue},
+        },
+        utils::{pump_fun::BondingCurve, risk_model::RiskModel},
+    },
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    solana_sdk::native_token::LAMPORTS_PER_SOL,
+    std::{collections::HashMap, env, path::Path, sync::Arc, time::Duration},
+};
+
+/// Number of equal slices `TokenAnalysis::temporal_features` splits a
+/// token's life into.
+pub const TEMPORAL_SLICES: usize = 6;
+
+/// Version of the layout `feature_vector` produces. Training rows and models
+/// record it, so any change to `feature_names` or `feature_vector` must bump
+/// it.
+pub const FEATURE_SCHEMA_VERSION: i32 = 5;
+
+/// Column names of `feature_vector`, in order.
+pub fn feature_names() -> Vec<String> {
+    let mut names: Vec<String> = [
+        "bonding_curve_progress",
+        "ilv",
+        "metadata_score",
+        "creator_age",
+        "sell_pressure",
+        "holder_count",
+        "top10_holder_share",
+        "clone_count",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect();
+    for slice in 0..TEMPORAL_SLICES {
+        names.push(format!("slice{slice}_trades"));
+        names.push(format!("slice{slice}_buy_sol"));
+        names.push(format!("slice{slice}_sell_sol"));
+    }
+    names
+}
+
+/// Flattens an analysis into the fixed layout models are trained on.
+///
+/// `risk_score` is left out: it is the model's own output, and training rows
+/// are labelled from trades the rug check never saw.
+pub fn feature_vector(analysis: &TokenAnalysis) -> Vec<f32> {
+    let mut features = vec![
+        analysis.bonding_curve_progress,
+        analysis.ilv,
+        analysis.metadata_score,
+        analysis.creator_age as f32,
+        analysis.sell_pressure,
+        analysis.holder_count as f32,
+        analysis.top10_holder_share,
+        analysis.clone_count as f32,
+    ];
+    // Older rows may have been computed with a different slice count.
+    let mut temporal = analysis.temporal_features.clone();
+    temporal.resize(TEMPORAL_SLICES * 3, 0.0);
+    features.extend(temporal);
+    features
+}
+
+/// Loads the model at `RISK_MODEL_PATH` (default `risk_model.json`), as
+/// written by the `train` subcommand. `RISK_MODEL_THRESHOLD` overrides the
+/// threshold chosen in training. Returns `None`, and tokens go unscored, if
+/// there is no usable model for the current feature schema.
+pub fn load_risk_model() -> Option<Arc<RiskModel>> {
+    let path = env::var("RISK_MODEL_PATH").unwrap_or_else(|_| "risk_model.json".to_string());
+    if !Path::new(&path).exists() {
+        println!("No risk model at {path}; tokens will not be scored");
+        return None;
+    }
+    match RiskModel::load(Path::new(&path), FEATURE_SCHEMA_VERSION, &feature_names()) {
+        Ok(mut model) => {
+            if let Some(threshold) = env::var("RISK_MODEL_THRESHOLD").ok().and_then(|v| v.parse().ok()) {
+                model.threshold = threshold;
+            }
+            println!("Loaded risk model from {path} (threshold {:.2})", model.threshold);
+            Some(Arc::new(model))
+        }
+        Err(e) => {
+            eprintln!("Failed to load risk model from {path}: {e:?}");
+            None
+        }
+    }
+}
+
+/// Builds `TokenAnalysis` feature vectors from what the monitor has stored.
+///
+/// Every feature is computed as of a token age using only rows recorded by
+/// then, so a vector computed long after the fact matches one computed live.
+/// With a risk model, `risk_score` is one minus the model's probability that
+/// buying at that age is a good buy.
+pub struct FeatureExtractor {
+    db_manager: Arc<DbManager>,
+    risk_model: Option<Arc<RiskModel>>,
+}
+
+impl FeatureExtractor {
+    pub fn new(db_manager: Arc<DbManager>, risk_model: Option<Arc<RiskModel>>) -> Self {
+        FeatureExtractor { db_manager, risk_model }
+    }
+
+    /// Computes the features of a token `age` after its launch.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token to analyze
+    /// * `age` - Time since launch the features should describe
+    pub async fn extract(&self, mint_address: &str, age: Duration) -> Result<TokenAnalysis> {
+        let token = self
+            .db_manager
+            .get_token(mint_address)
+            .await?
+            .ok_or_else(|| anyhow!("Token {mint_address} not found"))?;
+        let launched_at = token.created_at.unwrap_or(token.status_changed_at);
+        let as_of = launched_at + chrono::Duration::from_std(age)?;
+
+        let creator_first_launch = async {
+            let Some(creator) = &token.creator_address else {
+                return Ok(None);
+            };
+            let (record, tokens) = tokio::try_join!(
+                self.db_manager.get_creator(creator),
+                self.db_manager.get_tokens_by_creator(creator),
+            )?;
+            // The creator record remembers launches retention has deleted;
+            // the tokens cover ones it hasn't synced yet.
+            Ok::<_, anyhow::Error>(
+                record
+                    .map(|record| record.first_launch_at)
+                    .into_iter()
+                    .chain(tokens.iter().filter_map(|t| t.created_at))
+                    .min(),
+            )
+        };
+        // Copies of the original this token copies, or of this token if it
+        // is one, detected by then.
+        let clone_count = async {
+            let original = self
+                .db_manager
+                .get_token_copy(mint_address)
+                .await?
+                .filter(|copy| copy.detected_at <= as_of)
+                .map(|copy| copy.original_mint)
+                .unwrap_or_else(|| mint_address.to_string());
+            self.db_manager.count_clones(&original, as_of).await
+        };
+        let (trades, metadata, creator_first_launch, clone_count) = tokio::try_join!(
+            self.db_manager.get_trades(mint_address),
+            self.db_manager.get_token_metadata(mint_address),
+            creator_first_launch,
+            clone_count,
+        )?;
+
+        let trades: Vec<Trade> = trades.into_iter().filter(|t| t.traded_at <= as_of).collect();
+        // Metadata fetched after `as_of` wasn't known yet.
+        let metadata_score = metadata
+            .filter(|m| m.fetched_at <= as_of)
+            .map(|m| m.metadata_score as f32)
+            .unwrap_or(0.0);
+
+        let mut analysis = compute_features(
+            &token,
+            launched_at,
+            age,
+            &trades,
+            metadata_score,
+            creator_first_launch,
+            clone_count as u32,
+        );
+        if let Some(model) = &self.risk_model {
+            analysis.risk_score = 1.0 - model.score(&feature_vector(&analysis));
+        }
+        Ok(analysis)
+    }
+
+    /// Computes the features of a token `age` after its launch and stores them.
+    pub async fn analyze(&self, mint_address: &str, age: Duration) -> Result<TokenAnalysis> {
+        let analysis = self.extract(mint_address, age).await?;
+        self.db_manager.store_token_analysis(&analysis).await?;
+        Ok(analysis)
+    }
+}
+
+/// The single definition of every `TokenAnalysis` field.
+///
+/// `trades` must already be limited to the ones before the given age.
+/// `risk_score` is left at 0 for the model to fill in.
+fn compute_features(
+    token: &TokenRecord,
+    launched_at: DateTime<Utc>,
+    age: Duration,
+    trades: &[Trade],
+    metadata_score: f32,
+    creator_first_launch: Option<DateTime<Utc>>,
+    clone_count: u32,
+) -> TokenAnalysis {
+    let age_secs = age.as_secs() as i64;
+
+    // Curve state after the last curve trade. Once a token trades on the AMM
+    // its curve is complete.
+    let migrated = trades.iter().any(|t| t.venue == TradeVenue::Amm.as_str());
+    let curve = trades
+        .iter()
+        .rfind(|t| t.venue == TradeVenue::Curve.as_str())
+        .map(|t| BondingCurve::from_virtual_reserves(t.virtual_sol_reserves as u64, t.virtual_token_reserves as u64));
+    let (bonding_curve_progress, liquidity) = match curve {
+        _ if migrated => (100.0, curve.map(|c| c.liquidity_sol()).unwrap_or(0.0)),
+        Some(curve) => (curve.progress_percent(), curve.liquidity_sol()),
+        None => (0.0, 0.0),
+    };
+    let ilv = liquidity / (age_secs.max(1) as f64 / 60.0);
+
+    let (buy_lamports, sell_lamports) = trades.iter().fold((0i64, 0i64), |(buy, sell), t| {
+        if t.is_buy {
+            (buy + t.sol_amount, sell)
+        } else {
+            (buy, sell + t.sol_amount)
+        }
+    });
+    let sell_pressure = if buy_lamports + sell_lamports > 0 {
+        sell_lamports as f32 / (buy_lamports + sell_lamports) as f32
+    } else {
+        0.0
+    };
+
+    let (holder_count, top10_holder_share) = holder_distribution(trades);
+
+    // Measured from the creator's first launch we saw, up to this one.
+    let creator_age = creator_first_launch
+        .filter(|first| *first <= launched_at)
+        .map(|first| (launched_at - first).num_seconds())
+        .unwrap_or(0);
+
+    TokenAnalysis {
+        mint_address: token.mint_address.clone(),
+        age_secs,
+        bonding_curve_progress: bonding_curve_progress as f32,
+        ilv: ilv as f32,
+        metadata_score,
+        creator_age,
+        sell_pressure,
+        holder_count,
+        top10_holder_share,
+        clone_count,
+        temporal_features: temporal_features(trades, launched_at, age_secs),
+        risk_score: 0.0,
+    }
+}
+
+/// Wallets left holding tokens and the share held by the largest ten, from
+/// net traded amounts. Tokens that moved outside trades aren't seen.
+fn holder_distribution(trades: &[Trade]) -> (u32, f32) {
+    let mut balances: HashMap<&str, i64> = HashMap::new();
+    for trade in trades {
+        let delta = if trade.is_buy { trade.token_amount } else { -trade.token_amount };
+        *balances.entry(&trade.trader).or_default() += delta;
+    }
+    let mut held: Vec<i64> = balances.into_values().filter(|balance| *balance > 0).collect();
+    let total: i64 = held.iter().sum();
+    if total == 0 {
+        return (0, 0.0);
+    }
+    held.sort_unstable_by(|a, b| b.cmp(a));
+    let top10: i64 = held.iter().take(10).sum();
+    (held.len() as u32, top10 as f32 / total as f32)
+}
+
+fn temporal_features(trades: &[Trade], launched_at: DateTime<Utc>, age_secs: i64) -> Vec<f32> {
+    let mut features = vec![0.0f32; TEMPORAL_SLICES * 3];
+    let slice_secs = (age_secs.max(1) as f64) / TEMPORAL_SLICES as f64;
+    for trade in trades {
+        let offset = (trade.traded_at - launched_at).num_milliseconds().max(0) as f64 / 1000.0;
+        let slice = ((offset / slice_secs) as usize).min(TEMPORAL_SLICES - 1);
+        let sol = trade.sol_amount as f32 / LAMPORTS_PER_SOL as f32;
+        features[slice * 3] += 1.0;
+        features[slice * 3 + if trade.is_buy { 1 } else { 2 }] += sol;
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::{
+                copycat::{CopyMatch, TokenCopy},
+                token::{TokenInfo, TokenStatus},
+            },
+            utils::pump_fun::{INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES},
+        },
+        chrono::SubsecRound,
+    };
+
+    fn token(launched_at: DateTime<Utc>) -> TokenRecord {
+        TokenRecord {
+            id: 1,
+            mint_address: "mint".to_string(),
+            creator_address: Some("creator".to_string()),
+            created_at: Some(launched_at),
+            symbol: None,
+            bonding_address: None,
+            status: TokenStatus::Monitoring.as_str().to_string(),
+            status_changed_at: launched_at,
+        }
+    }
+
+    #[test]
+    fn top_ten_share_counts_only_wallets_still_holding() {
+        let at = Utc::now();
+        let mut trades: Vec<Trade> = (0..12)
+            .map(|n| Trade::test("mint").by(&format!("holder{n}")).buy(1, (12 - n) * 1_000).at(at))
+            .collect();
+        // A round trip and a wallet that only sold hold nothing.
+        trades.push(Trade::test("mint").by("flipper").buy(1, 5_000).at(at));
+        trades.push(Trade::test("mint").by("flipper").sell(1, 5_000).at(at));
+        trades.push(Trade::test("mint").by("seller").sell(1, 100).at(at));
+
+        let (holders, top10_share) = holder_distribution(&trades);
+        assert_eq!(holders, 12);
+        // Holders 1..=12 thousand; the largest ten hold 3..=12 thousand.
+        assert_eq!(top10_share, 75_000.0 / 78_000.0);
+        assert_eq!(holder_distribution(&[]), (0, 0.0));
+    }
+
+    #[test]
+    fn temporal_features_bucket_trades_by_slice_of_life() {
+        let launched_at = Utc::now();
+        let at = |millis: i64| launched_at + chrono::Duration::milliseconds(millis);
+        let sol = |amount: f32| (amount * LAMPORTS_PER_SOL as f32) as i64;
+        let trades = [
+            Trade::test("mint").by("a").buy(sol(1.0), 1).at(at(0)),
+            Trade::test("mint").by("b").sell(sol(0.5), 1).at(at(9_999)),
+            // Clock skew before launch counts as the first slice.
+            Trade::test("mint").by("c").buy(sol(0.25), 1).at(at(-5_000)),
+            Trade::test("mint").by("d").buy(sol(2.0), 1).at(at(10_000)),
+            Trade::test("mint").by("e").buy(sol(1.0), 1).at(at(59_000)),
+            // Past the age: clamped into the last slice.
+            Trade::test("mint").by("f").sell(sol(3.0), 1).at(at(120_000)),
+        ];
+
+        // A 60s life in six 10s slices of (trades, buy SOL, sell SOL).
+        let features = temporal_features(&trades, launched_at, 60);
+        assert_eq!(features.len(), TEMPORAL_SLICES * 3);
+        assert_eq!(
+            features,
+            [3.0, 1.25, 0.5, 1.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 1.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn computes_pressure_curve_and_creator_age() {
+        let launched_at = Utc::now();
+        let at = |secs: i64| launched_at + chrono::Duration::seconds(secs);
+        // The last curve trade leaves half the sellable supply bought.
+        let half_sold = Trade::test("mint").by("a").sell(LAMPORTS_PER_SOL as i64, 1_000).at(at(30)).reserves(
+            INITIAL_VIRTUAL_SOL_RESERVES as i64,
+            (INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES / 2) as i64,
+        );
+        let trades = [
+            Trade::test("mint").by("a").buy(LAMPORTS_PER_SOL as i64, 1_000).at(at(10)),
+            Trade::test("mint").by("b").buy(2 * LAMPORTS_PER_SOL as i64, 1_000).at(at(20)),
+            half_sold,
+        ];
+
+        let analysis = compute_features(
+            &token(launched_at),
+            launched_at,
+            Duration::from_secs(60),
+            &trades,
+            0.5,
+            Some(launched_at - chrono::Duration::hours(1)),
+            3,
+        );
+        assert_eq!(analysis.age_secs, 60);
+        assert_eq!(analysis.bonding_curve_progress, 50.0);
+        assert_eq!(analysis.sell_pressure, 0.25);
+        assert_eq!((analysis.holder_count, analysis.top10_holder_share), (1, 1.0));
+        assert_eq!(analysis.metadata_score, 0.5);
+        assert_eq!(analysis.creator_age, 3600);
+        assert_eq!(analysis.clone_count, 3);
+        assert_eq!(analysis.risk_score, 0.0);
+
+        // A creator "first" seen after this launch doesn't make it older, and
+        // a token trading on the AMM has a complete curve.
+        let migrated = Trade::test("mint").by("c").buy(1, 1).at(at(40)).on(TradeVenue::Amm);
+        let analysis = compute_features(
+            &token(launched_at),
+            launched_at,
+            Duration::from_secs(60),
+            &[trades[0].clone(), migrated],
+            0.0,
+            Some(at(1)),
+            0,
+        );
+        assert_eq!(analysis.creator_age, 0);
+        assert_eq!(analysis.bonding_curve_progress, 100.0);
+    }
+
+    #[tokio::test]
+    async fn clone_count_only_counts_copies_detected_by_the_age() {
+        let db_manager = DbManager::new("sqlite::memory:").await.unwrap();
+        let launched_at = Utc::now().trunc_subsecs(0) - chrono::Duration::hours(1);
+        for mint_address in ["original", "first", "second"] {
+            db_manager
+                .store_token_info(&TokenInfo {
+                    mint_address: mint_address.to_string(),
+                    created_at: Some(launched_at),
+                    ..TokenInfo::default()
+                })
+                .await
+                .unwrap();
+        }
+        for (mint_address, secs) in [("first", 30), ("second", 90)] {
+            db_manager
+                .store_token_copy(&TokenCopy {
+                    mint_address: mint_address.to_string(),
+                    original_mint: "original".to_string(),
+                    matched_on: CopyMatch::Name.as_str().to_string(),
+                    detected_at: launched_at + chrono::Duration::seconds(secs),
+                })
+                .await
+                .unwrap();
+        }
+
+        let extractor = FeatureExtractor::new(db_manager, None);
+        let clones = |mint_address: &'static str, secs: u64| {
+            let extractor = &extractor;
+            async move { extractor.extract(mint_address, Duration::from_secs(secs)).await.unwrap().clone_count }
+        };
+        assert_eq!(clones("original", 10).await, 0);
+        assert_eq!(clones("original", 60).await, 1);
+        assert_eq!(clones("first", 60).await, 1);
+        assert_eq!(clones("first", 120).await, 2);
+        // Not yet known to be a copy.
+        assert_eq!(clones("second", 60).await, 0);
+    }
+}
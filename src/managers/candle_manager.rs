@@ -0,0 +1,267 @@
+use {
+    super::db_manager::DbManager,
+    crate::models::{
+        candle::{Candle, CandleInterval},
+        trade::Trade,
+    },
+    chrono::{DateTime, Utc},
+    std::{collections::HashMap, env, sync::{Arc, Mutex}, time::Duration},
+    tokio::time::MissedTickBehavior,
+};
+
+#[derive(Debug, Clone)]
+pub struct CandleAggregatorConfig {
+    /// How often changed candles are written to the database.
+    pub flush_interval: Duration,
+    /// How long after a bucket ends it still accepts late trades. Older
+    /// candles are dropped from memory.
+    pub late_trade_grace: Duration,
+}
+
+impl CandleAggregatorConfig {
+    /// Reads `CANDLE_FLUSH_INTERVAL_MS` (default 1000) and
+    /// `CANDLE_LATE_TRADE_GRACE_SECS` (default 30).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        CandleAggregatorConfig {
+            flush_interval: Duration::from_millis(read("CANDLE_FLUSH_INTERVAL_MS").unwrap_or(1000)),
+            late_trade_grace: Duration::from_secs(read("CANDLE_LATE_TRADE_GRACE_SECS").unwrap_or(30)),
+        }
+    }
+}
+
+type CandleKey = (String, CandleInterval, DateTime<Utc>);
+
+/// A candle that may still receive trades, with the times of its first and
+/// last trades so late trades don't move its open or close.
+struct OpenCandle {
+    candle: Candle,
+    first_trade_at: DateTime<Utc>,
+    last_trade_at: DateTime<Utc>,
+}
+
+impl OpenCandle {
+    fn new(candle: Candle, traded_at: DateTime<Utc>) -> Self {
+        OpenCandle {
+            candle,
+            first_trade_at: traded_at,
+            last_trade_at: traded_at,
+        }
+    }
+
+    fn apply(&mut self, trade: &Trade, price: f64) {
+        let close = self.candle.close;
+        self.candle.apply(price, trade.sol_amount, trade.token_amount);
+        if trade.traded_at < self.last_trade_at {
+            self.candle.close = close;
+        } else {
+            self.last_trade_at = trade.traded_at;
+        }
+        if trade.traded_at < self.first_trade_at {
+            self.candle.open = price;
+            self.first_trade_at = trade.traded_at;
+        }
+    }
+}
+
+#[derive(Default)]
+struct CandleState {
+    /// Candles that may still receive trades, by mint and resolution.
+    open: HashMap<(String, CandleInterval), OpenCandle>,
+    /// Candles changed since the last flush.
+    dirty: HashMap<CandleKey, Candle>,
+}
+
+/// Builds OHLCV candles at every `CandleInterval` from the trade stream.
+///
+/// Candles are kept in memory while their bucket is open and written to
+/// `candles` every `flush_interval`; a stored candle is replaced as its
+/// bucket fills. Curve trades and AMM swaps for the same mint go into the
+/// same candles. Trades may arrive out of order within a bucket; a trade for
+/// a bucket older than the open one is dropped.
+pub struct CandleAggregator {
+    db_manager: Arc<DbManager>,
+    state: Mutex<CandleState>,
+    config: CandleAggregatorConfig,
+}
+
+impl CandleAggregator {
+    pub fn new(db_manager: Arc<DbManager>, config: CandleAggregatorConfig) -> Arc<Self> {
+        Arc::new(CandleAggregator {
+            db_manager,
+            state: Mutex::new(CandleState::default()),
+            config,
+        })
+    }
+
+    /// Folds a trade into the open candle of every resolution.
+    pub fn apply(&self, trade: &Trade) {
+        let Some(price) = trade.price_sol() else {
+            return;
+        };
+        let cutoff = Utc::now() - self.grace();
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        for interval in CandleInterval::ALL {
+            let bucket_start = interval.bucket_start(trade.traded_at);
+            if bucket_start + chrono::Duration::seconds(interval.seconds()) < cutoff {
+                // Its candle may already have been flushed and evicted.
+                continue;
+            }
+            let open = state.open.entry((trade.mint_address.clone(), interval)).or_insert_with(|| {
+                OpenCandle::new(Candle::open_at(&trade.mint_address, interval, bucket_start, price), trade.traded_at)
+            });
+            if open.candle.bucket_start > bucket_start {
+                // A late trade for a bucket that has already been closed.
+                continue;
+            }
+            if open.candle.bucket_start < bucket_start {
+                *open = OpenCandle::new(Candle::open_at(&trade.mint_address, interval, bucket_start, price), trade.traded_at);
+            }
+            open.apply(trade, price);
+            state
+                .dirty
+                .insert((trade.mint_address.clone(), interval, bucket_start), open.candle.clone());
+        }
+    }
+
+    /// Writes every changed candle and drops candles whose bucket is past
+    /// the late-trade grace period.
+    pub async fn flush(&self) {
+        let dirty = {
+            let mut state = self.state.lock().unwrap();
+            let cutoff = Utc::now() - self.grace();
+            state.open.retain(|(_, interval), open| {
+                open.candle.bucket_start + chrono::Duration::seconds(interval.seconds()) >= cutoff
+            });
+            std::mem::take(&mut state.dirty)
+        };
+        if dirty.is_empty() {
+            return;
+        }
+
+        let candles: Vec<Candle> = dirty.values().cloned().collect();
+        if let Err(e) = self.db_manager.store_candle_batch(&candles).await {
+            eprintln!("Failed to write {} candles: {e:?}", candles.len());
+            // Retry on the next flush unless a newer version is already pending.
+            let mut state = self.state.lock().unwrap();
+            for (key, candle) in dirty {
+                state.dirty.entry(key).or_insert(candle);
+            }
+        }
+    }
+
+    /// Flushes every `flush_interval` until the task is dropped.
+    pub async fn run(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(self.config.flush_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            self.flush().await;
+        }
+    }
+
+    fn grace(&self) -> chrono::Duration {
+        chrono::Duration::from_std(self.config.late_trade_grace).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::native_token::LAMPORTS_PER_SOL};
+
+    async fn aggregator(late_trade_grace: Duration) -> Arc<CandleAggregator> {
+        CandleAggregator::new(
+            DbManager::new("sqlite::memory:").await.unwrap(),
+            CandleAggregatorConfig {
+                flush_interval: Duration::from_secs(1),
+                late_trade_grace,
+            },
+        )
+    }
+
+    /// Start of an hour a day ago, well inside a long grace period.
+    fn hour_start() -> DateTime<Utc> {
+        CandleInterval::OneHour.bucket_start(Utc::now() - chrono::Duration::days(1))
+    }
+
+    fn dirty(aggregator: &CandleAggregator, interval: CandleInterval, bucket_start: DateTime<Utc>) -> Candle {
+        let state = aggregator.state.lock().unwrap();
+        state.dirty[&("mint".to_string(), interval, bucket_start)].clone()
+    }
+
+    #[tokio::test]
+    async fn rolls_over_into_the_next_bucket() {
+        let aggregator = aggregator(Duration::from_secs(7 * 86400)).await;
+        let start = hour_start();
+        let second = |n: i64| start + chrono::Duration::seconds(n);
+        aggregator.apply(&Trade::test("mint").priced(1.0).at(second(10)));
+        aggregator.apply(&Trade::test("mint").priced(3.0).at(second(50)));
+        aggregator.apply(&Trade::test("mint").priced(2.0).at(second(70)));
+
+        let first = dirty(&aggregator, CandleInterval::OneMinute, second(0));
+        assert_eq!((first.open, first.high, first.low, first.close), (1.0, 3.0, 1.0, 3.0));
+        assert_eq!(first.trade_count, 2);
+        let next = dirty(&aggregator, CandleInterval::OneMinute, second(60));
+        assert_eq!((next.open, next.close, next.trade_count), (2.0, 2.0, 1));
+        let five = dirty(&aggregator, CandleInterval::FiveMinutes, second(0));
+        assert_eq!((five.open, five.high, five.low, five.close), (1.0, 3.0, 1.0, 2.0));
+        assert_eq!(five.trade_count, 3);
+
+        // The first minute is closed, so a trade for it is dropped there but
+        // still counted in the five-minute candle.
+        aggregator.apply(&Trade::test("mint").priced(0.5).at(second(20)));
+        assert_eq!(dirty(&aggregator, CandleInterval::OneMinute, second(0)), first);
+        let five = dirty(&aggregator, CandleInterval::FiveMinutes, second(0));
+        assert_eq!((five.open, five.low, five.close, five.trade_count), (1.0, 0.5, 2.0, 4));
+    }
+
+    #[tokio::test]
+    async fn late_trades_in_the_bucket_keep_its_open_and_close() {
+        let aggregator = aggregator(Duration::from_secs(7 * 86400)).await;
+        let start = hour_start();
+        let second = |n: i64| start + chrono::Duration::seconds(n);
+        aggregator.apply(&Trade::test("mint").priced(2.0).at(second(30)));
+        aggregator.apply(&Trade::test("mint").priced(3.0).at(second(50)));
+        // Earlier than both: the new open, but not the close.
+        aggregator.apply(&Trade::test("mint").priced(1.0).at(second(10)));
+        // Between them: neither the open nor the close.
+        aggregator.apply(&Trade::test("mint").priced(4.0).at(second(40)));
+
+        let candle = dirty(&aggregator, CandleInterval::OneMinute, second(0));
+        assert_eq!((candle.open, candle.high, candle.low, candle.close), (1.0, 4.0, 1.0, 3.0));
+        assert_eq!(candle.trade_count, 4);
+        assert_eq!(candle.sol_volume, 10 * LAMPORTS_PER_SOL as i64);
+    }
+
+    #[tokio::test]
+    async fn flush_writes_changed_candles_and_evicts_closed_buckets() {
+        let aggregator = aggregator(Duration::from_secs(1)).await;
+        let traded_at = Utc::now();
+        aggregator.apply(&Trade::test("mint").priced(1.0).at(traded_at));
+        aggregator.flush().await;
+
+        let bucket_start = CandleInterval::OneSecond.bucket_start(traded_at);
+        let stored = aggregator
+            .db_manager
+            .get_candles("mint", CandleInterval::OneSecond, bucket_start, bucket_start + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].close, 1.0);
+        assert!(aggregator.state.lock().unwrap().dirty.is_empty());
+
+        // Past the second's grace period; the hour is still open.
+        tokio::time::sleep(Duration::from_millis(2100)).await;
+        aggregator.flush().await;
+        let state = aggregator.state.lock().unwrap();
+        assert!(!state.open.contains_key(&("mint".to_string(), CandleInterval::OneSecond)));
+        assert!(state.open.contains_key(&("mint".to_string(), CandleInterval::OneHour)));
+        drop(state);
+
+        // A trade for the evicted second no longer opens a candle there.
+        aggregator.apply(&Trade::test("mint").priced(2.0).at(traded_at));
+        let state = aggregator.state.lock().unwrap();
+        assert!(!state.open.contains_key(&("mint".to_string(), CandleInterval::OneSecond)));
+    }
+}
@@ -0,0 +1,111 @@
+use {
+    super::db_manager::DbManager,
+    crate::{
+        models::{
+            copycat::{CopyTag, TokenCopy},
+            token::TokenInfo,
+        },
+        utils::copycat::{CopycatConfig, CopycatIndex},
+    },
+    anyhow::Result,
+    chrono::Utc,
+    std::sync::{Arc, Mutex},
+};
+
+/// Spots launches that copy a recent one's name, symbol or image, and
+/// records them in `token_copies`. Name and symbol copies are tagged at
+/// detection; image copies once the metadata collector has hashed the
+/// image.
+///
+/// The clone count of an original doubles as a momentum signal: a launch
+/// being copied many times is drawing attention.
+pub struct CopycatDetector {
+    db_manager: Arc<DbManager>,
+    index: Mutex<CopycatIndex>,
+    config: CopycatConfig,
+}
+
+impl CopycatDetector {
+    pub fn new(db_manager: Arc<DbManager>, config: CopycatConfig) -> Self {
+        CopycatDetector {
+            db_manager,
+            index: Mutex::new(CopycatIndex::new(config.clone())),
+            config,
+        }
+    }
+
+    /// Reloads the launches of the last window from the database, e.g.
+    /// after a restart.
+    pub async fn resume(&self) -> Result<usize> {
+        let launches = self
+            .db_manager
+            .get_launch_fingerprints(Utc::now() - self.config.window)
+            .await?;
+        let mut index = self.index.lock().unwrap();
+        for launch in &launches {
+            index.restore(launch);
+        }
+        Ok(launches.len())
+    }
+
+    /// Adds a new launch to the index and tags it with the launch it copies
+    /// by name or symbol, if any.
+    pub fn tag(&self, token_info: &mut TokenInfo) {
+        let launched_at = token_info.created_at.unwrap_or_else(Utc::now);
+        token_info.copy = self.index.lock().unwrap().add_launch(
+            &token_info.mint_address,
+            token_info.name.as_deref(),
+            token_info.symbol.as_deref(),
+            launched_at,
+        );
+        if let Some(copy) = &token_info.copy {
+            log_copy(&token_info.mint_address, copy);
+        }
+    }
+
+    /// Adds a launch's image hash to the index and records every launch it
+    /// reveals as an image copy.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token whose image was hashed
+    /// * `image_hash` - See `copycat::image_hash`
+    pub async fn record_image(&self, mint_address: &str, image_hash: i64) -> Result<()> {
+        let copies = self.index.lock().unwrap().add_image(mint_address, image_hash);
+        for (copy_mint, copy) in copies {
+            log_copy(&copy_mint, &copy);
+            self.store(&copy_mint, &copy).await?;
+        }
+        Ok(())
+    }
+
+    /// Queues a copy tag to be stored once the token's row has been written.
+    pub async fn store(&self, mint_address: &str, copy: &CopyTag) -> Result<()> {
+        self.db_manager
+            .queue_token_copy(TokenCopy {
+                mint_address: mint_address.to_string(),
+                original_mint: copy.original_mint.clone(),
+                matched_on: copy.matched_on.as_str().to_string(),
+                detected_at: Utc::now(),
+            })
+            .await
+    }
+
+    /// The original a recent launch copies, if it is a copy.
+    pub fn copy_of(&self, mint_address: &str) -> Option<String> {
+        self.index.lock().unwrap().copy_of(mint_address).map(String::from)
+    }
+
+    /// Whether copies should be filtered out instead of evaluated.
+    pub fn filters_copies(&self) -> bool {
+        self.config.filter_copies
+    }
+}
+
+fn log_copy(mint_address: &str, copy: &CopyTag) {
+    println!(
+        "Copycat: {mint_address} copies {} by {} ({} clones)",
+        copy.original_mint,
+        copy.matched_on.as_str(),
+        copy.clone_count
+    );
+}
@@ -0,0 +1,100 @@
+use {
+    super::{db_manager::DbManager, funding_manager::FundingGraph},
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    std::{env, sync::{Arc, Mutex}, time::Duration},
+    tokio::time::MissedTickBehavior,
+};
+
+/// How far before the previous run each sync looks back. Trades are stamped
+/// with their block time and reach the database a little later, so a sell can
+/// land with a timestamp before the run that should have seen it.
+const SYNC_OVERLAP: chrono::Duration = chrono::Duration::minutes(5);
+
+#[derive(Debug, Clone)]
+pub struct CreatorConfig {
+    /// How often creator reputations are brought up to date.
+    pub interval: Duration,
+    /// Creators whose funding source is looked up per run.
+    pub funding_batch_size: i64,
+}
+
+impl CreatorConfig {
+    /// Reads `CREATOR_SYNC_INTERVAL_SECS` (default 60) and
+    /// `CREATOR_FUNDING_BATCH` (default 20).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        CreatorConfig {
+            interval: Duration::from_secs(read("CREATOR_SYNC_INTERVAL_SECS").unwrap_or(60).max(1)),
+            funding_batch_size: read("CREATOR_FUNDING_BATCH").unwrap_or(20) as i64,
+        }
+    }
+}
+
+/// Maintains the `creators` table: launch count, outcomes, token lifetime
+/// and how soon the creator sells, synced from stored tokens and trades, plus
+/// the wallet that first funded each creator.
+///
+/// Launches are recorded in `creator_launches`, which retention leaves alone,
+/// so a creator's record covers tokens that have since been archived.
+pub struct CreatorTracker {
+    db_manager: Arc<DbManager>,
+    funding: Arc<FundingGraph>,
+    config: CreatorConfig,
+    /// When the last successful sync started; the epoch until then, so the
+    /// first run covers every stored token.
+    last_sync: Mutex<DateTime<Utc>>,
+}
+
+impl CreatorTracker {
+    pub fn new(db_manager: Arc<DbManager>, funding: Arc<FundingGraph>, config: CreatorConfig) -> Self {
+        CreatorTracker {
+            db_manager,
+            funding,
+            config,
+            last_sync: Mutex::new(DateTime::UNIX_EPOCH),
+        }
+    }
+
+    /// Runs every `interval` until the task is dropped. The first run
+    /// happens immediately.
+    pub async fn run(self) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.run_once().await {
+                eprintln!("Creator sync failed: {:?}", e);
+            }
+        }
+    }
+
+    /// Syncs every creator with activity since the last run, then looks up
+    /// the funding source of up to `funding_batch_size` new creators.
+    pub async fn run_once(&self) -> Result<()> {
+        let started = Utc::now();
+        let since = *self.last_sync.lock().unwrap() - SYNC_OVERLAP;
+        let updated = self.db_manager.sync_creators(since).await?;
+        *self.last_sync.lock().unwrap() = started;
+        if updated > 0 {
+            println!("Updated {updated} creator records");
+        }
+
+        for creator in self
+            .db_manager
+            .get_creators_without_funding(self.config.funding_batch_size)
+            .await?
+        {
+            match self.funding.funding(&creator).await {
+                Ok(funding) => {
+                    self.db_manager
+                        .set_creator_funding(&creator, funding.funder.as_deref(), funding.funded_at)
+                        .await?;
+                }
+                // Left unchecked so the next run retries it.
+                Err(e) => eprintln!("Failed to find funding source of {creator}: {:?}", e),
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,161 +1,145 @@
 use {
-    super::swqos_manager::SwqosRpcClient, crate::models::token::{TokenInfo, TokenMetrics}, anyhow::{Ok, Result}, futures::TryFutureExt, solana_sdk::pubkey::Pubkey, sqlx::PgPool, std::{env, result::Result::Err, str::FromStr, sync::Arc}
+    super::{
+        store::{
+            postgres::PgStore,
+            sqlite::SqliteStore,
+            write_buffer::{WriteBuffer, WriteBufferConfig},
+            TokenStore,
+        },
+    },
+    crate::models::{
+        copycat::TokenCopy,
+        metadata::OffchainMetadata,
+        token::{TokenInfo, TokenMetrics, TokenStatus},
+        trade::Trade,
+    },
+    anyhow::{Ok, Result},
+    std::{collections::HashSet, ops::Deref, sync::Arc},
 };
 
+/// Entry point for persistence. Holds the `TokenStore` selected by the
+/// `DATABASE_URL` scheme and derefs to it, so store operations are called
+/// directly on the manager.
+///
+/// High-volume writes (launches, trades and metrics samples) should go
+/// through the `queue_*` methods, which batch them behind a `WriteBuffer`, as
+/// should writes that need a token row that may not have been written yet.
+/// `get_trades` includes trades still waiting in the buffer.
 pub struct DbManager {
-    pub db_pool: Arc<PgPool>
+    store: Arc<dyn TokenStore>,
+    write_buffer: WriteBuffer,
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, sqlx::FromRow)]
 pub struct MintAddress {
     pub mint_address: String,
 }
 
 
 impl DbManager {
+    /// Connects to the database named by `db_uri` and applies any pending
+    /// migrations. `postgres://` and `postgresql://` URIs use Postgres,
+    /// `sqlite:` URIs the embedded SQLite backend.
+    pub async fn new(db_uri: &str) -> Result<Arc<DbManager>> {
+        let store: Arc<dyn TokenStore> = if db_uri.starts_with("postgres://") || db_uri.starts_with("postgresql://") {
+            Arc::new(PgStore::connect(db_uri).await?)
+        } else if db_uri.starts_with("sqlite:") {
+            Arc::new(SqliteStore::connect(db_uri).await?)
+        } else {
+            return Err(anyhow::anyhow!("Unsupported DATABASE_URL scheme: {db_uri}"));
+        };
+        let write_buffer = WriteBuffer::spawn(store.clone(), WriteBufferConfig::from_env());
+        Ok(Arc::new(DbManager { store, write_buffer }))
+    }
 
-    /// Deletes a token from the tokens table based on its mint address.
-    /// Instead of returning an error, any issues are logged.
-    /// 
-    /// 
-    /// 
-    // pub async fn delete_token_by_mint(&self, mint_address: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>{
-    //     sqlx::query!(
-    //         r#"
-    //         DELETE FROM tokens
-    //         WHERE mint_address = $1
-    //         "#,
-    //         mint_address
-    //     )
-    //     .execute(&*self.db_pool)
-    //     .await?;
-    //     Ok(())
-    // }
-
-    pub async fn process_all_tokens(&self) -> anyhow::Result<()> {
-        let tokens = sqlx::query_as!(
-            MintAddress,
-            r#"SELECT mint_address FROM tokens"#
-        )
-        .fetch_all(&*self.db_pool)
-        .await?;
-        if tokens.len() < 1 {
-            return  Ok(());
-        }
-        let rpc_endpoint = env::var("RPC_ENDPOINT").expect("Missing RPC endpoint");
-        let rpc_manager = SwqosRpcClient::new(&rpc_endpoint);
-        for mint in tokens {
-            let mint_address = mint.mint_address.clone();
-            // let  rug_status = check_solana_rug(&mint_address).await.expect("msg");
-            let pubkey = Pubkey::from_str(&mint_address).unwrap();
-            match rpc_manager.validate_token(&pubkey).await {
-                std::result::Result::Ok(is_valid) => {
-                    if !is_valid {
-                        println!("🚨Deleting token : {mint_address:?} from table");
-                        sqlx::query!(
-                            r#"
-                            DELETE FROM tokens
-                            WHERE mint_address = $1
-                            "#,
-                            mint_address
-                        )
-                        .execute(&*self.db_pool).await?;
-                    }
-                },
-                Err(_) => {}
-            };
-        }
-        Ok(())
+    /// Queues a launch for the next batched write.
+    pub async fn queue_token_info(&self, token_info: TokenInfo) -> Result<()> {
+        self.write_buffer.queue_token_info(token_info).await
     }
-    
-
-    
-    pub async fn store_token_info(&self, token_info: &TokenInfo) -> Result<()> {
-        sqlx::query!(
-            r#"
-            INSERT INTO tokens (
-                mint_address,
-                creator_address,
-                created_at,
-                symbol,
-                bonding_address
-            )
-            VALUES (
-                $1, 
-                $2, 
-                $3, 
-                $4, 
-                $5
-            )
-            ON CONFLICT (mint_address) DO UPDATE SET
-                creator_address = EXCLUDED.creator_address,
-                symbol = EXCLUDED.symbol,
-                bonding_address = EXCLUDED.bonding_address
-            "#,
-            token_info.mint_address,
-            token_info.creator_address,
-            token_info.created_at,
-            token_info.symbol,
-            token_info.bonding_address,
-        )
-        .execute(&*self.db_pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to store token info: {:?}", e))?;
-        Ok(())
-        
+
+    /// Queues a trade for the next batched write.
+    pub async fn queue_trade(&self, trade: Trade) -> Result<()> {
+        self.write_buffer.queue_trade(trade).await
     }
 
+    /// Queues a metrics sample for the next batched write.
+    pub async fn queue_token_metrics(&self, token_metrics: TokenMetrics) -> Result<()> {
+        self.write_buffer.queue_token_metrics(token_metrics).await
+    }
 
-    pub async fn store_token_metrics(&self, token_metric: &TokenMetrics) -> Result<()> {
-        let token = sqlx::query!(
-            r#"
-            SELECT id FROM tokens
-            WHERE mint_address = $1
-            "#,
-            token_metric.mint_address
-        )
-        .fetch_one(&*self.db_pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to fetch token: {:?}", e))?;
-
-        sqlx::query!(
-            r#"
-            INSERT INTO token_metrics (
-                token_id,
-                bonding_percent,
-                ilv,
-                social_replies,
-                safety_score,
-                liquidity,
-                holders,
-                volume,
-                buy_volume,
-                sell_volume
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            "#,
-            token.id,
-            token_metric.bonding_percent,
-            token_metric.ilv,
-            token_metric.social_replies,
-            token_metric.safety_score,
-            token_metric.liquidity,
-            token_metric.holders,
-            token_metric.volume,
-            token_metric.buy_volume,
-            token_metric.sell_volume
-        )
-        .execute(&*self.db_pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to store token metrics: {:?}", e))?;
-        Ok(())
+    /// Queues a token's metadata, to be written after its token row.
+    pub async fn queue_token_metadata(&self, metadata: OffchainMetadata) -> Result<()> {
+        self.write_buffer.queue_token_metadata(metadata).await
     }
-    
-    pub async fn new(db_uri: &str) -> Result<Arc<DbManager>> {
-        let db_pool = Arc::new(PgPool::connect(db_uri).await?);
-        Ok(Arc::new(DbManager { db_pool }))
+
+    /// Queues a copy tag, to be written after its token row.
+    pub async fn queue_token_copy(&self, copy: TokenCopy) -> Result<()> {
+        self.write_buffer.queue_token_copy(copy).await
+    }
+
+    /// Queues a status change, to be applied after the token row is written.
+    pub async fn queue_status_transition(&self, mint_address: &str, to: TokenStatus, reason: &str) -> Result<()> {
+        self.write_buffer.queue_status_transition(mint_address, to, reason).await
+    }
+
+    /// Every trade on a token, oldest first, including trades still waiting
+    /// in the write buffer. Shadows `TokenStore::get_trades`.
+    pub async fn get_trades(&self, mint_address: &str) -> Result<Vec<Trade>> {
+        // Taken before the read so a trade written in between is still seen.
+        let pending = self.write_buffer.pending_trades(mint_address);
+        let mut trades = self.store.get_trades(mint_address).await?;
+        let stored: HashSet<(String, i32)> = trades
+            .iter()
+            .map(|trade| (trade.signature.clone(), trade.event_index))
+            .collect();
+        trades.extend(
+            pending
+                .into_iter()
+                .filter(|trade| !stored.contains(&(trade.signature.clone(), trade.event_index))),
+        );
+        trades.sort_by_key(|trade| trade.traded_at);
+        Ok(trades)
+    }
+
+    /// Waits until every queued write has been written.
+    pub async fn flush(&self) -> Result<()> {
+        self.write_buffer.flush().await
     }
 }
 
+impl Deref for DbManager {
+    type Target = dyn TokenStore;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.store
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::models::trade::TradeVolume};
+
+    #[tokio::test]
+    async fn reads_include_buffered_trades_once() {
+        let db_manager = DbManager::new("sqlite::memory:").await.unwrap();
+        db_manager
+            .queue_token_info(TokenInfo {
+                mint_address: "mint".to_string(),
+                ..TokenInfo::default()
+            })
+            .await
+            .unwrap();
+        db_manager.queue_trade(Trade::test("mint").signature("written").buy(3, 1_000_000)).await.unwrap();
+        db_manager.flush().await.unwrap();
+        db_manager.queue_trade(Trade::test("mint").signature("pending").sell(2, 1_000_000)).await.unwrap();
+        // Already written, so it must not be counted twice.
+        db_manager.queue_trade(Trade::test("mint").signature("written").buy(3, 1_000_000)).await.unwrap();
+
+        let trades = db_manager.get_trades("mint").await.unwrap();
+        let signatures: Vec<&str> = trades.iter().map(|trade| trade.signature.as_str()).collect();
+        assert_eq!(signatures, ["written", "pending"]);
+        let volume = TradeVolume::of(&trades);
+        assert_eq!((volume.buy_volume, volume.sell_volume), (3, 2));
+    }
+}
@@ -0,0 +1,325 @@
+use {
+    super::swqos_manager::{Landing, SendStatus},
+    crate::{
+        models::{
+            signal::{DevSold, TokenSignal},
+            trade::Trade,
+        },
+        scripts::swap::PumpSwapper,
+    },
+    chrono::{DateTime, Utc},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::{HashMap, HashSet},
+        env,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    },
+    tokio::sync::broadcast,
+};
+
+/// Signals buffered per subscriber before the slowest one starts missing them.
+const SIGNAL_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct DevSellConfig {
+    /// Share of their buys, 0-100, the creator's wallets must have sold for
+    /// the token to count as dumped.
+    pub dump_percent: f64,
+    /// How long after launch a token's dev wallets are watched.
+    pub track_window: chrono::Duration,
+}
+
+impl DevSellConfig {
+    /// Reads `DEV_SELL_DUMP_PERCENT` (default 50) and `DEV_TRACK_HOURS`
+    /// (default 24).
+    pub fn from_env() -> Self {
+        DevSellConfig {
+            dump_percent: env::var("DEV_SELL_DUMP_PERCENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(50.0),
+            track_window: chrono::Duration::hours(
+                env::var("DEV_TRACK_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(24).max(1),
+            ),
+        }
+    }
+}
+
+/// What the creator's wallets have done on one tracked token.
+struct DevPosition {
+    creator_address: String,
+    launched_at: DateTime<Utc>,
+    /// Net raw token balance of each dev wallet, from its trades.
+    holdings: HashMap<String, i64>,
+    bought: i64,
+    sold: i64,
+    dumped: bool,
+}
+
+/// Recognizes sells by a token's creator, or wallets linked to it, as trades
+/// come off the stream.
+///
+/// Launches must be registered with `track_launch` before their trades are
+/// observed; the gRPC handler does so for the `create` transaction before
+/// forwarding its trades, so the creator's initial buy is always counted.
+/// Everything is kept in memory, so detection needs no database round trip.
+pub struct DevSellDetector {
+    positions: Mutex<HashMap<String, DevPosition>>,
+    /// Wallets known to act for a creator, by creator.
+    linked_wallets: Mutex<HashMap<String, HashSet<String>>>,
+    signals: broadcast::Sender<TokenSignal>,
+    config: DevSellConfig,
+}
+
+impl DevSellDetector {
+    pub fn new(config: DevSellConfig) -> Self {
+        DevSellDetector {
+            positions: Mutex::new(HashMap::new()),
+            linked_wallets: Mutex::new(HashMap::new()),
+            signals: broadcast::channel(SIGNAL_CHANNEL_CAPACITY).0,
+            config,
+        }
+    }
+
+    /// A receiver for every signal published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<TokenSignal> {
+        self.signals.subscribe()
+    }
+
+    /// Starts watching the creator's wallets on `mint_address`. Tokens past
+    /// `track_window` are dropped at the same time.
+    pub fn track_launch(&self, mint_address: &str, creator_address: &str, launched_at: DateTime<Utc>) {
+        let mut positions = self.positions.lock().unwrap();
+        let cutoff = Utc::now() - self.config.track_window;
+        positions.retain(|_, position| position.launched_at >= cutoff);
+        positions.entry(mint_address.to_string()).or_insert_with(|| DevPosition {
+            creator_address: creator_address.to_string(),
+            launched_at,
+            holdings: HashMap::new(),
+            bought: 0,
+            sold: 0,
+            dumped: false,
+        });
+        // Links for creators with nothing left to watch are no longer needed.
+        let creators: HashSet<&str> = positions.values().map(|p| p.creator_address.as_str()).collect();
+        self.linked_wallets
+            .lock()
+            .unwrap()
+            .retain(|creator, _| creators.contains(creator.as_str()));
+    }
+
+    /// Treats `wallet` as the creator's own on every token it launched.
+    pub fn link_wallet(&self, creator_address: &str, wallet: &str) {
+        self.linked_wallets
+            .lock()
+            .unwrap()
+            .entry(creator_address.to_string())
+            .or_default()
+            .insert(wallet.to_string());
+    }
+
+    /// Counts `bought` raw tokens as held by `wallet` on `mint_address`, for a
+    /// wallet linked after its buys came off the stream. Does nothing if the
+    /// token isn't tracked or the wallet's trades on it already are.
+    pub fn credit_buys(&self, mint_address: &str, wallet: &str, bought: i64) {
+        let mut positions = self.positions.lock().unwrap();
+        let Some(position) = positions.get_mut(mint_address) else {
+            return;
+        };
+        if bought <= 0 || position.holdings.contains_key(wallet) {
+            return;
+        }
+        position.holdings.insert(wallet.to_string(), bought);
+        position.bought += bought;
+    }
+
+    /// Updates the dev wallets' positions with `trade`. Returns the signal,
+    /// after publishing it, if the trade was a dev sell.
+    pub fn observe(&self, trade: &Trade) -> Option<DevSold> {
+        let mut positions = self.positions.lock().unwrap();
+        let position = positions.get_mut(&trade.mint_address)?;
+        let is_dev = trade.trader == position.creator_address
+            || self
+                .linked_wallets
+                .lock()
+                .unwrap()
+                .get(&position.creator_address)
+                .is_some_and(|wallets| wallets.contains(&trade.trader));
+        if !is_dev {
+            return None;
+        }
+
+        let holding = position.holdings.entry(trade.trader.clone()).or_default();
+        if trade.is_buy {
+            *holding += trade.token_amount;
+            position.bought += trade.token_amount;
+            return None;
+        }
+        // Tokens that reached the wallet outside a trade we saw count as
+        // fully sold.
+        let sold_percent = share(trade.token_amount, *holding);
+        *holding = (*holding - trade.token_amount).max(0);
+        position.sold += trade.token_amount;
+        let total_sold_percent = share(position.sold, position.bought);
+        let dumped = !position.dumped && total_sold_percent >= self.config.dump_percent;
+        position.dumped |= dumped;
+
+        let sold = DevSold {
+            mint_address: trade.mint_address.clone(),
+            creator_address: position.creator_address.clone(),
+            wallet: trade.trader.clone(),
+            signature: trade.signature.clone(),
+            slot: trade.slot,
+            token_amount: trade.token_amount,
+            sold_percent,
+            total_sold_percent,
+            dumped,
+            traded_at: trade.traded_at,
+        };
+        // Nobody listening isn't an error.
+        let _ = self.signals.send(TokenSignal::DevSold(sold.clone()));
+        Some(sold)
+    }
+
+    /// Whether the creator's wallets have sold past `dump_percent` of a
+    /// tracked token.
+    pub fn has_dumped(&self, mint_address: &str) -> bool {
+        self.positions
+            .lock()
+            .unwrap()
+            .get(mint_address)
+            .is_some_and(|position| position.dumped)
+    }
+}
+
+/// `part` as a percentage of `whole`, capped at 100; 100 if `whole` is empty.
+fn share(part: i64, whole: i64) -> f64 {
+    if whole <= 0 {
+        return 100.0;
+    }
+    (part as f64 / whole as f64 * 100.0).min(100.0)
+}
+
+/// Logs every signal until the channel closes.
+pub async fn log_signals(mut signals: broadcast::Receiver<TokenSignal>) {
+    loop {
+        match signals.recv().await {
+            Ok(TokenSignal::DevSold(sold)) => {
+                let wallet = if sold.wallet == sold.creator_address {
+                    format!("Creator {}", sold.wallet)
+                } else {
+                    format!("Wallet {} linked to creator {}", sold.wallet, sold.creator_address)
+                };
+                println!(
+                    "{wallet} sold {} tokens, {:.0}% of its holdings, on {} ({:.0}% of buys) at {} in slot {}: {}",
+                    sold.token_amount,
+                    sold.sold_percent,
+                    sold.mint_address,
+                    sold.total_sold_percent,
+                    sold.traded_at.to_rfc3339(),
+                    sold.slot,
+                    sold.signature
+                );
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => eprintln!("Signal log missed {missed} signals"),
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Sells our whole position in a token as soon as its creator's wallets
+/// dump it, until the channel closes. Tokens we don't hold are skipped.
+pub async fn sell_dumped(swapper: Arc<PumpSwapper>, mut signals: broadcast::Receiver<TokenSignal>) {
+    loop {
+        match signals.recv().await {
+            Ok(TokenSignal::DevSold(sold)) if sold.dumped => {
+                tokio::spawn(sell(swapper.clone(), sold.mint_address));
+            }
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(missed)) => eprintln!("Dump seller missed {missed} signals"),
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn sell(swapper: Arc<PumpSwapper>, mint_address: String) {
+    let sold = match Pubkey::from_str(&mint_address) {
+        Ok(mint) => swapper.sell_all(&mint).await,
+        Err(e) => Err(e.into()),
+    };
+    match sold {
+        Ok(Some(report)) => match report.status {
+            SendStatus::Landed(Landing { slot, error: None }) => {
+                println!("Sold {mint_address} after a dev dump in slot {slot}: {}", report.signature)
+            }
+            SendStatus::Landed(Landing { slot, error: Some(error) }) => {
+                eprintln!("Sell of {mint_address} failed in slot {slot}: {error} ({})", report.signature)
+            }
+            SendStatus::Expired => eprintln!(
+                "Sell of {mint_address} expired after {} broadcasts: {}",
+                report.broadcasts, report.signature
+            ),
+        },
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to sell {mint_address}: {e:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> DevSellDetector {
+        let detector = DevSellDetector::new(DevSellConfig {
+            dump_percent: 50.0,
+            track_window: chrono::Duration::hours(1),
+        });
+        detector.track_launch("mint", "creator", Utc::now());
+        detector
+    }
+
+    #[test]
+    fn creator_sells_are_signalled_once_dumped() {
+        let detector = detector();
+        let mut signals = detector.subscribe();
+        assert!(detector.observe(&Trade::test("mint").by("creator").buy(1_000, 1_000)).is_none());
+        assert!(detector.observe(&Trade::test("mint").by("someone").sell(1_000, 500)).is_none());
+
+        let first = detector.observe(&Trade::test("mint").by("creator").sell(1_000, 400)).unwrap();
+        assert_eq!((first.sold_percent, first.total_sold_percent, first.dumped), (40.0, 40.0, false));
+        assert!(!detector.has_dumped("mint"));
+
+        let second = detector.observe(&Trade::test("mint").by("creator").sell(1_000, 300)).unwrap();
+        assert_eq!(second.sold_percent, 50.0);
+        assert_eq!((second.total_sold_percent, second.dumped), (70.0, true));
+        assert!(detector.has_dumped("mint"));
+        // Only the sell that crossed the threshold is marked dumped.
+        assert!(!detector.observe(&Trade::test("mint").by("creator").sell(1_000, 300)).unwrap().dumped);
+
+        let TokenSignal::DevSold(published) = signals.try_recv().unwrap();
+        assert_eq!(published.signature, first.signature);
+    }
+
+    #[test]
+    fn linked_wallets_count_as_the_creator() {
+        let detector = detector();
+        detector.link_wallet("creator", "sniper");
+        detector.credit_buys("mint", "sniper", 1_000);
+        // Already credited, so a second credit is ignored.
+        detector.credit_buys("mint", "sniper", 1_000);
+
+        let sold = detector.observe(&Trade::test("mint").by("sniper").sell(1_000, 1_000)).unwrap();
+        assert_eq!((sold.wallet.as_str(), sold.creator_address.as_str()), ("sniper", "creator"));
+        assert_eq!((sold.sold_percent, sold.total_sold_percent), (100.0, 100.0));
+        assert!(sold.dumped);
+    }
+
+    #[test]
+    fn untracked_tokens_are_ignored() {
+        let detector = detector();
+        let other = Trade::test("other").by("creator").sell(1_000, 100);
+        assert!(detector.observe(&other).is_none());
+        assert!(!detector.has_dumped("other"));
+    }
+}
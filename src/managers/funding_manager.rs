@@ -0,0 +1,318 @@
+use {
+    super::db_manager::DbManager,
+    crate::{
+        models::wallet::{CommonFunder, RelatedWallet, Relation, WalletFunding},
+        utils::funding::first_funder,
+    },
+    anyhow::Result,
+    chrono::Utc,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        env,
+        sync::Arc,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct FundingConfig {
+    /// Funders followed back from a wallet, and the widest a relation found
+    /// through the graph may be.
+    pub max_hops: usize,
+    /// Pages of signatures walked back per wallet looking for its first
+    /// transaction. Wallets with a longer history are recorded as unfunded.
+    pub max_pages: usize,
+    /// Most wallets `related_wallets` returns.
+    pub max_related: usize,
+    /// Wallets that fund too many others to say anything about them, such as
+    /// exchange hot wallets. Tracing stops at them and they never relate two
+    /// wallets.
+    pub ignored_wallets: HashSet<String>,
+}
+
+impl FundingConfig {
+    /// Reads `FUNDING_MAX_HOPS` (default 3), `FUNDING_MAX_PAGES` (default 3),
+    /// `FUNDING_MAX_RELATED` (default 200) and `FUNDING_IGNORED_WALLETS`
+    /// (comma-separated, default none).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<usize>().ok());
+        FundingConfig {
+            max_hops: read("FUNDING_MAX_HOPS").unwrap_or(3).max(1),
+            max_pages: read("FUNDING_MAX_PAGES").unwrap_or(3).max(1),
+            max_related: read("FUNDING_MAX_RELATED").unwrap_or(200),
+            ignored_wallets: env::var("FUNDING_IGNORED_WALLETS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|wallet| !wallet.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+/// Who funded whom: each wallet's first funder, traced over RPC on demand
+/// and cached in `wallet_funding` so every wallet is looked up once. The
+/// rug check and the launch pipeline use it to tie creators, bundlers and
+/// snipers together.
+///
+/// Only a wallet's first funding is followed, so the graph has one edge into
+/// each traced wallet. Wallets the graph has never traced are invisible from
+/// their funder's side: `funded_wallets` only knows about wallets something
+/// else already looked up.
+pub struct FundingGraph {
+    db_manager: Arc<DbManager>,
+    rpc: Arc<RpcClient>,
+    config: FundingConfig,
+}
+
+impl FundingGraph {
+    pub fn new(db_manager: Arc<DbManager>, rpc: Arc<RpcClient>, config: FundingConfig) -> Self {
+        FundingGraph {
+            db_manager,
+            rpc,
+            config,
+        }
+    }
+
+    /// Where `wallet` got its first SOL, from the cache or, the first time
+    /// it's asked about, from its transaction history.
+    pub async fn funding(&self, wallet: &str) -> Result<WalletFunding> {
+        if let Some(funding) = self.db_manager.get_wallet_funding(wallet).await? {
+            return Ok(funding);
+        }
+        let found = first_funder(&self.rpc, wallet, self.config.max_pages).await?;
+        let (funder, funded_at) = found.map(|f| (f.funder, f.funded_at)).unzip();
+        let funding = WalletFunding {
+            wallet: wallet.to_string(),
+            funder,
+            funded_at,
+            checked_at: Utc::now(),
+        };
+        self.db_manager.store_wallet_funding(&funding).await?;
+        Ok(funding)
+    }
+
+    /// The funder of `wallet`, unless it is an ignored wallet.
+    pub async fn funder(&self, wallet: &str) -> Result<Option<String>> {
+        let funder = self.funding(wallet).await?.funder;
+        Ok(funder.filter(|funder| !self.config.ignored_wallets.contains(funder)))
+    }
+
+    /// `wallet`'s funder, that wallet's funder and so on, closest first, up
+    /// to `max_hops` of them. Stops early at a wallet with no known funder,
+    /// an ignored wallet or a cycle.
+    pub async fn funding_chain(&self, wallet: &str) -> Result<Vec<String>> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut current = wallet.to_string();
+        while chain.len() < self.config.max_hops {
+            let Some(funder) = self.funder(&current).await? else {
+                break;
+            };
+            if funder == wallet || chain.contains(&funder) {
+                break;
+            }
+            chain.push(funder.clone());
+            current = funder;
+        }
+        Ok(chain)
+    }
+
+    /// Traced wallets funded by `wallet`, directly or through up to
+    /// `max_hops` wallets in between, closest first. Reads the cache only.
+    pub async fn funded_wallets(&self, wallet: &str) -> Result<Vec<RelatedWallet>> {
+        let mut seen = HashSet::from([wallet.to_string()]);
+        let mut funded = Vec::new();
+        let mut queue = VecDeque::from([(wallet.to_string(), 0)]);
+        while let Some((current, hops)) = queue.pop_front() {
+            if hops == self.config.max_hops || funded.len() >= self.config.max_related {
+                continue;
+            }
+            for child in self.db_manager.get_funded_wallets(&current).await? {
+                if seen.insert(child.clone()) {
+                    funded.push(RelatedWallet {
+                        address: child.clone(),
+                        relation: Relation::Funded,
+                        hops: hops + 1,
+                    });
+                    queue.push_back((child, hops + 1));
+                }
+            }
+        }
+        funded.truncate(self.config.max_related);
+        Ok(funded)
+    }
+
+    /// Every wallet within `max_hops` funding edges of `wallet`: its funders
+    /// (traced if need be), the wallets it funded, and wallets funded by its
+    /// funders. Ignored wallets neither appear nor connect anything. At most
+    /// `max_related` wallets, closest first.
+    ///
+    /// # Arguments
+    /// * `wallet` - The address to find relatives of
+    pub async fn related_wallets(&self, wallet: &str) -> Result<Vec<RelatedWallet>> {
+        let chain = self.funding_chain(wallet).await?;
+        let mut related: HashMap<String, RelatedWallet> = HashMap::new();
+        for (i, funder) in chain.iter().enumerate() {
+            related.insert(
+                funder.clone(),
+                RelatedWallet {
+                    address: funder.clone(),
+                    relation: Relation::Funder,
+                    hops: i + 1,
+                },
+            );
+        }
+        for child in self.funded_wallets(wallet).await? {
+            related.entry(child.address.clone()).or_insert(child);
+        }
+        // Siblings: what each funder funded, within what's left of the hops.
+        for (i, funder) in chain.iter().enumerate() {
+            let mut queue = VecDeque::from([(funder.clone(), i + 1)]);
+            while let Some((current, hops)) = queue.pop_front() {
+                if hops == self.config.max_hops {
+                    continue;
+                }
+                for child in self.db_manager.get_funded_wallets(&current).await? {
+                    if child == wallet || related.contains_key(&child) {
+                        continue;
+                    }
+                    related.insert(
+                        child.clone(),
+                        RelatedWallet {
+                            address: child.clone(),
+                            relation: Relation::Sibling,
+                            hops: hops + 1,
+                        },
+                    );
+                    queue.push_back((child, hops + 1));
+                }
+            }
+        }
+        let mut related: Vec<RelatedWallet> = related.into_values().collect();
+        related.sort_by(|a, b| a.hops.cmp(&b.hops).then_with(|| a.address.cmp(&b.address)));
+        related.truncate(self.config.max_related);
+        Ok(related)
+    }
+
+    /// The closest wallet that `a` and `b` both trace back to within
+    /// `max_hops`, counting each wallet as its own funder at 0 hops. `None`
+    /// if their chains never meet.
+    pub async fn common_funder(&self, a: &str, b: &str) -> Result<Option<CommonFunder>> {
+        let chain_a: Vec<String> = std::iter::once(a.to_string()).chain(self.funding_chain(a).await?).collect();
+        let chain_b: Vec<String> = std::iter::once(b.to_string()).chain(self.funding_chain(b).await?).collect();
+        let common = chain_a
+            .iter()
+            .enumerate()
+            .filter_map(|(hops_a, funder)| {
+                let hops_b = chain_b.iter().position(|other| other == funder)?;
+                Some(CommonFunder {
+                    funder: funder.clone(),
+                    hops_a,
+                    hops_b,
+                })
+            })
+            .min_by_key(|common| common.hops_a + common.hops_b);
+        Ok(common)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A graph with every wallet already traced, so nothing goes to RPC:
+    ///
+    /// ```text
+    /// root ─┬─ mom ─┬─ creator ── dev ── dev2
+    ///       │       └─ cousin
+    ///       └─ uncle ── second_cousin
+    /// exchange ─┬─ bot_a
+    ///           └─ bot_b
+    /// ```
+    async fn graph() -> FundingGraph {
+        let db_manager = DbManager::new("sqlite::memory:").await.unwrap();
+        let edges = [
+            ("root", None),
+            ("mom", Some("root")),
+            ("uncle", Some("root")),
+            ("creator", Some("mom")),
+            ("cousin", Some("mom")),
+            ("second_cousin", Some("uncle")),
+            ("dev", Some("creator")),
+            ("dev2", Some("dev")),
+            ("exchange", None),
+            ("bot_a", Some("exchange")),
+            ("bot_b", Some("exchange")),
+        ];
+        for (wallet, funder) in edges {
+            db_manager
+                .store_wallet_funding(&WalletFunding {
+                    wallet: wallet.to_string(),
+                    funder: funder.map(String::from),
+                    funded_at: funder.map(|_| Utc::now()),
+                    checked_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        let config = FundingConfig {
+            max_hops: 3,
+            max_pages: 1,
+            max_related: 200,
+            ignored_wallets: HashSet::from(["exchange".to_string()]),
+        };
+        // Never reached: every lookup is answered from the cache.
+        let rpc = Arc::new(RpcClient::new("http://127.0.0.1:9".to_string()));
+        FundingGraph::new(db_manager, rpc, config)
+    }
+
+    #[tokio::test]
+    async fn chains_stop_at_max_hops_and_unknown_funders() {
+        let graph = graph().await;
+        assert_eq!(graph.funding_chain("creator").await.unwrap(), ["mom", "root"]);
+        assert_eq!(graph.funding_chain("dev2").await.unwrap(), ["dev", "creator", "mom"]);
+        assert!(graph.funding_chain("bot_a").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn related_wallets_are_funders_funded_and_siblings() {
+        let graph = graph().await;
+        let related: Vec<(String, Relation, usize)> = graph
+            .related_wallets("creator")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|wallet| (wallet.address, wallet.relation, wallet.hops))
+            .collect();
+        let expected = [
+            ("dev", Relation::Funded, 1),
+            ("mom", Relation::Funder, 1),
+            ("cousin", Relation::Sibling, 2),
+            ("dev2", Relation::Funded, 2),
+            ("root", Relation::Funder, 2),
+            ("uncle", Relation::Sibling, 3),
+        ]
+        .map(|(address, relation, hops)| (address.to_string(), relation, hops));
+        // second_cousin is four edges away.
+        assert_eq!(related, expected);
+    }
+
+    #[tokio::test]
+    async fn common_funders_are_the_closest_shared_ancestor() {
+        let graph = graph().await;
+        let common = |funder: &str, hops_a, hops_b| {
+            Some(CommonFunder {
+                funder: funder.to_string(),
+                hops_a,
+                hops_b,
+            })
+        };
+        assert_eq!(graph.common_funder("dev", "cousin").await.unwrap(), common("mom", 2, 1));
+        assert_eq!(graph.common_funder("dev", "creator").await.unwrap(), common("creator", 1, 0));
+        assert_eq!(graph.common_funder("cousin", "second_cousin").await.unwrap(), common("root", 2, 2));
+        // Ignored wallets never relate two wallets.
+        assert_eq!(graph.common_funder("bot_a", "bot_b").await.unwrap(), None);
+        assert_eq!(graph.funder("bot_a").await.unwrap(), None);
+    }
+}
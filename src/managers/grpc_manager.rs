@@ -1,10 +1,10 @@
 
 use {
-    crate::{models::token::TokenInfo, utils::token_filter::matches_pump_fun_pattern}, anyhow::Result, bs58, futures::{sink::SinkExt, stream::StreamExt}, log::error, solana_client::nonblocking::rpc_client::RpcClient, solana_sdk::pubkey::Pubkey, std::{str::FromStr, sync::Arc, time::Duration}, tokio::sync::{mpsc, Mutex}, tonic::{metadata::errors::InvalidMetadataValue, transport::Endpoint}, tonic_health::pb::health_client::HealthClient, yellowstone_grpc_client::{GeyserGrpcClient, InterceptorXToken}, yellowstone_grpc_proto::{
+    crate::{managers::{dev_sell_manager::DevSellDetector, swqos_manager::ConfirmationTracker}, models::{token::TokenInfo, trade::Trade}, utils::{pump_fun::{bonding_curve_pda, parse_events, PumpEvent}, token_filter::matches_pump_fun_pattern}}, anyhow::Result, bs58, chrono::Utc, futures::{sink::SinkExt, stream::StreamExt}, log::error, solana_sdk::pubkey::Pubkey, std::{str::FromStr, sync::Arc, time::Duration}, tokio::sync::{mpsc, Mutex}, tonic::{metadata::errors::InvalidMetadataValue, transport::Endpoint}, tonic_health::pb::health_client::HealthClient, yellowstone_grpc_client::{GeyserGrpcClient, InterceptorXToken}, yellowstone_grpc_proto::{
         geyser::{
             geyser_client::GeyserClient, subscribe_update::UpdateOneof, SubscribeRequest, SubscribeUpdateTransaction
         },
-        prelude::SubscribeRequestPing,
+        prelude::{SubscribeRequestPing, TokenBalance},
     }
 };
 
@@ -16,6 +16,9 @@ pub struct GrpcStreamManager {
     max_reconnect_attempts: u32,
     reconnect_interval: Duration,
     event_sender: mpsc::Sender<TokenInfo>,
+    trade_sender: mpsc::Sender<Trade>,
+    dev_sells: Arc<DevSellDetector>,
+    confirmations: Arc<ConfirmationTracker>,
 }
 
 
@@ -34,17 +37,28 @@ impl GrpcStreamManager {
     // pub fn handle_account_update(&self, account_update: &Pubkey)
     pub fn handle_transaction_update(&self, transaction_update: &SubscribeUpdateTransaction) {
         if let Some(transaction) = &transaction_update.transaction {
-        
+            self.confirmations.observe(&transaction.signature, transaction_update.slot);
+            let events = transaction
+                .meta
+                .as_ref()
+                .map(|meta| parse_events(&meta.log_messages))
+                .unwrap_or_default();
+            let create_event = events.iter().find_map(|event| match event {
+                PumpEvent::Create(create) => Some(create),
+                _ => None,
+            });
+
             if let Some(transaction_message) = &transaction.transaction {
                 if let Some(message) = &transaction_message.message {
-                    if matches_pump_fun_pattern(&message) {                       
+                    if matches_pump_fun_pattern(message) {                       
                         // println!("Pump.fun launch detected. Transaction {}", bs58::encode(&transaction.signature.as_slice()).into_string());
-                        if let Some(token_key) = message.account_keys.get(0 as usize){
-                            if let Ok(pubkey) = Pubkey::try_from(token_key.as_slice()) {
-                                // println!("  Developer account: {}", pubkey.to_string());
-                            }
-                        };
-                        if let Some(token_key) = message.account_keys.get(1 as usize){
+                        // The creator signs and pays for the launch, so it's always the first account.
+                        let creator_address = message
+                            .account_keys
+                            .first()
+                            .and_then(|key| Pubkey::try_from(key.as_slice()).ok())
+                            .map(|pubkey| pubkey.to_string());
+                        if let Some(token_key) = message.account_keys.get(1){
                             // let mut keys : Vec<String> = vec![];
                             // for account in message.account_keys.clone() {
                             //     let pkey = Pubkey::try_from(account.as_slice()).unwrap();
@@ -52,10 +66,29 @@ impl GrpcStreamManager {
                             // }
                             // println!("Account keys: {:?}", keys);
                             if let Ok(pubkey) = Pubkey::try_from(token_key.as_slice()) {
+                                // The create event names the creator and curve; without
+                                // it, fall back to the fee payer and the derived curve.
+                                let create = create_event.filter(|create| create.mint == pubkey);
+                                let creator_address = create.map(|create| create.user.to_string()).or(creator_address);
+                                let bonding_curve = create.map_or_else(|| bonding_curve_pda(&pubkey), |create| create.bonding_curve);
+                                // Registered before this transaction's trades are
+                                // forwarded so the creator's initial buy is counted.
+                                if let Some(creator) = &creator_address {
+                                    self.dev_sells.track_launch(&pubkey.to_string(), creator, Utc::now());
+                                }
                                 let event_sender = self.event_sender.clone();
+                                let token_info = TokenInfo {
+                                    mint_address: pubkey.to_string(),
+                                    creator_address,
+                                    bonding_address: Some(bonding_curve.to_string()),
+                                    symbol: create.map(|create| create.symbol.clone()),
+                                    name: create.map(|create| create.name.clone()),
+                                    uri: create.map(|create| create.uri.clone()),
+                                    ..Default::default()
+                                };
                                 tokio::spawn(async move {
-                                    if let Err(e) = event_sender.send(TokenInfo { mint_address: pubkey.to_string(), ..Default::default()}).await {
-                                        // eprintln!("Failed to send token info: {:?}", e);
+                                    if let Err(e) = event_sender.send(token_info).await {
+                                        eprintln!("Failed to send token info: {:?}", e);
                                     }
                                 });
                                 // println!("  Pump.fun token: {}\n", pubkey.to_string());
@@ -64,6 +97,42 @@ impl GrpcStreamManager {
                     }
                 }
             }
+            let pool_balances = transaction
+                .meta
+                .as_ref()
+                .map(|meta| meta.post_token_balances.as_slice())
+                .unwrap_or_default();
+            self.forward_trades(transaction_update.slot, &transaction.signature, &events, pool_balances);
+        }
+    }
+
+    /// Sends every curve trade and AMM swap in a transaction to the trade
+    /// channel. Trades are dropped rather than stalling the stream when the
+    /// channel is full.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot the transaction landed in
+    /// * `signature` - The raw transaction signature
+    /// * `events` - The pump.fun events decoded from the transaction's logs
+    /// * `token_balances` - The transaction's post-execution token balances
+    fn forward_trades(&self, slot: u64, signature: &[u8], events: &[PumpEvent], token_balances: &[TokenBalance]) {
+        let signature = bs58::encode(signature).into_string();
+        for (event_index, event) in events.iter().enumerate() {
+            let trade = match event {
+                PumpEvent::Trade(trade_event) => {
+                    Trade::from_event(trade_event, signature.clone(), event_index as i32, slot)
+                }
+                PumpEvent::AmmSwap(swap) => {
+                    let Some(mint) = amm_pool_mint(token_balances, &swap.pool) else {
+                        continue;
+                    };
+                    Trade::from_amm_swap(swap, &mint, signature.clone(), event_index as i32, slot)
+                }
+                PumpEvent::Create(_) => continue,
+            };
+            if let Err(e) = self.trade_sender.try_send(trade) {
+                eprintln!("Dropping trade from {signature}: {e}");
+            }
         }
     }
 
@@ -72,7 +141,18 @@ impl GrpcStreamManager {
     /// # Arguments
     /// * `endpoint` - The gRPC endpoint URL
     /// * `x_token` - Authentication token for the endpoint
-    pub async fn new(endpoint: &str, x_token: &str, event_sender: mpsc::Sender<TokenInfo>) -> Result<Arc<Mutex<GrpcStreamManager>>> {
+    /// * `event_sender` - Receives detected launches
+    /// * `trade_sender` - Receives decoded curve trades
+    /// * `dev_sells` - Told about every launch before its trades are sent
+    /// * `confirmations` - Told about every transaction, to confirm our own
+    pub async fn new(
+        endpoint: &str,
+        x_token: &str,
+        event_sender: mpsc::Sender<TokenInfo>,
+        trade_sender: mpsc::Sender<Trade>,
+        dev_sells: Arc<DevSellDetector>,
+        confirmations: Arc<ConfirmationTracker>,
+    ) -> Result<Arc<Mutex<GrpcStreamManager>>> {
         let interceptor = InterceptorXToken {
             x_token: Some(x_token.parse().map_err(|e: InvalidMetadataValue| anyhow::Error::from(e))?),
             x_request_snapshot: true,
@@ -83,7 +163,7 @@ impl GrpcStreamManager {
             .timeout(Duration::from_secs(10))
             .connect()
             .await
-            .map_err(|e| anyhow::Error::from(e))?;
+            .map_err(anyhow::Error::from)?;
 
         let client: GeyserGrpcClient<InterceptorXToken> = GeyserGrpcClient::new(
             HealthClient::with_interceptor(channel.clone(), interceptor.clone()),
@@ -95,7 +175,10 @@ impl GrpcStreamManager {
             reconnect_attempts: 0,
             max_reconnect_attempts: 10,
             reconnect_interval: Duration::from_secs(5),
-            event_sender
+            event_sender,
+            trade_sender,
+            dev_sells,
+            confirmations,
         })))
     }
 
@@ -161,4 +244,23 @@ impl GrpcStreamManager {
 
         Box::pin(self.connect(request)).await
     }
-}
\ No newline at end of file
+}
+
+/// The base mint of a pump.fun AMM pool, read from the token accounts the
+/// pool owns. Returns `None` unless the pool is quoted in wrapped SOL.
+fn amm_pool_mint(token_balances: &[TokenBalance], pool: &Pubkey) -> Option<Pubkey> {
+    let pool = pool.to_string();
+    let wsol = spl_token::native_mint::id().to_string();
+    let pool_mints: Vec<&str> = token_balances
+        .iter()
+        .filter(|balance| balance.owner == pool)
+        .map(|balance| balance.mint.as_str())
+        .collect();
+    if !pool_mints.contains(&wsol.as_str()) {
+        return None;
+    }
+    pool_mints
+        .into_iter()
+        .find(|mint| *mint != wsol)
+        .and_then(|mint| Pubkey::from_str(mint).ok())
+}
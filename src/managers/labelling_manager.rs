@@ -0,0 +1,374 @@
+use {
+    super::{
+        analysis_manager::{feature_names, feature_vector, FEATURE_SCHEMA_VERSION},
+        db_manager::DbManager,
+    },
+    crate::{
+        models::{
+            token::{ModelTrainingData, TokenAnalysis, TokenRecord, TrainingOutcome},
+            trade::{Trade, TradeVenue},
+        },
+        utils::{archive::CsvGzWriter, pump_fun::BondingCurve},
+    },
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    std::{env, path::Path, sync::Arc, time::Duration},
+    tokio::time::MissedTickBehavior,
+};
+
+/// Tokens labelled per round trip.
+const LABEL_BATCH_SIZE: i64 = 100;
+
+#[derive(Debug, Clone)]
+pub struct LabellingConfig {
+    /// How often the job runs.
+    pub interval: Duration,
+    /// How long after launch a token is observed before it is labelled. Only
+    /// trades in this window count. Must be shorter than the trade retention
+    /// window.
+    pub horizon: Duration,
+    /// A snapshot is a `PeakMultiple` if the price later reached this
+    /// multiple of the price at the snapshot.
+    pub target_multiple: f32,
+    /// A snapshot is `Rugged` if the price later fell at least this far from
+    /// a high...
+    pub rug_drawdown: f32,
+    /// ...within this long of reaching it. Slower declines are `Abandoned`.
+    pub rug_window: Duration,
+}
+
+impl LabellingConfig {
+    /// Reads `LABEL_INTERVAL_SECS` (default 300), `LABEL_HORIZON_SECS`
+    /// (default 21600), `LABEL_TARGET_MULTIPLE` (default 2.0),
+    /// `LABEL_RUG_DRAWDOWN` (default 0.9) and `LABEL_RUG_WINDOW_SECS`
+    /// (default 600).
+    pub fn from_env() -> Self {
+        let secs = |name: &str, default: u64| {
+            Duration::from_secs(env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default).max(1))
+        };
+        let ratio = |name: &str, default: f32| env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        LabellingConfig {
+            interval: secs("LABEL_INTERVAL_SECS", 300),
+            horizon: secs("LABEL_HORIZON_SECS", 21_600),
+            target_multiple: ratio("LABEL_TARGET_MULTIPLE", 2.0),
+            rug_drawdown: ratio("LABEL_RUG_DRAWDOWN", 0.9),
+            rug_window: secs("LABEL_RUG_WINDOW_SECS", 600),
+        }
+    }
+}
+
+/// Turns `token_analysis` snapshots into `model_training_data` rows once a
+/// token has been observed for `horizon`.
+///
+/// Every snapshot of a token is labelled separately: the peak multiple and
+/// drawdown are measured from the price at the snapshot's age, so the same
+/// token can be a good buy at 10s and a bad one at 30m. Outcomes come from
+/// the trades in the window, never from the token's status: `rugged` is
+/// mostly set by the launch rug check, and labelling with it would teach the
+/// model to reproduce that check.
+pub struct LabellingJob {
+    db_manager: Arc<DbManager>,
+    config: LabellingConfig,
+}
+
+impl LabellingJob {
+    pub fn new(db_manager: Arc<DbManager>, config: LabellingConfig) -> Self {
+        LabellingJob { db_manager, config }
+    }
+
+    /// Runs every `interval` until the task is dropped.
+    pub async fn run(self) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match self.run_once().await {
+                Ok(0) => {}
+                Ok(labelled) => println!("Labelled {labelled} training rows"),
+                Err(e) => eprintln!("Labelling run failed: {:?}", e),
+            }
+        }
+    }
+
+    /// Labels every token launched more than `horizon` ago. Returns the
+    /// number of training rows written.
+    pub async fn run_once(&self) -> Result<u64> {
+        let launched_before = Utc::now() - chrono::Duration::from_std(self.config.horizon)?;
+        let mut written = 0;
+        loop {
+            let tokens = self
+                .db_manager
+                .get_unlabelled_tokens(FEATURE_SCHEMA_VERSION, launched_before, LABEL_BATCH_SIZE)
+                .await?;
+            if tokens.is_empty() {
+                break;
+            }
+            let mut rows = Vec::new();
+            for token in &tokens {
+                let (analyses, trades) = tokio::try_join!(
+                    self.db_manager.get_token_analyses(&token.mint_address),
+                    self.db_manager.get_trades(&token.mint_address),
+                )?;
+                rows.extend(label(&self.config, token, &analyses, &trades));
+            }
+            written += self.db_manager.store_training_data_batch(&rows).await?;
+            if (tokens.len() as i64) < LABEL_BATCH_SIZE {
+                break;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Writes every training row for the current feature schema to a gzip
+    /// CSV at `path`. Returns the number of rows written.
+    pub async fn export_dataset(&self, path: &Path) -> Result<usize> {
+        let rows = self.db_manager.get_training_data(FEATURE_SCHEMA_VERSION).await?;
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || write_dataset(&path, &rows))
+            .await
+            .map_err(|e| anyhow!("Export task failed: {e}"))?
+    }
+}
+
+/// Labels each snapshot of `token` from the trades in its observation window.
+fn label(config: &LabellingConfig, token: &TokenRecord, analyses: &[TokenAnalysis], trades: &[Trade]) -> Vec<ModelTrainingData> {
+    let launched_at = token.created_at.unwrap_or(token.status_changed_at);
+    let window_end = launched_at + chrono::Duration::from_std(config.horizon).unwrap_or(chrono::Duration::MAX);
+    // Trades come oldest first.
+    let trades: Vec<&Trade> = trades.iter().take_while(|t| t.traded_at <= window_end).collect();
+    let prices: Vec<(DateTime<Utc>, f64)> = trades
+        .iter()
+        .filter_map(|t| Some((t.traded_at, t.price_sol()?)))
+        .filter(|(_, price)| *price > 0.0)
+        .collect();
+    let completed = trades.iter().any(|t| t.venue == TradeVenue::Amm.as_str())
+        || trades
+            .iter()
+            .rfind(|t| t.venue == TradeVenue::Curve.as_str())
+            .is_some_and(|t| {
+                BondingCurve::from_virtual_reserves(t.virtual_sol_reserves as u64, t.virtual_token_reserves as u64).complete
+            });
+    let rug_window = chrono::Duration::from_std(config.rug_window).unwrap_or(chrono::Duration::MAX);
+
+    analyses
+        .iter()
+        .map(|analysis| {
+            let as_of = launched_at + chrono::Duration::seconds(analysis.age_secs);
+            let split = prices.partition_point(|(at, _)| *at <= as_of);
+            // The last price before the snapshot, or the first after it if
+            // the token hadn't traded yet.
+            let entry = split.checked_sub(1).or((split < prices.len()).then_some(split));
+            let excursion = match entry {
+                Some(entry) => price_excursion(&prices[entry..], config.rug_drawdown, rug_window),
+                None => Excursion::default(),
+            };
+
+            let outcome = if completed {
+                TrainingOutcome::Graduated
+            } else if excursion.peak_multiple >= config.target_multiple {
+                TrainingOutcome::PeakMultiple
+            } else if excursion.crashed {
+                TrainingOutcome::Rugged
+            } else {
+                TrainingOutcome::Abandoned
+            };
+
+            ModelTrainingData {
+                id: 0,
+                mint_address: token.mint_address.clone(),
+                age_secs: analysis.age_secs,
+                schema_version: FEATURE_SCHEMA_VERSION,
+                features: feature_vector(analysis),
+                outcome: if outcome.is_positive() { 1.0 } else { 0.0 },
+                outcome_label: outcome.as_str().to_string(),
+                peak_multiple: excursion.peak_multiple,
+                max_drawdown: excursion.max_drawdown,
+                created_at: Utc::now(),
+            }
+        })
+        .collect()
+}
+
+struct Excursion {
+    /// Highest later price over the entry price.
+    peak_multiple: f32,
+    /// Deepest fall from a running high.
+    max_drawdown: f32,
+    /// Whether the price fell `rug_drawdown` from a high within `rug_window`
+    /// of reaching it.
+    crashed: bool,
+}
+
+impl Default for Excursion {
+    fn default() -> Self {
+        Excursion {
+            peak_multiple: 1.0,
+            max_drawdown: 0.0,
+            crashed: false,
+        }
+    }
+}
+
+/// Measures a price series starting at the entry.
+fn price_excursion(prices: &[(DateTime<Utc>, f64)], rug_drawdown: f32, rug_window: chrono::Duration) -> Excursion {
+    let entry = prices[0].1;
+    let (mut high, mut high_at) = (entry, prices[0].0);
+    let mut max_drawdown: f64 = 0.0;
+    let mut crashed = false;
+    for (at, price) in prices {
+        if *price > high {
+            (high, high_at) = (*price, *at);
+        }
+        let drawdown = (high - price) / high;
+        max_drawdown = max_drawdown.max(drawdown);
+        crashed |= drawdown >= rug_drawdown as f64 && *at - high_at <= rug_window;
+    }
+    Excursion {
+        peak_multiple: (high / entry) as f32,
+        max_drawdown: max_drawdown as f32,
+        crashed,
+    }
+}
+
+fn write_dataset(path: &Path, rows: &[ModelTrainingData]) -> Result<usize> {
+    let mut header = vec![
+        "id".to_string(),
+        "schema_version".to_string(),
+        "mint_address".to_string(),
+        "age_secs".to_string(),
+    ];
+    header.extend(feature_names());
+    header.extend(["outcome", "outcome_label", "peak_multiple", "max_drawdown", "labelled_at"].map(String::from));
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+
+    let mut writer = CsvGzWriter::create(path, &header)?;
+    for row in rows {
+        let mut record = vec![
+            row.id.to_string(),
+            row.schema_version.to_string(),
+            row.mint_address.clone(),
+            row.age_secs.to_string(),
+        ];
+        record.extend(row.features.iter().map(|v| v.to_string()));
+        record.extend([
+            row.outcome.to_string(),
+            row.outcome_label.clone(),
+            row.peak_multiple.to_string(),
+            row.max_drawdown.to_string(),
+            row.created_at.to_rfc3339(),
+        ]);
+        writer.write_record(&record)?;
+    }
+    writer.finish()?;
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::TimeZone};
+
+    fn config() -> LabellingConfig {
+        LabellingConfig {
+            interval: Duration::from_secs(300),
+            horizon: Duration::from_secs(3_600),
+            target_multiple: 2.0,
+            rug_drawdown: 0.9,
+            rug_window: Duration::from_secs(600),
+        }
+    }
+
+    fn launched_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    /// A rugged token, as the launch rug check would have marked it.
+    fn token() -> TokenRecord {
+        TokenRecord {
+            id: 1,
+            mint_address: "mint".to_string(),
+            creator_address: None,
+            created_at: Some(launched_at()),
+            symbol: None,
+            bonding_address: None,
+            status: "rugged".to_string(),
+            status_changed_at: launched_at(),
+        }
+    }
+
+    fn analysis(age_secs: i64) -> TokenAnalysis {
+        TokenAnalysis {
+            mint_address: "mint".to_string(),
+            age_secs,
+            bonding_curve_progress: 0.0,
+            ilv: 0.0,
+            metadata_score: 0.0,
+            creator_age: 0,
+            sell_pressure: 0.0,
+            holder_count: 0,
+            top10_holder_share: 0.0,
+            clone_count: 0,
+            temporal_features: Vec::new(),
+            risk_score: 0.0,
+        }
+    }
+
+    /// A curve trade `secs` after launch at `price` SOL per token.
+    fn trade(secs: i64, price: f64) -> Trade {
+        Trade::test("mint").priced(price).at(launched_at() + chrono::Duration::seconds(secs))
+    }
+
+    fn outcomes(trades: &[Trade], ages: &[i64]) -> Vec<String> {
+        let analyses: Vec<TokenAnalysis> = ages.iter().map(|age| analysis(*age)).collect();
+        label(&config(), &token(), &analyses, trades)
+            .into_iter()
+            .map(|row| row.outcome_label)
+            .collect()
+    }
+
+    #[test]
+    fn outcomes_come_from_prices_not_status() {
+        let trades = [trade(5, 1.0), trade(60, 1.5), trade(600, 3.0)];
+        assert_eq!(outcomes(&trades, &[10, 120]), ["peak_multiple", "peak_multiple"]);
+
+        let flat = [trade(5, 1.0), trade(600, 1.2)];
+        assert_eq!(outcomes(&flat, &[10]), ["abandoned"]);
+    }
+
+    #[test]
+    fn each_snapshot_is_measured_from_its_own_price() {
+        let trades = [trade(5, 1.0), trade(60, 2.5), trade(120, 2.6)];
+        let rows = label(&config(), &token(), &[analysis(10), analysis(90)], &trades);
+        assert_eq!(rows[0].outcome_label, "peak_multiple");
+        assert!((rows[0].peak_multiple - 2.6).abs() < 1e-6);
+        assert_eq!(rows[1].outcome_label, "abandoned");
+    }
+
+    #[test]
+    fn only_fast_crashes_are_rugs() {
+        let dumped = [trade(5, 1.0), trade(60, 1.5), trade(120, 0.1)];
+        assert_eq!(outcomes(&dumped, &[10]), ["rugged"]);
+
+        let faded = [trade(5, 1.0), trade(60, 1.5), trade(1_800, 0.5), trade(3_000, 0.1)];
+        let rows = label(&config(), &token(), &[analysis(10)], &faded);
+        assert_eq!(rows[0].outcome_label, "abandoned");
+        assert!(rows[0].max_drawdown > 0.9);
+    }
+
+    #[test]
+    fn trades_after_the_horizon_are_ignored() {
+        let trades = [trade(5, 1.0), trade(3_700, 10.0)];
+        assert_eq!(outcomes(&trades, &[10]), ["abandoned"]);
+    }
+
+    #[test]
+    fn amm_trades_mean_the_curve_completed() {
+        let migrated = trade(900, 0.5).on(TradeVenue::Amm);
+        assert_eq!(outcomes(&[trade(5, 1.0), migrated], &[10]), ["graduated"]);
+    }
+
+    #[test]
+    fn snapshots_before_the_first_trade_use_it_as_entry() {
+        assert_eq!(outcomes(&[trade(30, 1.0), trade(40, 2.0)], &[10]), ["peak_multiple"]);
+        assert_eq!(outcomes(&[], &[10]), ["abandoned"]);
+    }
+}
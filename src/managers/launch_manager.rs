@@ -0,0 +1,385 @@
+use {
+    super::{
+        analysis_manager::FeatureExtractor,
+        copycat_manager::CopycatDetector,
+        db_manager::DbManager,
+        dev_sell_manager::DevSellDetector,
+        funding_manager::FundingGraph,
+        metadata_manager::MetadataCollector,
+        metrics_manager::MetricsScheduler,
+        swqos_manager::{Landing, SendStatus, SwqosRpcClient},
+    },
+    crate::{
+        models::token::{TokenInfo, TokenStatus},
+        providers::RiskAggregator,
+        scripts::swap::PumpSwapper,
+        utils::{
+            risk_model::RiskModel,
+            rug_check::{check_solana_rug, RugStatus},
+        },
+    },
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::HashMap, env, str::FromStr, sync::Arc, time::Duration},
+    tokio::sync::{mpsc, Semaphore},
+};
+
+#[derive(Debug, Clone)]
+pub struct LaunchPipelineConfig {
+    /// How long to wait after a launch before evaluating the token.
+    pub evaluation_delay: Duration,
+    /// Maximum number of tokens evaluated at the same time.
+    pub max_concurrent_evaluations: usize,
+    /// How many times a token is evaluated when its rug check fails or is
+    /// inconclusive, before it is filtered.
+    pub rug_check_attempts: u32,
+    /// How long to wait before evaluating such a token again.
+    pub rug_check_retry_delay: Duration,
+}
+
+impl LaunchPipelineConfig {
+    /// Reads `LAUNCH_EVAL_DELAY_SECS` (default 20), `LAUNCH_EVAL_CONCURRENCY`
+    /// (default 16), `LAUNCH_RUG_CHECK_ATTEMPTS` (default 3) and
+    /// `LAUNCH_RUG_CHECK_RETRY_SECS` (default 30).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        LaunchPipelineConfig {
+            evaluation_delay: Duration::from_secs(read("LAUNCH_EVAL_DELAY_SECS").unwrap_or(20)),
+            max_concurrent_evaluations: read("LAUNCH_EVAL_CONCURRENCY").unwrap_or(16) as usize,
+            rug_check_attempts: read("LAUNCH_RUG_CHECK_ATTEMPTS").unwrap_or(3).max(1) as u32,
+            rug_check_retry_delay: Duration::from_secs(read("LAUNCH_RUG_CHECK_RETRY_SECS").unwrap_or(30)),
+        }
+    }
+}
+
+/// Consumes detected launches: queues each token for storage immediately, then
+/// evaluates it once `evaluation_delay` has passed. With a risk model loaded,
+/// a token that passes the rug check is only a buy if the model also scores
+/// it at or above its threshold. A token whose creator has dumped is never a
+/// buy. Each launch's off-chain metadata is fetched in the background while
+/// the evaluation waits, and copies of recent launches are tagged and, unless
+/// configured otherwise, filtered out.
+///
+/// A token whose rug check fails or has too little data is evaluated again
+/// after `rug_check_retry_delay`, and filtered once `rug_check_attempts` are
+/// used up. Every failed attempt is recorded as an evaluation failure.
+pub struct LaunchPipeline {
+    db_manager: Arc<DbManager>,
+    rpc_manager: Arc<SwqosRpcClient>,
+    aggregator: Arc<RiskAggregator>,
+    metrics: Arc<MetricsScheduler>,
+    dev_sells: Arc<DevSellDetector>,
+    funding: Arc<FundingGraph>,
+    metadata: Arc<MetadataCollector>,
+    copycat: Arc<CopycatDetector>,
+    swapper: Option<Arc<PumpSwapper>>,
+    features: FeatureExtractor,
+    risk_model: Option<Arc<RiskModel>>,
+    evaluation_limit: Arc<Semaphore>,
+    config: LaunchPipelineConfig,
+}
+
+/// The stage of an evaluation, recorded alongside failures.
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    Store,
+    Validate,
+    RugCheck,
+    StoreVerdict,
+    Score,
+}
+
+impl Stage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Store => "store",
+            Stage::Validate => "validate",
+            Stage::RugCheck => "rug_check",
+            Stage::StoreVerdict => "store_verdict",
+            Stage::Score => "score",
+        }
+    }
+}
+
+impl LaunchPipeline {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db_manager: Arc<DbManager>,
+        rpc_manager: Arc<SwqosRpcClient>,
+        aggregator: Arc<RiskAggregator>,
+        metrics: Arc<MetricsScheduler>,
+        dev_sells: Arc<DevSellDetector>,
+        funding: Arc<FundingGraph>,
+        metadata: Arc<MetadataCollector>,
+        copycat: Arc<CopycatDetector>,
+        swapper: Option<Arc<PumpSwapper>>,
+        risk_model: Option<Arc<RiskModel>>,
+        config: LaunchPipelineConfig,
+    ) -> Arc<Self> {
+        Arc::new(LaunchPipeline {
+            features: FeatureExtractor::new(db_manager.clone(), risk_model.clone()),
+            db_manager,
+            rpc_manager,
+            aggregator,
+            metrics,
+            dev_sells,
+            funding,
+            metadata,
+            copycat,
+            swapper,
+            risk_model,
+            evaluation_limit: Arc::new(Semaphore::new(config.max_concurrent_evaluations)),
+            config,
+        })
+    }
+
+    /// Runs until the launch channel closes. Every launch is handled on its
+    /// own task so a slow evaluation never holds up the ones behind it.
+    pub async fn run(self: Arc<Self>, mut launches: mpsc::Receiver<TokenInfo>) {
+        while let Some(token_info) = launches.recv().await {
+            let pipeline = self.clone();
+            tokio::spawn(async move { pipeline.handle_launch(token_info).await });
+        }
+    }
+
+    async fn handle_launch(&self, mut token_info: TokenInfo) {
+        self.copycat.tag(&mut token_info);
+        let copy = token_info.copy.clone();
+        let mint_address = token_info.mint_address.clone();
+        let launched_at = token_info.created_at.unwrap_or_else(Utc::now);
+        let metadata_uri = token_info.uri.clone();
+        let (name, symbol) = (token_info.name.clone(), token_info.symbol.clone());
+        // The row is written by the next batch flush, well before the
+        // evaluation delay is up.
+        if let Err(e) = self.db_manager.queue_token_info(token_info).await {
+            // Without the token row there's nothing to attach an evaluation to.
+            eprintln!("Failed to store token {mint_address}: {e:?}");
+            return;
+        }
+        self.metrics.track(mint_address.clone(), launched_at);
+        // Gateways can take seconds, so copy tags and metadata are stored
+        // alongside the delay.
+        let (metadata, copycat) = (self.metadata.clone(), self.copycat.clone());
+        let mint = mint_address.clone();
+        tokio::spawn(async move {
+            if let Some(copy) = copy {
+                if let Err(e) = copycat.store(&mint, &copy).await {
+                    eprintln!("Failed to store copy tag of {mint}: {e:?}");
+                }
+            }
+            let Some(uri) = metadata_uri else {
+                return;
+            };
+            match metadata.collect(&mint, &uri, name, symbol).await {
+                Ok(collected) => {
+                    if let Some(image_hash) = collected.image_hash {
+                        if let Err(e) = copycat.record_image(&mint, image_hash).await {
+                            eprintln!("Failed to record image copies of {mint}: {e:?}");
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to collect metadata for {mint}: {e:?}"),
+            }
+        });
+
+        tokio::time::sleep(self.config.evaluation_delay).await;
+        match self
+            .db_manager
+            .transition_token_status(&mint_address, TokenStatus::Monitoring, "evaluation started")
+            .await
+        {
+            Ok(true) => {}
+            // The token already has an outcome, e.g. a dev sell during the
+            // evaluation delay marked it rugged.
+            Ok(false) => return,
+            Err(e) => return self.record_failure(&mint_address, Stage::Store, e).await,
+        }
+
+        for attempt in 1..=self.config.rug_check_attempts {
+            let Ok(permit) = self.evaluation_limit.acquire().await else {
+                return;
+            };
+            match self.evaluate(&mint_address, launched_at).await {
+                Ok(true) => {
+                    println!("Buy identified: {mint_address:?}");
+                    // Confirmation can take until the blockhash expires, so the
+                    // buy doesn't hold an evaluation slot.
+                    if let Some(swapper) = self.swapper.clone() {
+                        tokio::spawn(buy(swapper, mint_address));
+                    }
+                    return;
+                }
+                Ok(false) => return,
+                Err((Stage::RugCheck, e)) => {
+                    self.record_failure(&mint_address, Stage::RugCheck, e).await;
+                    if attempt < self.config.rug_check_attempts {
+                        // The retry waits without holding an evaluation slot.
+                        drop(permit);
+                        tokio::time::sleep(self.config.rug_check_retry_delay).await;
+                    }
+                }
+                Err((stage, e)) => return self.record_failure(&mint_address, stage, e).await,
+            }
+        }
+        let reason = format!("rug check inconclusive after {} attempts", self.config.rug_check_attempts);
+        if let Err(e) = self
+            .db_manager
+            .transition_token_status(&mint_address, TokenStatus::Filtered, &reason)
+            .await
+        {
+            self.record_failure(&mint_address, Stage::Store, e).await;
+        }
+    }
+
+    async fn record_failure(&self, mint_address: &str, stage: Stage, e: anyhow::Error) {
+        eprintln!("Error evaluating token {mint_address} at {}: {e:?}", stage.as_str());
+        if let Err(e) = self
+            .db_manager
+            .record_evaluation_failure(mint_address, stage.as_str(), &e.to_string())
+            .await
+        {
+            eprintln!("Failed to record evaluation failure for {mint_address}: {e:?}");
+        }
+    }
+
+    /// Returns whether the token passed every check. Fails at
+    /// `Stage::RugCheck` when the rug check couldn't reach a verdict.
+    async fn evaluate(&self, mint_address: &str, launched_at: DateTime<Utc>) -> Result<bool, (Stage, anyhow::Error)> {
+        let pubkey = Pubkey::from_str(mint_address).map_err(|e| (Stage::Validate, e.into()))?;
+        if self.copycat.filters_copies() {
+            if let Some(original) = self.copycat.copy_of(mint_address) {
+                self.db_manager
+                    .transition_token_status(mint_address, TokenStatus::Filtered, &format!("copy of {original}"))
+                    .await
+                    .map_err(|e| (Stage::Store, e))?;
+                return Ok(false);
+            }
+        }
+
+        let passes = self
+            .rpc_manager
+            .validate_token(&pubkey)
+            .await
+            .map_err(|e| (Stage::Validate, anyhow!(e)))?;
+        if !passes {
+            self.db_manager
+                .transition_token_status(mint_address, TokenStatus::Filtered, "bonding curve progress below threshold")
+                .await
+                .map_err(|e| (Stage::Store, e))?;
+            return Ok(false);
+        }
+
+        let verdict = check_solana_rug(&self.aggregator, mint_address)
+            .await
+            .map_err(|e| (Stage::RugCheck, anyhow!(e)))?;
+        self.db_manager
+            .store_rug_verdict(&verdict)
+            .await
+            .map_err(|e| (Stage::StoreVerdict, e))?;
+        if verdict.token_status == RugStatus::Rug {
+            let mut reasons: Vec<&str> = Vec::new();
+            for finding in &verdict.risk_factors {
+                let description = finding.signal.description();
+                if !reasons.contains(&description) {
+                    reasons.push(description);
+                }
+            }
+            let reason = format!("rug check confidence {:.0}: {}", verdict.confidence, reasons.join("; "));
+            self.db_manager
+                .transition_token_status(mint_address, TokenStatus::Rugged, &reason)
+                .await
+                .map_err(|e| (Stage::Store, e))?;
+            return Ok(false);
+        }
+        if verdict.token_status == RugStatus::InsufficientData {
+            let failed: Vec<&str> = verdict.failures.iter().map(|failure| failure.source.name()).collect();
+            return Err((Stage::RugCheck, anyhow!("insufficient data; failed sources: {}", failed.join(", "))));
+        }
+
+        // The rug check traced the early buyers' funding; any the creator
+        // funded are watched as its own wallets from here on.
+        if let Err(e) = self.link_creator_wallets(mint_address).await {
+            eprintln!("Failed to link creator wallets of {mint_address}: {e:?}");
+        }
+        // The creator may have dumped while the rug check ran.
+        if self.dev_sells.has_dumped(mint_address) {
+            return Ok(false);
+        }
+
+        let Some(model) = &self.risk_model else {
+            return Ok(true);
+        };
+        // Score on the same features the model was trained on, as of now.
+        let age = (Utc::now() - launched_at).to_std().unwrap_or_default();
+        let analysis = self
+            .features
+            .extract(mint_address, age)
+            .await
+            .map_err(|e| (Stage::Score, e))?;
+        let score = 1.0 - analysis.risk_score;
+        if !model.is_buy(score) {
+            let reason = format!("model score {score:.2} below {:.2}", model.threshold);
+            self.db_manager
+                .transition_token_status(mint_address, TokenStatus::Filtered, &reason)
+                .await
+                .map_err(|e| (Stage::Store, e))?;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Links every traced wallet the token's creator funded to the creator
+    /// in the dev sell detector, crediting the buys they already made on
+    /// this token.
+    async fn link_creator_wallets(&self, mint_address: &str) -> Result<()> {
+        let Some(creator_address) = self
+            .db_manager
+            .get_token(mint_address)
+            .await?
+            .and_then(|token| token.creator_address)
+        else {
+            return Ok(());
+        };
+        let wallets = self.funding.funded_wallets(&creator_address).await?;
+        if wallets.is_empty() {
+            return Ok(());
+        }
+        let mut bought: HashMap<String, i64> = HashMap::new();
+        for trade in self.db_manager.get_trades(mint_address).await? {
+            let net = bought.entry(trade.trader).or_default();
+            *net += if trade.is_buy { trade.token_amount } else { -trade.token_amount };
+        }
+        for wallet in wallets {
+            self.dev_sells.link_wallet(&creator_address, &wallet.address);
+            if let Some(&held) = bought.get(&wallet.address) {
+                self.dev_sells.credit_buys(mint_address, &wallet.address, held);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Buys a token that passed evaluation. Failures are logged; the token
+/// keeps its status either way.
+async fn buy(swapper: Arc<PumpSwapper>, mint_address: String) {
+    let bought = match Pubkey::from_str(&mint_address) {
+        Ok(mint) => swapper.buy(&mint).await,
+        Err(e) => Err(e.into()),
+    };
+    match bought {
+        Ok(report) => match report.status {
+            SendStatus::Landed(Landing { slot, error: None }) => {
+                println!("Bought {mint_address} in slot {slot}: {}", report.signature)
+            }
+            SendStatus::Landed(Landing { slot, error: Some(error) }) => {
+                eprintln!("Buy of {mint_address} failed in slot {slot}: {error} ({})", report.signature)
+            }
+            SendStatus::Expired => eprintln!(
+                "Buy of {mint_address} expired after {} broadcasts: {}",
+                report.broadcasts, report.signature
+            ),
+        },
+        Err(e) => eprintln!("Failed to buy {mint_address}: {e:?}"),
+    }
+}
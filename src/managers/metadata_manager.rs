@@ -0,0 +1,231 @@
+use {
+    super::db_manager::DbManager,
+    crate::{
+        models::metadata::{MetadataReuse, OffchainMetadata},
+        utils::{
+            copycat::image_hash,
+            hash::fnv1a,
+            metadata_fetch::{MetadataFetcher, MetadataJson},
+        },
+    },
+    anyhow::Result,
+    chrono::Utc,
+    std::sync::Arc,
+};
+
+/// Fetches each new token's off-chain metadata, scores it and stores it in
+/// `token_metadata` for the analysis features.
+pub struct MetadataCollector {
+    db_manager: Arc<DbManager>,
+    fetcher: MetadataFetcher,
+}
+
+impl MetadataCollector {
+    pub fn new(db_manager: Arc<DbManager>, fetcher: MetadataFetcher) -> Self {
+        MetadataCollector { db_manager, fetcher }
+    }
+
+    /// Fetches, scores and stores the metadata of a launch. A URI that
+    /// can't be fetched on any gateway is stored with its error and a score
+    /// of 0, so the launch still has a row.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token's mint
+    /// * `uri` - The metadata URI from the create instruction
+    /// * `name` - The on-chain name, used if the JSON has none
+    /// * `symbol` - The on-chain symbol, used if the JSON has none
+    pub async fn collect(
+        &self,
+        mint_address: &str,
+        uri: &str,
+        name: Option<String>,
+        symbol: Option<String>,
+    ) -> Result<OffchainMetadata> {
+        let (json, fetch_error) = match self.fetcher.fetch(uri).await {
+            Ok(json) => (json, None),
+            Err(e) => (MetadataJson::default(), Some(e.to_string())),
+        };
+        let description_hash = json.description.as_deref().map(description_hash);
+        let reuse = if fetch_error.is_none() {
+            self.db_manager
+                .count_metadata_reuse(mint_address, json.image.as_deref(), description_hash)
+                .await?
+        } else {
+            MetadataReuse::default()
+        };
+        let metadata_score = if fetch_error.is_none() { metadata_score(&json, reuse) } else { 0.0 };
+        let image_hash = match &json.image {
+            Some(image) => self.hash_image(mint_address, image).await,
+            None => None,
+        };
+
+        let metadata = OffchainMetadata {
+            mint_address: mint_address.to_string(),
+            uri: uri.to_string(),
+            name: json.name.or(name),
+            symbol: json.symbol.or(symbol),
+            description: json.description,
+            description_hash,
+            image: json.image,
+            image_hash,
+            twitter: json.twitter,
+            telegram: json.telegram,
+            website: json.website,
+            metadata_score,
+            fetch_error,
+            fetched_at: Utc::now(),
+        };
+        self.db_manager.queue_token_metadata(metadata.clone()).await?;
+        Ok(metadata)
+    }
+
+    /// Perceptual hash of the token's image, or `None` if it can't be
+    /// downloaded or decoded. Decoding runs off the async workers.
+    async fn hash_image(&self, mint_address: &str, image: &str) -> Option<i64> {
+        let hashed = match self.fetcher.fetch_image(image).await {
+            Ok(bytes) => tokio::task::spawn_blocking(move || image_hash(&bytes))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|hash| hash),
+            Err(e) => Err(e),
+        };
+        hashed
+            .map_err(|e| eprintln!("Failed to hash image of {mint_address}: {e:?}"))
+            .ok()
+    }
+}
+
+/// How complete and original a token's metadata is, 0-1. Each of image,
+/// description, Twitter, Telegram and website adds 0.2; an image used by
+/// another token takes 0.3 off and a copied description 0.2, since
+/// copy-paste launches reuse both.
+///
+/// # Arguments
+/// * `json` - The fetched metadata
+/// * `reuse` - How many other tokens share its image and description
+pub fn metadata_score(json: &MetadataJson, reuse: MetadataReuse) -> f64 {
+    let present = [&json.image, &json.description, &json.twitter, &json.telegram, &json.website]
+        .iter()
+        .filter(|field| field.is_some())
+        .count();
+    let mut score = present as f64 * 0.2;
+    if reuse.image_count > 0 {
+        score -= 0.3;
+    }
+    if reuse.description_count > 0 {
+        score -= 0.2;
+    }
+    score.clamp(0.0, 1.0)
+}
+
+/// 64-bit FNV-1a of a description, lowercased with whitespace collapsed so
+/// copies that only differ in spacing or case hash the same.
+pub fn description_hash(description: &str) -> i64 {
+    let normalized = description.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ");
+    fnv1a(normalized.as_bytes()) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::token::TokenInfo,
+            utils::{
+                metadata_fetch::MetadataConfig,
+                test_server::{serve, Reply},
+            },
+        },
+        serde_json::json,
+        std::time::Duration,
+    };
+
+    fn json(image: Option<&str>, description: Option<&str>) -> MetadataJson {
+        MetadataJson {
+            image: image.map(String::from),
+            description: description.map(String::from),
+            ..MetadataJson::default()
+        }
+    }
+
+    #[test]
+    fn score_rewards_completeness_and_penalizes_reuse() {
+        let complete = MetadataJson {
+            twitter: Some("https://x.com/token".to_string()),
+            telegram: Some("https://t.me/token".to_string()),
+            website: Some("https://token.fun".to_string()),
+            ..json(Some("ipfs://image"), Some("A token"))
+        };
+        let unique = MetadataReuse::default();
+        assert_eq!(metadata_score(&complete, unique), 1.0);
+        assert_eq!(metadata_score(&MetadataJson::default(), unique), 0.0);
+
+        let reused_image = MetadataReuse { image_count: 3, description_count: 0 };
+        let reused_description = MetadataReuse { image_count: 0, description_count: 1 };
+        let both = MetadataReuse { image_count: 1, description_count: 1 };
+        assert!((metadata_score(&complete, reused_image) - 0.7).abs() < 1e-9);
+        assert!((metadata_score(&complete, reused_description) - 0.8).abs() < 1e-9);
+        assert!((metadata_score(&complete, both) - 0.5).abs() < 1e-9);
+        // Never below zero.
+        assert_eq!(metadata_score(&json(Some("ipfs://image"), None), both), 0.0);
+    }
+
+    #[test]
+    fn description_hash_ignores_case_and_spacing() {
+        assert_eq!(description_hash("To the  Moon\n"), description_hash("to the moon"));
+        assert_ne!(description_hash("to the moon"), description_hash("to the mars"));
+    }
+
+    #[tokio::test]
+    async fn collected_copies_score_lower_than_the_original() {
+        let gateway = serve(|path| match path {
+            "/ipfs/original" => Reply::json(
+                200,
+                &json!({"image": "https://img/cat.png", "description": "Cat coin", "twitter": "https://x.com/cat"}),
+            ),
+            "/ipfs/copy" => Reply::json(
+                200,
+                &json!({"image": "https://img/cat.png", "description": "CAT  coin", "twitter": "https://x.com/cat2"}),
+            ),
+            _ => Reply::new(404, "not found"),
+        })
+        .await;
+        let db_manager = DbManager::new("sqlite::memory:").await.unwrap();
+        for mint_address in ["original", "copy", "missing"] {
+            db_manager
+                .queue_token_info(TokenInfo {
+                    mint_address: mint_address.to_string(),
+                    ..TokenInfo::default()
+                })
+                .await
+                .unwrap();
+        }
+        let fetcher = MetadataFetcher::new(MetadataConfig {
+            timeout: Duration::from_secs(1),
+            max_bytes: 1024,
+            max_image_bytes: 1024,
+            ipfs_gateways: vec![format!("{gateway}/ipfs/")],
+            arweave_gateways: Vec::new(),
+        })
+        .unwrap();
+        let collector = MetadataCollector::new(db_manager.clone(), fetcher);
+
+        let original = collector.collect("original", "ipfs://original", None, None).await.unwrap();
+        assert!((original.metadata_score - 0.6).abs() < 1e-9);
+        db_manager.flush().await.unwrap();
+
+        let copy = collector
+            .collect("copy", "ipfs://copy", Some("Cat".to_string()), None)
+            .await
+            .unwrap();
+        assert_eq!(copy.name.as_deref(), Some("Cat"));
+        assert_eq!(copy.description_hash, original.description_hash);
+        assert!((copy.metadata_score - 0.1).abs() < 1e-9);
+
+        let missing = collector.collect("missing", "ipfs://missing", None, None).await.unwrap();
+        assert_eq!(missing.metadata_score, 0.0);
+        assert!(missing.fetch_error.unwrap().contains("HTTP 404"));
+        db_manager.flush().await.unwrap();
+        assert!(db_manager.get_token_metadata("missing").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,334 @@
+use {
+    super::{
+        analysis_manager::FeatureExtractor,
+        db_manager::DbManager,
+        swqos_manager::SwqosRpcClient,
+    },
+    crate::{
+        models::{
+            token::{TokenAnalysis, TokenMetrics, TokenStatus},
+            trade::TradeVolume,
+        },
+        utils::{
+            risk_model::RiskModel,
+            wash_check::{analyze_wash_trading, WashConfig},
+        },
+    },
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey},
+    sqlx::types::BigDecimal,
+    std::{env, str::FromStr, sync::Arc, time::Duration},
+};
+
+const DEFAULT_SAMPLE_OFFSETS: &str = "10s,1m,5m,30m";
+const DEFAULT_INACTIVITY: &str = "10m";
+
+#[derive(Debug, Clone)]
+pub struct MetricsSchedulerConfig {
+    /// When to sample each token, measured from its launch.
+    pub sample_offsets: Vec<Duration>,
+    /// How long a token may go without trades after its last sample before
+    /// it is marked abandoned.
+    pub inactivity: Duration,
+    /// How wash trading is recognized when estimating organic volume.
+    pub wash: WashConfig,
+}
+
+impl MetricsSchedulerConfig {
+    /// Reads `METRICS_SAMPLE_OFFSETS`, a comma-separated list of offsets such
+    /// as `10s,1m,5m,30m` (the default), and `METRICS_INACTIVITY` (default
+    /// `10m`; `0` never marks tokens abandoned). Units are `s`, `m` and `h`. The wash-trading settings come from
+    /// `WashConfig::from_env`.
+    pub fn from_env() -> Result<Self> {
+        let offsets = env::var("METRICS_SAMPLE_OFFSETS").unwrap_or_else(|_| DEFAULT_SAMPLE_OFFSETS.to_string());
+        let mut sample_offsets = offsets
+            .split(',')
+            .map(|offset| parse_offset(offset.trim()))
+            .collect::<Result<Vec<_>>>()?;
+        sample_offsets.sort();
+        sample_offsets.dedup();
+        let inactivity = env::var("METRICS_INACTIVITY").unwrap_or_else(|_| DEFAULT_INACTIVITY.to_string());
+        Ok(MetricsSchedulerConfig {
+            sample_offsets,
+            inactivity: parse_offset(inactivity.trim())?,
+            wash: WashConfig::from_env(),
+        })
+    }
+}
+
+fn parse_offset(offset: &str) -> Result<Duration> {
+    let split = offset.find(|c: char| !c.is_ascii_digit()).unwrap_or(offset.len());
+    let (value, unit) = offset.split_at(split);
+    let value: u64 = value.parse().map_err(|_| anyhow!("Invalid sample offset: {offset:?}"))?;
+    match unit {
+        "s" | "" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        "h" => Ok(Duration::from_secs(value * 3600)),
+        _ => Err(anyhow!("Invalid sample offset unit: {offset:?}")),
+    }
+}
+
+/// Samples every live token into `token_metrics` at fixed offsets after its
+/// launch. A token stops being sampled once its curve completes or it is
+/// graduated, rugged or abandoned. Filtered tokens keep being sampled so
+/// their outcome is still recorded.
+///
+/// After the last offset, a token is checked every `inactivity` until its
+/// curve completes or it goes that long without a trade, which marks it
+/// abandoned.
+///
+/// Alongside each sample, a `TokenAnalysis` feature vector is stored for the
+/// offset's age. With a risk model loaded, the sample's `safety_score` is the
+/// model's score of that vector (0-100); without one it is the inverse of the
+/// latest rug-check confidence.
+pub struct MetricsScheduler {
+    db_manager: Arc<DbManager>,
+    rpc_manager: Arc<SwqosRpcClient>,
+    features: FeatureExtractor,
+    risk_model: Option<Arc<RiskModel>>,
+    config: MetricsSchedulerConfig,
+}
+
+impl MetricsScheduler {
+    pub fn new(
+        db_manager: Arc<DbManager>,
+        rpc_manager: Arc<SwqosRpcClient>,
+        risk_model: Option<Arc<RiskModel>>,
+        config: MetricsSchedulerConfig,
+    ) -> Arc<Self> {
+        Arc::new(MetricsScheduler {
+            features: FeatureExtractor::new(db_manager.clone(), risk_model.clone()),
+            db_manager,
+            rpc_manager,
+            risk_model,
+            config,
+        })
+    }
+
+    /// Starts sampling a token on its own task.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token to sample
+    /// * `launched_at` - When the token was launched; offsets are measured from here
+    pub fn track(self: &Arc<Self>, mint_address: String, launched_at: DateTime<Utc>) {
+        let scheduler = self.clone();
+        tokio::spawn(async move { scheduler.run_schedule(&mint_address, launched_at).await });
+    }
+
+    /// Resumes sampling for every active or filtered token in the database,
+    /// e.g. after a restart. Offsets that have already passed are skipped.
+    pub async fn resume(self: &Arc<Self>) -> Result<usize> {
+        let mut tokens = self.db_manager.get_active_tokens().await?;
+        tokens.extend(self.db_manager.get_tokens_by_status(TokenStatus::Filtered).await?);
+        let mut resumed = 0;
+        for token in tokens {
+            if let Some(record) = self.db_manager.get_token(&token.mint_address).await? {
+                self.track(record.mint_address, record.created_at.unwrap_or(record.status_changed_at));
+                resumed += 1;
+            }
+        }
+        Ok(resumed)
+    }
+
+    async fn run_schedule(&self, mint_address: &str, launched_at: DateTime<Utc>) {
+        let Ok(mint) = Pubkey::from_str(mint_address) else {
+            eprintln!("Not sampling {mint_address}: invalid mint address");
+            return;
+        };
+        // The holder count is an RPC scan that can fail on its own; a sample
+        // keeps the last count rather than being dropped.
+        let mut last_holders = 0;
+        for offset in &self.config.sample_offsets {
+            let due = launched_at + chrono::Duration::from_std(*offset).unwrap_or_default();
+            let Ok(wait) = (due - Utc::now()).to_std() else {
+                continue;
+            };
+            tokio::time::sleep(wait).await;
+
+            match self.is_settled(mint_address).await {
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(e) => {
+                    eprintln!("Failed to load token {mint_address}: {e:?}");
+                    continue;
+                }
+            }
+
+            match self.sample(&mint, mint_address, launched_at, last_holders).await {
+                Ok((mut metrics, complete)) => {
+                    last_holders = metrics.holders;
+                    let analysis = self.analyze(mint_address, *offset).await;
+                    if let (Some(_), Some(analysis)) = (&self.risk_model, &analysis) {
+                        metrics.safety_score = (1.0 - analysis.risk_score) as f64 * 100.0;
+                    }
+                    if let Err(e) = self.db_manager.queue_token_metrics(metrics).await {
+                        eprintln!("Failed to store metrics for {mint_address}: {e:?}");
+                    }
+                    if complete {
+                        self.graduate(mint_address).await;
+                        return;
+                    }
+                }
+                Err(e) => eprintln!("Failed to sample {mint_address}: {e:?}"),
+            }
+        }
+        self.watch_activity(&mint, mint_address).await;
+    }
+
+    /// Checks the token every `inactivity` until it graduates, reaches
+    /// another outcome, or goes `inactivity` without a trade and is marked
+    /// abandoned.
+    async fn watch_activity(&self, mint: &Pubkey, mint_address: &str) {
+        let inactivity = self.config.inactivity;
+        if inactivity.is_zero() {
+            return;
+        }
+        loop {
+            tokio::time::sleep(inactivity).await;
+            match self.is_settled(mint_address).await {
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(e) => {
+                    eprintln!("Failed to load token {mint_address}: {e:?}");
+                    continue;
+                }
+            }
+            match self.rpc_manager.get_bonding_curve(mint).await {
+                Ok(Some(curve)) if curve.complete => {
+                    self.graduate(mint_address).await;
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to load the bonding curve of {mint_address}: {e:?}"),
+            }
+            let last_trade = match self.db_manager.get_trades(mint_address).await {
+                Ok(trades) => trades.iter().map(|trade| trade.traded_at).max(),
+                Err(e) => {
+                    eprintln!("Failed to load trades for {mint_address}: {e:?}");
+                    continue;
+                }
+            };
+            let idle_since = Utc::now() - chrono::Duration::from_std(inactivity).unwrap_or_default();
+            if last_trade.is_none_or(|traded_at| traded_at < idle_since) {
+                let reason = format!("no trades for {}s", inactivity.as_secs());
+                if let Err(e) = self
+                    .db_manager
+                    .transition_token_status(mint_address, TokenStatus::Abandoned, &reason)
+                    .await
+                {
+                    eprintln!("Failed to mark {mint_address} abandoned: {e:?}");
+                }
+                return;
+            }
+        }
+    }
+
+    /// Whether the token is gone or has reached an outcome.
+    async fn is_settled(&self, mint_address: &str) -> Result<bool> {
+        match self.db_manager.get_token(mint_address).await? {
+            Some(token) => Ok(token.status()?.is_terminal()),
+            None => Ok(true),
+        }
+    }
+
+    async fn graduate(&self, mint_address: &str) {
+        if let Err(e) = self
+            .db_manager
+            .transition_token_status(mint_address, TokenStatus::Graduated, "bonding curve complete")
+            .await
+        {
+            eprintln!("Failed to mark {mint_address} graduated: {e:?}");
+        }
+    }
+
+    /// Stores the feature vector for `age`.
+    async fn analyze(&self, mint_address: &str, age: Duration) -> Option<TokenAnalysis> {
+        match self.features.analyze(mint_address, age).await {
+            Ok(analysis) => Some(analysis),
+            Err(e) => {
+                eprintln!("Failed to analyze {mint_address}: {e:?}");
+                None
+            }
+        }
+    }
+
+    /// Takes one sample. Returns the metrics and whether the curve is complete.
+    /// If the holders can't be counted, the sample keeps `last_holders`.
+    async fn sample(
+        &self,
+        mint: &Pubkey,
+        mint_address: &str,
+        launched_at: DateTime<Utc>,
+        last_holders: i32,
+    ) -> Result<(TokenMetrics, bool)> {
+        let (curve, holders, trades, verdicts) = tokio::join!(
+            self.rpc_manager.get_bonding_curve(mint),
+            self.rpc_manager.count_holders(mint),
+            self.db_manager.get_trades(mint_address),
+            self.db_manager.get_rug_verdicts(mint_address),
+        );
+        let curve = curve
+            .map_err(|e| anyhow!(e))?
+            .ok_or_else(|| anyhow!("No bonding curve for {mint_address}"))?;
+        let holders = match holders {
+            Ok(holders) => holders as i32,
+            Err(e) => {
+                eprintln!("Failed to count holders of {mint_address}, keeping {last_holders}: {e:?}");
+                last_holders
+            }
+        };
+        let trades = trades?;
+        let volume = TradeVolume::of(&trades);
+        let wash = analyze_wash_trading(&trades, &self.config.wash);
+
+        let sampled_at = Utc::now();
+        let token_age_secs = (sampled_at - launched_at).num_seconds().max(0);
+        let liquidity = curve.liquidity_sol();
+        // SOL raised per minute since launch.
+        let ilv = liquidity / (token_age_secs.max(1) as f64 / 60.0);
+        // Safety is the inverse of the latest rug-check confidence; 0 until
+        // the token has been checked.
+        let safety_score = verdicts?
+            .first()
+            .map(|verdict| 100.0 - verdict.confidence)
+            .unwrap_or(0.0);
+
+        let metrics = TokenMetrics {
+            mint_address: mint_address.to_string(),
+            sampled_at,
+            token_age_secs,
+            bonding_percent: curve.progress_percent(),
+            ilv,
+            safety_score,
+            liquidity,
+            holders,
+            volume: Some(lamports_to_sol(volume.buy_volume + volume.sell_volume)),
+            buy_volume: Some(lamports_to_sol(volume.buy_volume)),
+            sell_volume: Some(lamports_to_sol(volume.sell_volume)),
+            organic_volume: Some(lamports_to_sol(wash.organic_volume)),
+            top_trader_share: Some(wash.top_trader_share),
+        };
+        Ok((metrics, curve.complete))
+    }
+}
+
+fn lamports_to_sol(lamports: i64) -> BigDecimal {
+    BigDecimal::from(lamports) / BigDecimal::from(LAMPORTS_PER_SOL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_offsets_with_units() {
+        assert_eq!(parse_offset("10s").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_offset("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_offset("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_offset("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_offset("0").unwrap(), Duration::ZERO);
+        assert!(parse_offset("m").is_err());
+        assert!(parse_offset("5d").is_err());
+    }
+}
@@ -1,3 +1,16 @@
 pub mod grpc_manager;
 pub mod db_manager;
-pub mod swqos_manager;
\ No newline at end of file
+pub mod swqos_manager;
+pub mod store;
+pub mod launch_manager;
+pub mod metrics_manager;
+pub mod trade_manager;
+pub mod candle_manager;
+pub mod retention_manager;
+pub mod analysis_manager;
+pub mod labelling_manager;
+pub mod creator_manager;
+pub mod dev_sell_manager;
+pub mod funding_manager;
+pub mod metadata_manager;
+pub mod copycat_manager;
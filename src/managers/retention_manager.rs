@@ -0,0 +1,386 @@
+use {
+    super::db_manager::DbManager,
+    crate::{
+        models::{
+            candle::CandleInterval,
+            token::{TokenAnalysis, TokenMetrics, TokenRecord, TokenStatus, TokenStatusChange},
+            trade::Trade,
+        },
+        utils::archive::CsvGzWriter,
+    },
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    sqlx::types::BigDecimal,
+    std::{
+        env,
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    },
+    tokio::time::MissedTickBehavior,
+};
+
+/// Tokens archived and deleted per round trip.
+const ARCHIVE_BATCH_SIZE: i64 = 200;
+
+#[derive(Debug, Clone)]
+pub struct RetentionConfig {
+    /// How often the job runs.
+    pub interval: Duration,
+    /// Raw trades older than this are rolled into candles and deleted.
+    pub trade_retention: chrono::Duration,
+    /// How long a token is kept after reaching each terminal status before
+    /// it is archived and deleted.
+    pub status_retention: Vec<(TokenStatus, chrono::Duration)>,
+    /// Directory archives are written under.
+    pub archive_dir: PathBuf,
+}
+
+impl RetentionConfig {
+    /// Reads `RETENTION_INTERVAL_SECS` (default 3600), `TRADE_RETENTION_DAYS`
+    /// (default 7), `ARCHIVE_DIR` (default `archive`) and a
+    /// `RETENTION_<STATUS>_DAYS` window for each of rugged (14), abandoned (7),
+    /// graduated (90) and filtered (7).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<i64>().ok());
+        let days = |name: &str, default: i64| chrono::Duration::days(read(name).unwrap_or(default).max(1));
+        RetentionConfig {
+            interval: Duration::from_secs(read("RETENTION_INTERVAL_SECS").unwrap_or(3600).max(1) as u64),
+            trade_retention: days("TRADE_RETENTION_DAYS", 7),
+            status_retention: vec![
+                (TokenStatus::Rugged, days("RETENTION_RUGGED_DAYS", 14)),
+                (TokenStatus::Abandoned, days("RETENTION_ABANDONED_DAYS", 7)),
+                (TokenStatus::Graduated, days("RETENTION_GRADUATED_DAYS", 90)),
+                (TokenStatus::Filtered, days("RETENTION_FILTERED_DAYS", 7)),
+            ],
+            archive_dir: env::var("ARCHIVE_DIR").unwrap_or_else(|_| "archive".to_string()).into(),
+        }
+    }
+}
+
+/// Keeps the database from growing without bound.
+///
+/// Each run rolls raw trades past `trade_retention` into candles and deletes
+/// them. It then archives tokens that have sat in a terminal status past
+/// their window to gzip CSV files under `archive_dir/<status>/` and deletes
+/// their metrics, feature vectors, status history, trades and evaluation
+/// failures. Rug verdicts and candles are kept.
+pub struct RetentionJob {
+    db_manager: Arc<DbManager>,
+    config: RetentionConfig,
+}
+
+struct ArchivedToken {
+    token: TokenRecord,
+    history: Vec<TokenStatusChange>,
+    metrics: Vec<TokenMetrics>,
+    trades: Vec<Trade>,
+    analyses: Vec<TokenAnalysis>,
+}
+
+impl RetentionJob {
+    pub fn new(db_manager: Arc<DbManager>, config: RetentionConfig) -> Self {
+        RetentionJob { db_manager, config }
+    }
+
+    /// Runs every `interval` until the task is dropped. The first run
+    /// happens immediately.
+    pub async fn run(self) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.run_once().await {
+                eprintln!("Retention run failed: {:?}", e);
+            }
+        }
+    }
+
+    pub async fn run_once(&self) -> Result<()> {
+        let now = Utc::now();
+        // Align to the widest candle so no bucket is split between a rolled-up
+        // and a live part.
+        let trade_cutoff = CandleInterval::OneHour.bucket_start(now - self.config.trade_retention);
+        let rolled_up = self.db_manager.rollup_trades(trade_cutoff).await?;
+        if rolled_up > 0 {
+            println!("Rolled {rolled_up} trades before {trade_cutoff} into candles");
+        }
+
+        for (status, retention) in &self.config.status_retention {
+            let archived = self.archive_expired(*status, now - *retention, now).await?;
+            if archived > 0 {
+                println!("Archived and deleted {archived} {status} tokens");
+            }
+        }
+        Ok(())
+    }
+
+    /// Archives and deletes every token that reached `status` before
+    /// `older_than`, in batches. Returns the number of tokens deleted.
+    async fn archive_expired(&self, status: TokenStatus, older_than: DateTime<Utc>, now: DateTime<Utc>) -> Result<u64> {
+        let mut deleted = 0;
+        for batch in 0.. {
+            let tokens = self
+                .db_manager
+                .get_expired_tokens(status, older_than, ARCHIVE_BATCH_SIZE)
+                .await?;
+            if tokens.is_empty() {
+                break;
+            }
+            let full_batch = tokens.len() as i64 == ARCHIVE_BATCH_SIZE;
+
+            let mut archived = Vec::with_capacity(tokens.len());
+            for token in tokens {
+                let (history, metrics, trades, analyses) = tokio::try_join!(
+                    self.db_manager.get_status_history(&token.mint_address),
+                    self.db_manager.get_token_metrics(&token.mint_address),
+                    self.db_manager.get_trades(&token.mint_address),
+                    self.db_manager.get_token_analyses(&token.mint_address),
+                )?;
+                archived.push(ArchivedToken {
+                    token,
+                    history,
+                    metrics,
+                    trades,
+                    analyses,
+                });
+            }
+            let mint_addresses: Vec<String> = archived.iter().map(|a| a.token.mint_address.clone()).collect();
+
+            let dir = self
+                .config
+                .archive_dir
+                .join(status.as_str())
+                .join(format!("{}-{batch}", now.format("%Y%m%dT%H%M%SZ")));
+            tokio::task::spawn_blocking(move || write_archive(&dir, &archived))
+                .await
+                .map_err(|e| anyhow!("Archive task failed: {e}"))??;
+
+            // Only delete once the archive is safely on disk.
+            deleted += self.db_manager.delete_tokens(&mint_addresses).await?;
+            if !full_batch {
+                break;
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+fn write_archive(dir: &Path, archived: &[ArchivedToken]) -> Result<()> {
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+
+    let mut tokens = CsvGzWriter::create(
+        &dir.join("tokens.csv.gz"),
+        &["mint_address", "creator_address", "created_at", "symbol", "bonding_address", "status", "status_changed_at"],
+    )?;
+    let mut history = CsvGzWriter::create(
+        &dir.join("status_history.csv.gz"),
+        &["mint_address", "from_status", "to_status", "reason", "changed_at"],
+    )?;
+    let mut metrics = CsvGzWriter::create(
+        &dir.join("metrics.csv.gz"),
+        &[
+            "mint_address",
+            "sampled_at",
+            "token_age_secs",
+            "bonding_percent",
+            "ilv",
+            "safety_score",
+            "liquidity",
+            "holders",
+            "volume",
+            "buy_volume",
+            "sell_volume",
+            "organic_volume",
+            "top_trader_share",
+        ],
+    )?;
+    let mut trades = CsvGzWriter::create(
+        &dir.join("trades.csv.gz"),
+        &[
+            "signature",
+            "event_index",
+            "slot",
+            "mint_address",
+            "trader",
+            "is_buy",
+            "sol_amount",
+            "token_amount",
+            "virtual_sol_reserves",
+            "virtual_token_reserves",
+            "traded_at",
+            "venue",
+        ],
+    )?;
+
+    let mut analyses = CsvGzWriter::create(
+        &dir.join("analysis.csv.gz"),
+        &[
+            "mint_address",
+            "age_secs",
+            "bonding_curve_progress",
+            "ilv",
+            "metadata_score",
+            "creator_age",
+            "sell_pressure",
+            "holder_count",
+            "top10_holder_share",
+            "clone_count",
+            "temporal_features",
+            "risk_score",
+        ],
+    )?;
+
+    for entry in archived {
+        let token = &entry.token;
+        tokens.write_record(&[
+            token.mint_address.clone(),
+            optional(&token.creator_address),
+            token.created_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            optional(&token.symbol),
+            optional(&token.bonding_address),
+            token.status.clone(),
+            token.status_changed_at.to_rfc3339(),
+        ])?;
+        for change in &entry.history {
+            history.write_record(&[
+                change.mint_address.clone(),
+                optional(&change.from_status),
+                change.to_status.clone(),
+                change.reason.clone(),
+                change.changed_at.to_rfc3339(),
+            ])?;
+        }
+        for sample in &entry.metrics {
+            let decimal = |value: &Option<BigDecimal>| value.as_ref().map(|v| v.to_string()).unwrap_or_default();
+            metrics.write_record(&[
+                sample.mint_address.clone(),
+                sample.sampled_at.to_rfc3339(),
+                sample.token_age_secs.to_string(),
+                sample.bonding_percent.to_string(),
+                sample.ilv.to_string(),
+                sample.safety_score.to_string(),
+                sample.liquidity.to_string(),
+                sample.holders.to_string(),
+                decimal(&sample.volume),
+                decimal(&sample.buy_volume),
+                decimal(&sample.sell_volume),
+                decimal(&sample.organic_volume),
+                sample.top_trader_share.map(|share| share.to_string()).unwrap_or_default(),
+            ])?;
+        }
+        for trade in &entry.trades {
+            trades.write_record(&[
+                trade.signature.clone(),
+                trade.event_index.to_string(),
+                trade.slot.to_string(),
+                trade.mint_address.clone(),
+                trade.trader.clone(),
+                trade.is_buy.to_string(),
+                trade.sol_amount.to_string(),
+                trade.token_amount.to_string(),
+                trade.virtual_sol_reserves.to_string(),
+                trade.virtual_token_reserves.to_string(),
+                trade.traded_at.to_rfc3339(),
+                trade.venue.clone(),
+            ])?;
+        }
+        for analysis in &entry.analyses {
+            let temporal: Vec<String> = analysis.temporal_features.iter().map(|v| v.to_string()).collect();
+            analyses.write_record(&[
+                analysis.mint_address.clone(),
+                analysis.age_secs.to_string(),
+                analysis.bonding_curve_progress.to_string(),
+                analysis.ilv.to_string(),
+                analysis.metadata_score.to_string(),
+                analysis.creator_age.to_string(),
+                analysis.sell_pressure.to_string(),
+                analysis.holder_count.to_string(),
+                analysis.top10_holder_share.to_string(),
+                analysis.clone_count.to_string(),
+                temporal.join(";"),
+                analysis.risk_score.to_string(),
+            ])?;
+        }
+    }
+
+    tokens.finish()?;
+    history.finish()?;
+    metrics.finish()?;
+    trades.finish()?;
+    analyses.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::token::TokenInfo,
+        flate2::read::GzDecoder,
+        std::{
+            fs::{self, File},
+            io::Read,
+        },
+    };
+
+    fn read_gz(path: &Path) -> String {
+        let mut csv = String::new();
+        GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut csv).unwrap();
+        csv
+    }
+
+    #[tokio::test]
+    async fn archives_then_deletes_expired_tokens() {
+        let db_manager = DbManager::new("sqlite::memory:").await.unwrap();
+        for mint_address in ["rugged", "active"] {
+            db_manager
+                .store_token_info(&TokenInfo {
+                    mint_address: mint_address.to_string(),
+                    created_at: Some(Utc::now()),
+                    ..TokenInfo::default()
+                })
+                .await
+                .unwrap();
+        }
+        db_manager
+            .store_trade(&Trade::test("rugged").signature("rugged-buy").buy(1_000_000, 1_000))
+            .await
+            .unwrap();
+        db_manager
+            .transition_token_status("rugged", TokenStatus::Rugged, "dev sold 90%")
+            .await
+            .unwrap();
+
+        let archive_dir = env::temp_dir().join(format!("retention_{}", std::process::id()));
+        let job = RetentionJob::new(
+            db_manager.clone(),
+            RetentionConfig {
+                interval: Duration::from_secs(3600),
+                trade_retention: chrono::Duration::days(7),
+                status_retention: vec![],
+                archive_dir: archive_dir.clone(),
+            },
+        );
+        let now = Utc::now();
+        let deleted = job
+            .archive_expired(TokenStatus::Rugged, now + chrono::Duration::minutes(1), now)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(db_manager.get_token("rugged").await.unwrap().is_none());
+        assert!(db_manager.get_token("active").await.unwrap().is_some());
+
+        let dir = archive_dir
+            .join("rugged")
+            .join(format!("{}-0", now.format("%Y%m%dT%H%M%SZ")));
+        let tokens = read_gz(&dir.join("tokens.csv.gz"));
+        let history = read_gz(&dir.join("status_history.csv.gz"));
+        let trades = read_gz(&dir.join("trades.csv.gz"));
+        fs::remove_dir_all(&archive_dir).unwrap();
+        assert_eq!(tokens.lines().count(), 2);
+        assert!(tokens.lines().nth(1).unwrap().starts_with("rugged,"));
+        assert!(history.contains("rugged,detected,rugged,dev sold 90%,"));
+        assert!(trades.lines().nth(1).unwrap().starts_with("rugged-buy,0,1,rugged,trader,true,1000000,1000,"));
+    }
+}
@@ -0,0 +1,209 @@
+pub mod postgres;
+pub mod sqlite;
+pub mod write_buffer;
+
+#[cfg(test)]
+mod suite;
+
+use {
+    crate::{
+        managers::db_manager::MintAddress,
+        models::{
+            candle::{Candle, CandleInterval},
+            copycat::{LaunchFingerprint, TokenCopy},
+            creator::CreatorRecord,
+            metadata::{MetadataReuse, OffchainMetadata},
+            rug_verdict::{ProviderResponseRecord, RugVerdictRecord},
+            token::{EvaluationFailure, ModelTrainingData, TokenAnalysis, TokenInfo, TokenMetrics, TokenRecord, TokenStatus, TokenStatusChange},
+            trade::Trade,
+            wallet::WalletFunding,
+        },
+        utils::rug_check::RugCheckResult,
+    },
+    anyhow::Result,
+    async_trait::async_trait,
+    chrono::{DateTime, Utc},
+};
+
+/// Every persistence operation the monitor needs, independent of the backend.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Inserts or updates a detected token and records its initial `detected` status.
+    async fn store_token_info(&self, token_info: &TokenInfo) -> Result<()>;
+
+    /// `store_token_info` for many tokens in one round trip. Mint addresses
+    /// must be unique within the batch.
+    async fn store_token_info_batch(&self, token_infos: &[TokenInfo]) -> Result<()>;
+
+    /// Appends a metrics sample for an existing token.
+    async fn store_token_metrics(&self, token_metric: &TokenMetrics) -> Result<()>;
+
+    /// Appends many metrics samples in one round trip. Samples for tokens
+    /// that don't exist are skipped. Returns the number of rows written.
+    async fn store_token_metrics_batch(&self, token_metrics: &[TokenMetrics]) -> Result<u64>;
+
+    /// The token row for `mint_address`, if it has been stored.
+    async fn get_token(&self, mint_address: &str) -> Result<Option<TokenRecord>>;
+
+    /// Every token launched by `creator_address`, oldest first.
+    async fn get_tokens_by_creator(&self, creator_address: &str) -> Result<Vec<TokenRecord>>;
+
+    /// Tokens that are still being watched (`detected` or `monitoring`).
+    async fn get_active_tokens(&self) -> Result<Vec<MintAddress>>;
+
+    /// Mint addresses of every token currently in `status`.
+    async fn get_tokens_by_status(&self, status: TokenStatus) -> Result<Vec<MintAddress>>;
+
+    /// Moves a token to `to`, recording the change in `token_status_history`.
+    /// Returns `false` without changing anything if the token doesn't exist or
+    /// the transition isn't allowed from its current status.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token to update
+    /// * `to` - The new status
+    /// * `reason` - Why the status changed
+    async fn transition_token_status(&self, mint_address: &str, to: TokenStatus, reason: &str) -> Result<bool>;
+
+    /// Every status change for a token, oldest first.
+    async fn get_status_history(&self, mint_address: &str) -> Result<Vec<TokenStatusChange>>;
+
+    /// Stores a curve trade. Replaying the same event is a no-op.
+    async fn store_trade(&self, trade: &Trade) -> Result<()>;
+
+    /// Stores many trades in one round trip, skipping ones already stored.
+    /// Returns the number of new rows.
+    async fn store_trade_batch(&self, trades: &[Trade]) -> Result<u64>;
+
+    /// Inserts or replaces candles. Keys must be unique within the batch.
+    async fn store_candle_batch(&self, candles: &[Candle]) -> Result<()>;
+
+    /// Candles for a token at one resolution with `bucket_start` in
+    /// `[from, to)`, oldest first.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The token
+    /// * `interval` - The candle resolution
+    /// * `from` - Start of the range, inclusive
+    /// * `to` - End of the range, exclusive
+    async fn get_candles(
+        &self,
+        mint_address: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Candle>>;
+
+    /// Stores a feature vector, replacing any earlier one for the same token
+    /// and age. Does nothing if the token isn't stored.
+    async fn store_token_analysis(&self, analysis: &TokenAnalysis) -> Result<()>;
+
+    /// Every stored feature vector for a token, youngest age first.
+    async fn get_token_analyses(&self, mint_address: &str) -> Result<Vec<TokenAnalysis>>;
+
+    /// Up to `limit` tokens launched before `launched_before` with stored
+    /// feature vectors but no training rows for `schema_version`, whatever
+    /// their status.
+    async fn get_unlabelled_tokens(&self, schema_version: i32, launched_before: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>>;
+
+    /// Stores training rows, skipping any already stored for the same token,
+    /// age and schema version. Returns the number of new rows.
+    async fn store_training_data_batch(&self, rows: &[ModelTrainingData]) -> Result<u64>;
+
+    /// Every training row for `schema_version`, in insertion order.
+    async fn get_training_data(&self, schema_version: i32) -> Result<Vec<ModelTrainingData>>;
+
+    /// Brings `creator_launches` up to date for tokens that launched, reached
+    /// an outcome or were sold by their creator at or after `since`, then
+    /// recomputes the `creators` rows of everyone affected. Returns the
+    /// number of creators updated.
+    async fn sync_creators(&self, since: DateTime<Utc>) -> Result<u64>;
+
+    /// The reputation of `address`, if it has launched a synced token.
+    async fn get_creator(&self, address: &str) -> Result<Option<CreatorRecord>>;
+
+    /// Up to `limit` creators whose funding source hasn't been looked up,
+    /// most recent launch first.
+    async fn get_creators_without_funding(&self, limit: i64) -> Result<Vec<String>>;
+
+    /// Records the result of a funding lookup; `None` if no source was found.
+    ///
+    /// # Arguments
+    /// * `address` - The creator
+    /// * `funding_source` - The wallet that sent the creator its first SOL
+    /// * `funded_at` - When that transfer landed
+    async fn set_creator_funding(&self, address: &str, funding_source: Option<&str>, funded_at: Option<DateTime<Utc>>) -> Result<()>;
+
+    /// The cached funding lookup for `wallet`, if it has been traced.
+    async fn get_wallet_funding(&self, wallet: &str) -> Result<Option<WalletFunding>>;
+
+    /// Caches a funding lookup, replacing any earlier one for the wallet.
+    async fn store_wallet_funding(&self, funding: &WalletFunding) -> Result<()>;
+
+    /// Traced wallets whose first SOL came from `funder`.
+    async fn get_funded_wallets(&self, funder: &str) -> Result<Vec<String>>;
+
+    /// Stores a token's fetched metadata, replacing any earlier fetch. Does
+    /// nothing if the token isn't stored.
+    async fn store_token_metadata(&self, metadata: &OffchainMetadata) -> Result<()>;
+
+    /// The stored metadata of a token, if it has been fetched.
+    async fn get_token_metadata(&self, mint_address: &str) -> Result<Option<OffchainMetadata>>;
+
+    /// How many other tokens' metadata uses `image` or has a description
+    /// hashing to `description_hash`.
+    async fn count_metadata_reuse(&self, mint_address: &str, image: Option<&str>, description_hash: Option<i64>) -> Result<MetadataReuse>;
+
+    /// Records that a token copies another. A token keeps the first copy
+    /// recorded for it. Does nothing if the token isn't stored.
+    async fn store_token_copy(&self, copy: &TokenCopy) -> Result<()>;
+
+    /// What a token was found to copy, if anything.
+    async fn get_token_copy(&self, mint_address: &str) -> Result<Option<TokenCopy>>;
+
+    /// Stored tokens recorded as copies of `original_mint` by `detected_by`.
+    async fn count_clones(&self, original_mint: &str, detected_by: DateTime<Utc>) -> Result<i64>;
+
+    /// Tokens created since `since`, oldest first, with what the copycat
+    /// index matches on.
+    async fn get_launch_fingerprints(&self, since: DateTime<Utc>) -> Result<Vec<LaunchFingerprint>>;
+
+    /// Rolls every trade before `older_than` into candles at each resolution
+    /// and deletes the raw rows, in one transaction. Candles that already
+    /// exist are kept, so `older_than` should fall on an hour boundary.
+    /// Returns the number of trades deleted.
+    async fn rollup_trades(&self, older_than: DateTime<Utc>) -> Result<u64>;
+
+    /// Every trade on a token, oldest first.
+    async fn get_trades(&self, mint_address: &str) -> Result<Vec<Trade>>;
+
+    /// Every metrics sample for a token, oldest first.
+    async fn get_token_metrics(&self, mint_address: &str) -> Result<Vec<TokenMetrics>>;
+
+    /// Up to `limit` tokens that reached `status` before `older_than`,
+    /// longest-expired first.
+    async fn get_expired_tokens(&self, status: TokenStatus, older_than: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>>;
+
+    /// Deletes tokens together with their metrics, feature vectors, status
+    /// history, trades and evaluation failures. Rug verdicts and candles are kept. Returns the
+    /// number of tokens deleted.
+    async fn delete_tokens(&self, mint_addresses: &[String]) -> Result<u64>;
+
+    /// Records that evaluating a token failed at `stage`.
+    async fn record_evaluation_failure(&self, mint_address: &str, stage: &str, error: &str) -> Result<()>;
+
+    /// Every recorded evaluation failure for a token, oldest first.
+    async fn get_evaluation_failures(&self, mint_address: &str) -> Result<Vec<EvaluationFailure>>;
+
+    /// Stores a rug-check verdict together with the raw provider responses it
+    /// was built from. Returns the id of the new verdict.
+    async fn store_rug_verdict(&self, result: &RugCheckResult) -> Result<i64>;
+
+    /// All verdicts for a token, newest first.
+    async fn get_rug_verdicts(&self, mint_address: &str) -> Result<Vec<RugVerdictRecord>>;
+
+    /// Verdicts recorded in `[from, to)`, oldest first. Used to re-score history.
+    async fn get_rug_verdicts_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RugVerdictRecord>>;
+
+    /// The raw provider responses a verdict was built from.
+    async fn get_provider_responses(&self, verdict_id: i64) -> Result<Vec<ProviderResponseRecord>>;
+}
@@ -0,0 +1,1527 @@
+use {
+    super::TokenStore,
+    crate::{
+        managers::db_manager::MintAddress,
+        models::{
+            candle::{Candle, CandleInterval},
+            copycat::{LaunchFingerprint, TokenCopy},
+            creator::CreatorRecord,
+            metadata::{MetadataReuse, OffchainMetadata},
+            rug_verdict::{ProviderResponseRecord, RugVerdictRecord},
+            token::{EvaluationFailure, ModelTrainingData, TokenAnalysis, TokenInfo, TokenMetrics, TokenRecord, TokenStatus, TokenStatusChange},
+            trade::Trade,
+            wallet::WalletFunding,
+        },
+        utils::rug_check::RugCheckResult,
+    },
+    anyhow::Result,
+    async_trait::async_trait,
+    chrono::{DateTime, Utc},
+    sqlx::{types::BigDecimal, PgPool},
+    std::str::FromStr,
+};
+
+/// `TokenStore` backed by PostgreSQL. Queries are checked at compile time
+/// against the offline cache in `.sqlx/`.
+pub struct PgStore {
+    pool: PgPool,
+}
+
+impl PgStore {
+    /// Connects to `db_uri` and applies any pending migrations from
+    /// `migrations/postgres`, which are embedded in the binary at compile time.
+    pub async fn connect(db_uri: &str) -> Result<Self> {
+        let pool = PgPool::connect(db_uri).await?;
+        sqlx::migrate!("./migrations/postgres")
+            .run(&pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to run migrations: {:?}", e))?;
+        Ok(PgStore { pool })
+    }
+}
+
+#[async_trait]
+impl TokenStore for PgStore {
+    async fn store_token_info(&self, token_info: &TokenInfo) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO tokens (
+                mint_address,
+                creator_address,
+                created_at,
+                symbol,
+                bonding_address
+            )
+            VALUES (
+                $1, 
+                $2, 
+                $3, 
+                $4, 
+                $5
+            )
+            ON CONFLICT (mint_address) DO UPDATE SET
+                creator_address = EXCLUDED.creator_address,
+                symbol = EXCLUDED.symbol,
+                bonding_address = EXCLUDED.bonding_address
+            "#,
+            token_info.mint_address,
+            token_info.creator_address,
+            token_info.created_at,
+            token_info.symbol,
+            token_info.bonding_address,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store token info: {:?}", e))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO token_status_history (mint_address, from_status, to_status, reason)
+            SELECT $1, NULL, $2, 'launch detected'
+            WHERE NOT EXISTS (
+                SELECT 1 FROM token_status_history WHERE mint_address = $1
+            )
+            "#,
+            token_info.mint_address,
+            TokenStatus::Detected.as_str()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record token detection: {:?}", e))?;
+        Ok(())
+        
+    }
+
+    async fn store_token_info_batch(&self, token_infos: &[TokenInfo]) -> Result<()> {
+        let mut mint_addresses = Vec::with_capacity(token_infos.len());
+        let mut creator_addresses = Vec::with_capacity(token_infos.len());
+        let mut created_ats = Vec::with_capacity(token_infos.len());
+        let mut symbols = Vec::with_capacity(token_infos.len());
+        let mut bonding_addresses = Vec::with_capacity(token_infos.len());
+        for token_info in token_infos {
+            mint_addresses.push(token_info.mint_address.clone());
+            creator_addresses.push(token_info.creator_address.clone());
+            created_ats.push(token_info.created_at);
+            symbols.push(token_info.symbol.clone());
+            bonding_addresses.push(token_info.bonding_address.clone());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO tokens (
+                mint_address,
+                creator_address,
+                created_at,
+                symbol,
+                bonding_address
+            )
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::timestamptz[], $4::text[], $5::text[])
+            ON CONFLICT (mint_address) DO UPDATE SET
+                creator_address = EXCLUDED.creator_address,
+                symbol = EXCLUDED.symbol,
+                bonding_address = EXCLUDED.bonding_address
+            "#,
+            &mint_addresses,
+            &creator_addresses as &[Option<String>],
+            &created_ats as &[Option<DateTime<Utc>>],
+            &symbols as &[Option<String>],
+            &bonding_addresses as &[Option<String>],
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store token info batch: {:?}", e))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO token_status_history (mint_address, from_status, to_status, reason)
+            SELECT m.mint_address, NULL, $2, 'launch detected'
+            FROM UNNEST($1::text[]) AS m (mint_address)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM token_status_history h WHERE h.mint_address = m.mint_address
+            )
+            "#,
+            &mint_addresses,
+            TokenStatus::Detected.as_str()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record token detections: {:?}", e))?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn store_token_metrics(&self, token_metric: &TokenMetrics) -> Result<()> {
+        let inserted = self.store_token_metrics_batch(std::slice::from_ref(token_metric)).await?;
+        if inserted == 0 {
+            return Err(anyhow::anyhow!("Failed to fetch token: {} not found", token_metric.mint_address));
+        }
+        Ok(())
+    }
+
+    async fn store_token_metrics_batch(&self, token_metrics: &[TokenMetrics]) -> Result<u64> {
+        let mut mint_addresses = Vec::with_capacity(token_metrics.len());
+        let mut sampled_ats = Vec::with_capacity(token_metrics.len());
+        let mut token_ages = Vec::with_capacity(token_metrics.len());
+        let mut bonding_percents = Vec::with_capacity(token_metrics.len());
+        let mut ilvs = Vec::with_capacity(token_metrics.len());
+        let mut safety_scores = Vec::with_capacity(token_metrics.len());
+        let mut liquidities = Vec::with_capacity(token_metrics.len());
+        let mut holders = Vec::with_capacity(token_metrics.len());
+        let mut volumes = Vec::with_capacity(token_metrics.len());
+        let mut buy_volumes = Vec::with_capacity(token_metrics.len());
+        let mut sell_volumes = Vec::with_capacity(token_metrics.len());
+        let mut organic_volumes = Vec::with_capacity(token_metrics.len());
+        let mut top_trader_shares = Vec::with_capacity(token_metrics.len());
+        for metric in token_metrics {
+            mint_addresses.push(metric.mint_address.clone());
+            sampled_ats.push(metric.sampled_at);
+            token_ages.push(metric.token_age_secs);
+            bonding_percents.push(metric.bonding_percent);
+            ilvs.push(metric.ilv);
+            safety_scores.push(metric.safety_score);
+            liquidities.push(metric.liquidity);
+            holders.push(metric.holders);
+            volumes.push(metric.volume.clone());
+            buy_volumes.push(metric.buy_volume.clone());
+            sell_volumes.push(metric.sell_volume.clone());
+            organic_volumes.push(metric.organic_volume.clone());
+            top_trader_shares.push(metric.top_trader_share);
+        }
+
+        // Joining on tokens resolves every token id in the same statement.
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO token_metrics (
+                token_id,
+                sampled_at,
+                token_age_secs,
+                bonding_percent,
+                ilv,
+                safety_score,
+                liquidity,
+                holders,
+                volume,
+                buy_volume,
+                sell_volume,
+                organic_volume,
+                top_trader_share
+            )
+            SELECT
+                t.id,
+                m.sampled_at,
+                m.token_age_secs,
+                m.bonding_percent,
+                m.ilv,
+                m.safety_score,
+                m.liquidity,
+                m.holders,
+                m.volume,
+                m.buy_volume,
+                m.sell_volume,
+                m.organic_volume,
+                m.top_trader_share
+            FROM UNNEST(
+                $1::text[],
+                $2::timestamptz[],
+                $3::int8[],
+                $4::float8[],
+                $5::float8[],
+                $6::float8[],
+                $7::float8[],
+                $8::int4[],
+                $9::numeric[],
+                $10::numeric[],
+                $11::numeric[],
+                $12::numeric[],
+                $13::float8[]
+            ) AS m (
+                mint_address,
+                sampled_at,
+                token_age_secs,
+                bonding_percent,
+                ilv,
+                safety_score,
+                liquidity,
+                holders,
+                volume,
+                buy_volume,
+                sell_volume,
+                organic_volume,
+                top_trader_share
+            )
+            JOIN tokens t ON t.mint_address = m.mint_address
+            "#,
+            &mint_addresses,
+            &sampled_ats,
+            &token_ages,
+            &bonding_percents,
+            &ilvs,
+            &safety_scores,
+            &liquidities,
+            &holders,
+            &volumes as &[Option<BigDecimal>],
+            &buy_volumes as &[Option<BigDecimal>],
+            &sell_volumes as &[Option<BigDecimal>],
+            &organic_volumes as &[Option<BigDecimal>],
+            &top_trader_shares as &[Option<f64>],
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store token metrics: {:?}", e))?
+        .rows_affected();
+        Ok(inserted)
+    }
+
+    async fn get_token(&self, mint_address: &str) -> Result<Option<TokenRecord>> {
+        let token = sqlx::query_as!(
+            TokenRecord,
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(token)
+    }
+
+    async fn get_tokens_by_creator(&self, creator_address: &str) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as!(
+            TokenRecord,
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE creator_address = $1
+            ORDER BY created_at, id
+            "#,
+            creator_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn get_active_tokens(&self) -> Result<Vec<MintAddress>> {
+        let tokens = sqlx::query_as!(
+            MintAddress,
+            r#"
+            SELECT mint_address FROM tokens
+            WHERE status IN ('detected', 'monitoring')
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn get_tokens_by_status(&self, status: TokenStatus) -> Result<Vec<MintAddress>> {
+        let tokens = sqlx::query_as!(
+            MintAddress,
+            r#"
+            SELECT mint_address FROM tokens
+            WHERE status = $1
+            ORDER BY created_at
+            "#,
+            status.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn transition_token_status(&self, mint_address: &str, to: TokenStatus, reason: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let current = sqlx::query!(
+            r#"
+            SELECT status FROM tokens
+            WHERE mint_address = $1
+            FOR UPDATE
+            "#,
+            mint_address
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(current) = current else {
+            return Ok(false);
+        };
+        let from = TokenStatus::from_str(&current.status)?;
+        if !from.can_transition_to(to) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE tokens
+            SET status = $2, status_changed_at = now()
+            WHERE mint_address = $1
+            "#,
+            mint_address,
+            to.as_str()
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO token_status_history (mint_address, from_status, to_status, reason)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            mint_address,
+            from.as_str(),
+            to.as_str(),
+            reason
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_status_history(&self, mint_address: &str) -> Result<Vec<TokenStatusChange>> {
+        let history = sqlx::query_as!(
+            TokenStatusChange,
+            r#"
+            SELECT mint_address, from_status, to_status, reason, changed_at
+            FROM token_status_history
+            WHERE mint_address = $1
+            ORDER BY changed_at
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(history)
+    }
+
+    async fn store_trade(&self, trade: &Trade) -> Result<()> {
+        self.store_trade_batch(std::slice::from_ref(trade)).await?;
+        Ok(())
+    }
+
+    async fn store_trade_batch(&self, trades: &[Trade]) -> Result<u64> {
+        let mut signatures = Vec::with_capacity(trades.len());
+        let mut event_indexes = Vec::with_capacity(trades.len());
+        let mut slots = Vec::with_capacity(trades.len());
+        let mut mint_addresses = Vec::with_capacity(trades.len());
+        let mut traders = Vec::with_capacity(trades.len());
+        let mut is_buys = Vec::with_capacity(trades.len());
+        let mut sol_amounts = Vec::with_capacity(trades.len());
+        let mut token_amounts = Vec::with_capacity(trades.len());
+        let mut virtual_sol_reserves = Vec::with_capacity(trades.len());
+        let mut virtual_token_reserves = Vec::with_capacity(trades.len());
+        let mut traded_ats = Vec::with_capacity(trades.len());
+        let mut venues = Vec::with_capacity(trades.len());
+        for trade in trades {
+            signatures.push(trade.signature.clone());
+            event_indexes.push(trade.event_index);
+            slots.push(trade.slot);
+            mint_addresses.push(trade.mint_address.clone());
+            traders.push(trade.trader.clone());
+            is_buys.push(trade.is_buy);
+            sol_amounts.push(trade.sol_amount);
+            token_amounts.push(trade.token_amount);
+            virtual_sol_reserves.push(trade.virtual_sol_reserves);
+            virtual_token_reserves.push(trade.virtual_token_reserves);
+            traded_ats.push(trade.traded_at);
+            venues.push(trade.venue.clone());
+        }
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO trades (
+                signature,
+                event_index,
+                slot,
+                mint_address,
+                trader,
+                is_buy,
+                sol_amount,
+                token_amount,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                traded_at,
+                venue
+            )
+            SELECT * FROM UNNEST(
+                $1::text[],
+                $2::int4[],
+                $3::int8[],
+                $4::text[],
+                $5::text[],
+                $6::bool[],
+                $7::int8[],
+                $8::int8[],
+                $9::int8[],
+                $10::int8[],
+                $11::timestamptz[],
+                $12::text[]
+            )
+            ON CONFLICT (signature, event_index) DO NOTHING
+            "#,
+            &signatures,
+            &event_indexes,
+            &slots,
+            &mint_addresses,
+            &traders,
+            &is_buys,
+            &sol_amounts,
+            &token_amounts,
+            &virtual_sol_reserves,
+            &virtual_token_reserves,
+            &traded_ats,
+            &venues,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store trades: {:?}", e))?
+        .rows_affected();
+        Ok(inserted)
+    }
+
+    async fn store_candle_batch(&self, candles: &[Candle]) -> Result<()> {
+        let mut mint_addresses = Vec::with_capacity(candles.len());
+        let mut resolutions = Vec::with_capacity(candles.len());
+        let mut bucket_starts = Vec::with_capacity(candles.len());
+        let mut opens = Vec::with_capacity(candles.len());
+        let mut highs = Vec::with_capacity(candles.len());
+        let mut lows = Vec::with_capacity(candles.len());
+        let mut closes = Vec::with_capacity(candles.len());
+        let mut sol_volumes = Vec::with_capacity(candles.len());
+        let mut token_volumes = Vec::with_capacity(candles.len());
+        let mut trade_counts = Vec::with_capacity(candles.len());
+        for candle in candles {
+            mint_addresses.push(candle.mint_address.clone());
+            resolutions.push(candle.resolution.clone());
+            bucket_starts.push(candle.bucket_start);
+            opens.push(candle.open);
+            highs.push(candle.high);
+            lows.push(candle.low);
+            closes.push(candle.close);
+            sol_volumes.push(candle.sol_volume);
+            token_volumes.push(candle.token_volume);
+            trade_counts.push(candle.trade_count);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO candles (
+                mint_address,
+                resolution,
+                bucket_start,
+                open,
+                high,
+                low,
+                close,
+                sol_volume,
+                token_volume,
+                trade_count
+            )
+            SELECT * FROM UNNEST(
+                $1::text[],
+                $2::text[],
+                $3::timestamptz[],
+                $4::float8[],
+                $5::float8[],
+                $6::float8[],
+                $7::float8[],
+                $8::int8[],
+                $9::int8[],
+                $10::int4[]
+            )
+            ON CONFLICT (mint_address, resolution, bucket_start) DO UPDATE SET
+                open = EXCLUDED.open,
+                high = EXCLUDED.high,
+                low = EXCLUDED.low,
+                close = EXCLUDED.close,
+                sol_volume = EXCLUDED.sol_volume,
+                token_volume = EXCLUDED.token_volume,
+                trade_count = EXCLUDED.trade_count
+            "#,
+            &mint_addresses,
+            &resolutions,
+            &bucket_starts,
+            &opens,
+            &highs,
+            &lows,
+            &closes,
+            &sol_volumes,
+            &token_volumes,
+            &trade_counts,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store candles: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn get_candles(
+        &self,
+        mint_address: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Candle>> {
+        let candles = sqlx::query_as!(
+            Candle,
+            r#"
+            SELECT mint_address, resolution, bucket_start, open, high, low, close, sol_volume, token_volume, trade_count
+            FROM candles
+            WHERE mint_address = $1 AND resolution = $2 AND bucket_start >= $3 AND bucket_start < $4
+            ORDER BY bucket_start
+            "#,
+            mint_address,
+            interval.as_str(),
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candles)
+    }
+
+    async fn store_token_analysis(&self, analysis: &TokenAnalysis) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO token_analysis (
+                mint_address,
+                age_secs,
+                bonding_curve_progress,
+                ilv,
+                metadata_score,
+                creator_age,
+                sell_pressure,
+                holder_count,
+                top10_holder_share,
+                clone_count,
+                temporal_features,
+                risk_score
+            )
+            SELECT mint_address, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12
+            FROM tokens
+            WHERE mint_address = $1
+            ON CONFLICT (mint_address, age_secs) DO UPDATE SET
+                computed_at = now(),
+                bonding_curve_progress = EXCLUDED.bonding_curve_progress,
+                ilv = EXCLUDED.ilv,
+                metadata_score = EXCLUDED.metadata_score,
+                creator_age = EXCLUDED.creator_age,
+                sell_pressure = EXCLUDED.sell_pressure,
+                holder_count = EXCLUDED.holder_count,
+                top10_holder_share = EXCLUDED.top10_holder_share,
+                clone_count = EXCLUDED.clone_count,
+                temporal_features = EXCLUDED.temporal_features,
+                risk_score = EXCLUDED.risk_score
+            "#,
+            analysis.mint_address,
+            analysis.age_secs,
+            analysis.bonding_curve_progress,
+            analysis.ilv,
+            analysis.metadata_score,
+            analysis.creator_age,
+            analysis.sell_pressure,
+            analysis.holder_count as i32,
+            analysis.top10_holder_share,
+            analysis.clone_count as i32,
+            &analysis.temporal_features,
+            analysis.risk_score
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store token analysis: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn get_token_analyses(&self, mint_address: &str) -> Result<Vec<TokenAnalysis>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                mint_address,
+                age_secs,
+                bonding_curve_progress,
+                ilv,
+                metadata_score,
+                creator_age,
+                sell_pressure,
+                holder_count,
+                top10_holder_share,
+                clone_count,
+                temporal_features,
+                risk_score
+            FROM token_analysis
+            WHERE mint_address = $1
+            ORDER BY age_secs
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TokenAnalysis {
+                mint_address: row.mint_address,
+                age_secs: row.age_secs,
+                bonding_curve_progress: row.bonding_curve_progress,
+                ilv: row.ilv,
+                metadata_score: row.metadata_score,
+                creator_age: row.creator_age,
+                sell_pressure: row.sell_pressure,
+                holder_count: row.holder_count as u32,
+                top10_holder_share: row.top10_holder_share,
+                clone_count: row.clone_count as u32,
+                temporal_features: row.temporal_features,
+                risk_score: row.risk_score,
+            })
+            .collect())
+    }
+
+    async fn get_unlabelled_tokens(&self, schema_version: i32, launched_before: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as!(
+            TokenRecord,
+            r#"
+            SELECT t.id, t.mint_address, t.creator_address, t.created_at, t.symbol, t.bonding_address, t.status, t.status_changed_at
+            FROM tokens t
+            WHERE COALESCE(t.created_at, t.status_changed_at) < $2
+                AND EXISTS (SELECT 1 FROM token_analysis a WHERE a.mint_address = t.mint_address)
+                AND NOT EXISTS (
+                    SELECT 1 FROM model_training_data d
+                    WHERE d.mint_address = t.mint_address AND d.schema_version = $1
+                )
+            ORDER BY t.id
+            LIMIT $3
+            "#,
+            schema_version,
+            launched_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn store_training_data_batch(&self, rows: &[ModelTrainingData]) -> Result<u64> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let mut mint_addresses = Vec::with_capacity(rows.len());
+        let mut age_secs = Vec::with_capacity(rows.len());
+        let mut schema_versions = Vec::with_capacity(rows.len());
+        // Postgres has no arrays of arrays of varying length, so the feature
+        // vectors travel as JSON.
+        let mut features = Vec::with_capacity(rows.len());
+        let mut outcomes = Vec::with_capacity(rows.len());
+        let mut outcome_labels = Vec::with_capacity(rows.len());
+        let mut peak_multiples = Vec::with_capacity(rows.len());
+        let mut max_drawdowns = Vec::with_capacity(rows.len());
+        for row in rows {
+            mint_addresses.push(row.mint_address.clone());
+            age_secs.push(row.age_secs);
+            schema_versions.push(row.schema_version);
+            features.push(serde_json::to_value(&row.features)?);
+            outcomes.push(row.outcome);
+            outcome_labels.push(row.outcome_label.clone());
+            peak_multiples.push(row.peak_multiple);
+            max_drawdowns.push(row.max_drawdown);
+        }
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO model_training_data (
+                mint_address,
+                age_secs,
+                schema_version,
+                features,
+                outcome,
+                outcome_label,
+                peak_multiple,
+                max_drawdown
+            )
+            SELECT
+                r.mint_address,
+                r.age_secs,
+                r.schema_version,
+                ARRAY(SELECT jsonb_array_elements_text(r.features)::REAL),
+                r.outcome,
+                r.outcome_label,
+                r.peak_multiple,
+                r.max_drawdown
+            FROM UNNEST(
+                $1::TEXT[],
+                $2::BIGINT[],
+                $3::INTEGER[],
+                $4::JSONB[],
+                $5::REAL[],
+                $6::TEXT[],
+                $7::REAL[],
+                $8::REAL[]
+            ) AS r(mint_address, age_secs, schema_version, features, outcome, outcome_label, peak_multiple, max_drawdown)
+            ON CONFLICT (mint_address, age_secs, schema_version) DO NOTHING
+            "#,
+            &mint_addresses,
+            &age_secs,
+            &schema_versions,
+            &features,
+            &outcomes,
+            &outcome_labels,
+            &peak_multiples,
+            &max_drawdowns
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store training data: {:?}", e))?
+        .rows_affected();
+        Ok(inserted)
+    }
+
+    async fn get_training_data(&self, schema_version: i32) -> Result<Vec<ModelTrainingData>> {
+        let rows = sqlx::query_as!(
+            ModelTrainingData,
+            r#"
+            SELECT
+                id,
+                mint_address,
+                age_secs,
+                schema_version,
+                features,
+                outcome,
+                outcome_label,
+                peak_multiple,
+                max_drawdown,
+                created_at
+            FROM model_training_data
+            WHERE schema_version = $1
+            ORDER BY id
+            "#,
+            schema_version
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn sync_creators(&self, since: DateTime<Utc>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        // Outcomes never change once reached, and the first sell is kept even
+        // after the trade behind it has been rolled up. A rug the rug check
+        // itself flagged isn't an outcome: counting it would feed the verdict
+        // back into the creator's next check.
+        let mut creators: Vec<String> = sqlx::query!(
+            r#"
+            INSERT INTO creator_launches (mint_address, creator_address, launched_at, outcome, ended_at, first_sell_at)
+            SELECT
+                t.mint_address,
+                t.creator_address,
+                COALESCE(t.created_at, t.status_changed_at),
+                t.outcome,
+                CASE WHEN t.outcome IS NOT NULL THEN t.status_changed_at END,
+                (
+                    SELECT MIN(s.traded_at) FROM trades s
+                    WHERE s.mint_address = t.mint_address AND s.trader = t.creator_address AND NOT s.is_buy
+                )
+            FROM (
+                SELECT
+                    mint_address,
+                    creator_address,
+                    created_at,
+                    status_changed_at,
+                    CASE
+                        WHEN status IN ('graduated', 'abandoned') THEN status
+                        WHEN status = 'rugged' AND NOT EXISTS (
+                            SELECT 1 FROM rug_verdicts v
+                            WHERE v.mint_address = t.mint_address AND v.status = 'rug' AND v.checked_at <= t.status_changed_at
+                        ) THEN status
+                    END AS outcome
+                FROM tokens t
+                WHERE creator_address IS NOT NULL
+                    AND (
+                        status_changed_at >= $1
+                        OR (mint_address, creator_address) IN (
+                            SELECT s.mint_address, s.trader FROM trades s
+                            WHERE s.traded_at >= $1 AND NOT s.is_buy
+                        )
+                    )
+            ) t
+            ON CONFLICT (mint_address) DO UPDATE SET
+                outcome = EXCLUDED.outcome,
+                ended_at = EXCLUDED.ended_at,
+                first_sell_at = COALESCE(creator_launches.first_sell_at, EXCLUDED.first_sell_at),
+                updated_at = now()
+            RETURNING creator_address
+            "#,
+            since
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to sync creator launches: {:?}", e))?
+        .into_iter()
+        .map(|row| row.creator_address)
+        .collect();
+        creators.sort_unstable();
+        creators.dedup();
+        if creators.is_empty() {
+            return Ok(0);
+        }
+
+        let updated = sqlx::query!(
+            r#"
+            INSERT INTO creators (
+                address,
+                launch_count,
+                graduated_count,
+                rugged_count,
+                abandoned_count,
+                avg_lifetime_secs,
+                sold_count,
+                avg_first_sell_secs,
+                first_launch_at,
+                last_launch_at
+            )
+            SELECT
+                creator_address,
+                COUNT(*)::INTEGER,
+                (COUNT(*) FILTER (WHERE outcome = 'graduated'))::INTEGER,
+                (COUNT(*) FILTER (WHERE outcome = 'rugged'))::INTEGER,
+                (COUNT(*) FILTER (WHERE outcome = 'abandoned'))::INTEGER,
+                (AVG(GREATEST(EXTRACT(EPOCH FROM ended_at - launched_at), 0)) FILTER (WHERE ended_at IS NOT NULL))::DOUBLE PRECISION,
+                COUNT(first_sell_at)::INTEGER,
+                (AVG(GREATEST(EXTRACT(EPOCH FROM first_sell_at - launched_at), 0)) FILTER (WHERE first_sell_at IS NOT NULL))::DOUBLE PRECISION,
+                MIN(launched_at),
+                MAX(launched_at)
+            FROM creator_launches
+            WHERE creator_address = ANY($1)
+            GROUP BY creator_address
+            ON CONFLICT (address) DO UPDATE SET
+                launch_count = EXCLUDED.launch_count,
+                graduated_count = EXCLUDED.graduated_count,
+                rugged_count = EXCLUDED.rugged_count,
+                abandoned_count = EXCLUDED.abandoned_count,
+                avg_lifetime_secs = EXCLUDED.avg_lifetime_secs,
+                sold_count = EXCLUDED.sold_count,
+                avg_first_sell_secs = EXCLUDED.avg_first_sell_secs,
+                first_launch_at = EXCLUDED.first_launch_at,
+                last_launch_at = EXCLUDED.last_launch_at,
+                updated_at = now()
+            "#,
+            &creators
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update creators: {:?}", e))?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn get_creator(&self, address: &str) -> Result<Option<CreatorRecord>> {
+        let creator = sqlx::query_as!(
+            CreatorRecord,
+            r#"
+            SELECT
+                address,
+                launch_count,
+                graduated_count,
+                rugged_count,
+                abandoned_count,
+                avg_lifetime_secs,
+                sold_count,
+                avg_first_sell_secs,
+                first_launch_at,
+                last_launch_at,
+                funding_source,
+                funded_at,
+                funding_checked_at,
+                updated_at
+            FROM creators
+            WHERE address = $1
+            "#,
+            address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(creator)
+    }
+
+    async fn get_creators_without_funding(&self, limit: i64) -> Result<Vec<String>> {
+        let creators = sqlx::query!(
+            r#"
+            SELECT address FROM creators
+            WHERE funding_checked_at IS NULL
+            ORDER BY last_launch_at DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(creators.into_iter().map(|row| row.address).collect())
+    }
+
+    async fn set_creator_funding(&self, address: &str, funding_source: Option<&str>, funded_at: Option<DateTime<Utc>>) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE creators
+            SET funding_source = $2, funded_at = $3, funding_checked_at = now()
+            WHERE address = $1
+            "#,
+            address,
+            funding_source,
+            funded_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_wallet_funding(&self, wallet: &str) -> Result<Option<WalletFunding>> {
+        let funding = sqlx::query_as!(
+            WalletFunding,
+            r#"
+            SELECT wallet, funder, funded_at, checked_at
+            FROM wallet_funding
+            WHERE wallet = $1
+            "#,
+            wallet
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(funding)
+    }
+
+    async fn store_wallet_funding(&self, funding: &WalletFunding) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO wallet_funding (wallet, funder, funded_at, checked_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (wallet) DO UPDATE SET
+                funder = EXCLUDED.funder,
+                funded_at = EXCLUDED.funded_at,
+                checked_at = EXCLUDED.checked_at
+            "#,
+            funding.wallet,
+            funding.funder,
+            funding.funded_at,
+            funding.checked_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store funding of {}: {:?}", funding.wallet, e))?;
+        Ok(())
+    }
+
+    async fn get_funded_wallets(&self, funder: &str) -> Result<Vec<String>> {
+        let wallets = sqlx::query!(
+            r#"
+            SELECT wallet FROM wallet_funding
+            WHERE funder = $1
+            ORDER BY funded_at
+            "#,
+            funder
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(wallets.into_iter().map(|row| row.wallet).collect())
+    }
+
+    async fn store_token_metadata(&self, metadata: &OffchainMetadata) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO token_metadata (
+                mint_address,
+                uri,
+                name,
+                symbol,
+                description,
+                description_hash,
+                image,
+                image_hash,
+                twitter,
+                telegram,
+                website,
+                metadata_score,
+                fetch_error,
+                fetched_at
+            )
+            SELECT mint_address, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14
+            FROM tokens
+            WHERE mint_address = $1
+            ON CONFLICT (mint_address) DO UPDATE SET
+                uri = EXCLUDED.uri,
+                name = EXCLUDED.name,
+                symbol = EXCLUDED.symbol,
+                description = EXCLUDED.description,
+                description_hash = EXCLUDED.description_hash,
+                image = EXCLUDED.image,
+                image_hash = EXCLUDED.image_hash,
+                twitter = EXCLUDED.twitter,
+                telegram = EXCLUDED.telegram,
+                website = EXCLUDED.website,
+                metadata_score = EXCLUDED.metadata_score,
+                fetch_error = EXCLUDED.fetch_error,
+                fetched_at = EXCLUDED.fetched_at
+            "#,
+            metadata.mint_address,
+            metadata.uri,
+            metadata.name,
+            metadata.symbol,
+            metadata.description,
+            metadata.description_hash,
+            metadata.image,
+            metadata.image_hash,
+            metadata.twitter,
+            metadata.telegram,
+            metadata.website,
+            metadata.metadata_score,
+            metadata.fetch_error,
+            metadata.fetched_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store metadata of {}: {:?}", metadata.mint_address, e))?;
+        Ok(())
+    }
+
+    async fn get_token_metadata(&self, mint_address: &str) -> Result<Option<OffchainMetadata>> {
+        let metadata = sqlx::query_as!(
+            OffchainMetadata,
+            r#"
+            SELECT mint_address, uri, name, symbol, description, description_hash, image, image_hash, twitter, telegram,
+                website, metadata_score, fetch_error, fetched_at
+            FROM token_metadata
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(metadata)
+    }
+
+    async fn count_metadata_reuse(&self, mint_address: &str, image: Option<&str>, description_hash: Option<i64>) -> Result<MetadataReuse> {
+        let reuse = sqlx::query_as!(
+            MetadataReuse,
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE image = $2) AS "image_count!",
+                COUNT(*) FILTER (WHERE description_hash = $3) AS "description_count!"
+            FROM token_metadata
+            WHERE mint_address <> $1 AND (image = $2 OR description_hash = $3)
+            "#,
+            mint_address,
+            image,
+            description_hash
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(reuse)
+    }
+
+    async fn store_token_copy(&self, copy: &TokenCopy) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO token_copies (mint_address, original_mint, matched_on, detected_at)
+            SELECT mint_address, $2, $3, $4
+            FROM tokens
+            WHERE mint_address = $1
+            ON CONFLICT (mint_address) DO NOTHING
+            "#,
+            copy.mint_address,
+            copy.original_mint,
+            copy.matched_on,
+            copy.detected_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store copy of {}: {:?}", copy.mint_address, e))?;
+        Ok(())
+    }
+
+    async fn get_token_copy(&self, mint_address: &str) -> Result<Option<TokenCopy>> {
+        let copy = sqlx::query_as!(
+            TokenCopy,
+            r#"
+            SELECT mint_address, original_mint, matched_on, detected_at
+            FROM token_copies
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(copy)
+    }
+
+    async fn count_clones(&self, original_mint: &str, detected_by: DateTime<Utc>) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM token_copies WHERE original_mint = $1 AND detected_at <= $2"#,
+            original_mint,
+            detected_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    async fn get_launch_fingerprints(&self, since: DateTime<Utc>) -> Result<Vec<LaunchFingerprint>> {
+        let launches = sqlx::query_as!(
+            LaunchFingerprint,
+            r#"
+            SELECT
+                t.mint_address,
+                m.name AS "name?",
+                t.symbol,
+                m.image_hash AS "image_hash?",
+                t.created_at AS "created_at!",
+                c.original_mint AS "copy_of?"
+            FROM tokens t
+            LEFT JOIN token_metadata m ON m.mint_address = t.mint_address
+            LEFT JOIN token_copies c ON c.mint_address = t.mint_address
+            WHERE t.created_at >= $1
+            ORDER BY t.created_at, t.id
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(launches)
+    }
+
+    async fn rollup_trades(&self, older_than: DateTime<Utc>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        for interval in CandleInterval::ALL {
+            // Prices are SOL per whole token: lamports / 10^9 over base units / 10^6.
+            sqlx::query!(
+                r#"
+                INSERT INTO candles (
+                    mint_address,
+                    resolution,
+                    bucket_start,
+                    open,
+                    high,
+                    low,
+                    close,
+                    sol_volume,
+                    token_volume,
+                    trade_count
+                )
+                SELECT
+                    mint_address,
+                    $2,
+                    bucket_start,
+                    (array_agg(price ORDER BY traded_at, id))[1],
+                    MAX(price),
+                    MIN(price),
+                    (array_agg(price ORDER BY traded_at DESC, id DESC))[1],
+                    SUM(sol_amount)::BIGINT,
+                    SUM(token_amount)::BIGINT,
+                    COUNT(*)::INTEGER
+                FROM (
+                    SELECT
+                        id,
+                        mint_address,
+                        traded_at,
+                        sol_amount,
+                        token_amount,
+                        to_timestamp(floor(extract(epoch FROM traded_at)::float8 / $3::float8) * $3::float8) AS bucket_start,
+                        sol_amount::float8 / token_amount / 1000.0 AS price
+                    FROM trades
+                    WHERE traded_at < $1 AND token_amount > 0
+                ) t
+                GROUP BY mint_address, bucket_start
+                ON CONFLICT (mint_address, resolution, bucket_start) DO NOTHING
+                "#,
+                older_than,
+                interval.as_str(),
+                interval.seconds() as f64
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to roll up {} candles: {:?}", interval, e))?;
+        }
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM trades
+            WHERE traded_at < $1
+            "#,
+            older_than
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    async fn get_trades(&self, mint_address: &str) -> Result<Vec<Trade>> {
+        let trades = sqlx::query_as!(
+            Trade,
+            r#"
+            SELECT
+                signature,
+                event_index,
+                slot,
+                mint_address,
+                trader,
+                is_buy,
+                sol_amount,
+                token_amount,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                traded_at,
+                venue
+            FROM trades
+            WHERE mint_address = $1
+            ORDER BY traded_at, id
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(trades)
+    }
+
+    async fn get_token_metrics(&self, mint_address: &str) -> Result<Vec<TokenMetrics>> {
+        let metrics = sqlx::query_as!(
+            TokenMetrics,
+            r#"
+            SELECT
+                t.mint_address,
+                m.sampled_at,
+                m.token_age_secs,
+                m.bonding_percent,
+                m.ilv,
+                m.safety_score,
+                m.liquidity,
+                m.holders,
+                m.volume,
+                m.buy_volume,
+                m.sell_volume,
+                m.organic_volume,
+                m.top_trader_share
+            FROM token_metrics m
+            JOIN tokens t ON t.id = m.token_id
+            WHERE t.mint_address = $1
+            ORDER BY m.sampled_at
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(metrics)
+    }
+
+    async fn get_expired_tokens(&self, status: TokenStatus, older_than: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as!(
+            TokenRecord,
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE status = $1 AND status_changed_at < $2
+            ORDER BY status_changed_at
+            LIMIT $3
+            "#,
+            status.as_str(),
+            older_than,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn delete_tokens(&self, mint_addresses: &[String]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM trades
+            WHERE mint_address = ANY($1)
+            "#,
+            mint_addresses
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM evaluation_failures
+            WHERE mint_address = ANY($1)
+            "#,
+            mint_addresses
+        )
+        .execute(&mut *tx)
+        .await?;
+        // Metrics and status history cascade.
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM tokens
+            WHERE mint_address = ANY($1)
+            "#,
+            mint_addresses
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    async fn record_evaluation_failure(&self, mint_address: &str, stage: &str, error: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO evaluation_failures (mint_address, stage, error)
+            VALUES ($1, $2, $3)
+            "#,
+            mint_address,
+            stage,
+            error
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record evaluation failure: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn get_evaluation_failures(&self, mint_address: &str) -> Result<Vec<EvaluationFailure>> {
+        let failures = sqlx::query_as!(
+            EvaluationFailure,
+            r#"
+            SELECT stage, error, failed_at
+            FROM evaluation_failures
+            WHERE mint_address = $1
+            ORDER BY failed_at
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(failures)
+    }
+
+    async fn store_rug_verdict(&self, result: &RugCheckResult) -> Result<i64> {
+        let risk_factors = serde_json::to_value(&result.risk_factors)?;
+        let failures = serde_json::to_value(&result.failures)?;
+        let mut tx = self.pool.begin().await?;
+
+        let verdict = sqlx::query!(
+            r#"
+            INSERT INTO rug_verdicts (
+                mint_address,
+                checked_at,
+                status,
+                confidence,
+                risk_factors,
+                failures
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+            result.mint_address,
+            result.checked_at,
+            result.token_status.as_str(),
+            result.confidence,
+            risk_factors,
+            failures,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store rug verdict: {:?}", e))?;
+
+        for response in &result.responses {
+            sqlx::query!(
+                r#"
+                INSERT INTO provider_responses (
+                    verdict_id,
+                    mint_address,
+                    provider,
+                    http_status,
+                    latency_ms,
+                    body,
+                    error,
+                    fetched_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+                verdict.id,
+                result.mint_address,
+                response.source.name(),
+                response.http_status.map(i32::from),
+                response.latency_ms as i32,
+                response.body,
+                response.error,
+                result.checked_at,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store provider response: {:?}", e))?;
+        }
+
+        tx.commit().await?;
+        Ok(verdict.id)
+    }
+
+    async fn get_rug_verdicts(&self, mint_address: &str) -> Result<Vec<RugVerdictRecord>> {
+        let verdicts = sqlx::query_as!(
+            RugVerdictRecord,
+            r#"
+            SELECT id, mint_address, checked_at, status, confidence, risk_factors, failures
+            FROM rug_verdicts
+            WHERE mint_address = $1
+            ORDER BY checked_at DESC
+            "#,
+            mint_address
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(verdicts)
+    }
+
+    async fn get_rug_verdicts_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RugVerdictRecord>> {
+        let verdicts = sqlx::query_as!(
+            RugVerdictRecord,
+            r#"
+            SELECT id, mint_address, checked_at, status, confidence, risk_factors, failures
+            FROM rug_verdicts
+            WHERE checked_at >= $1 AND checked_at < $2
+            ORDER BY checked_at
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(verdicts)
+    }
+
+    async fn get_provider_responses(&self, verdict_id: i64) -> Result<Vec<ProviderResponseRecord>> {
+        let responses = sqlx::query_as!(
+            ProviderResponseRecord,
+            r#"
+            SELECT provider, http_status, latency_ms, body, error, fetched_at
+            FROM provider_responses
+            WHERE verdict_id = $1
+            ORDER BY provider
+            "#,
+            verdict_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::managers::store::suite};
+
+    /// Runs against the database in `DATABASE_URL` when it is a Postgres
+    /// URL, and passes trivially otherwise.
+    #[tokio::test]
+    async fn passes_the_store_suite() {
+        let Some(database_url) = std::env::var("DATABASE_URL").ok().filter(|url| url.starts_with("postgres")) else {
+            eprintln!("DATABASE_URL is not a Postgres URL; skipping the Postgres store suite");
+            return;
+        };
+        let store = PgStore::connect(&database_url).await.unwrap();
+        suite::run(&store).await;
+    }
+}
@@ -0,0 +1,1370 @@
+use {
+    super::TokenStore,
+    crate::{
+        managers::db_manager::MintAddress,
+        models::{
+            candle::{Candle, CandleInterval},
+            copycat::{LaunchFingerprint, TokenCopy},
+            creator::CreatorRecord,
+            metadata::{MetadataReuse, OffchainMetadata},
+            rug_verdict::{ProviderResponseRecord, RugVerdictRecord},
+            token::{EvaluationFailure, ModelTrainingData, TokenAnalysis, TokenInfo, TokenMetrics, TokenRecord, TokenStatus, TokenStatusChange},
+            trade::Trade,
+            wallet::WalletFunding,
+        },
+        utils::rug_check::RugCheckResult,
+    },
+    anyhow::Result,
+    async_trait::async_trait,
+    chrono::{DateTime, Utc},
+    sqlx::{
+        sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow},
+        types::BigDecimal,
+        Row, SqliteConnection, SqlitePool,
+    },
+    std::str::FromStr,
+};
+
+/// Embedded `TokenStore` for local development and tests.
+///
+/// Uses runtime-checked queries since the compile-time checked macros are
+/// bound to the Postgres schema. Timestamps are always bound from Rust so
+/// every row uses the same RFC 3339 text format and compares correctly.
+pub struct SqliteStore {
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    /// Opens (creating if needed) the database at `db_uri`, e.g.
+    /// `sqlite://monitor.db` or `sqlite::memory:`, and applies any pending
+    /// migrations from `migrations/sqlite`.
+    pub async fn connect(db_uri: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(db_uri)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .foreign_keys(true);
+        // Every connection to `:memory:` gets its own database, so keep one.
+        let max_connections = if db_uri.contains(":memory:") { 1 } else { 5 };
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await?;
+        sqlx::migrate!("./migrations/sqlite")
+            .run(&pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to run migrations: {:?}", e))?;
+        Ok(SqliteStore { pool })
+    }
+}
+
+#[async_trait]
+impl TokenStore for SqliteStore {
+    async fn store_token_info(&self, token_info: &TokenInfo) -> Result<()> {
+        self.store_token_info_batch(std::slice::from_ref(token_info)).await
+    }
+
+    async fn store_token_info_batch(&self, token_infos: &[TokenInfo]) -> Result<()> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        for token_info in token_infos {
+            insert_token_info(&mut tx, token_info, now).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn store_token_metrics(&self, token_metric: &TokenMetrics) -> Result<()> {
+        let inserted = self.store_token_metrics_batch(std::slice::from_ref(token_metric)).await?;
+        if inserted == 0 {
+            return Err(anyhow::anyhow!("Failed to fetch token: {} not found", token_metric.mint_address));
+        }
+        Ok(())
+    }
+
+    async fn store_token_metrics_batch(&self, token_metrics: &[TokenMetrics]) -> Result<u64> {
+        let now = Utc::now();
+        let mut inserted = 0;
+        let mut tx = self.pool.begin().await?;
+        for token_metric in token_metrics {
+            inserted += insert_token_metrics(&mut tx, token_metric, now).await?;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn get_token(&self, mint_address: &str) -> Result<Option<TokenRecord>> {
+        let token = sqlx::query_as::<_, TokenRecord>(
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE mint_address = ?1
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(token)
+    }
+
+    async fn get_tokens_by_creator(&self, creator_address: &str) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as::<_, TokenRecord>(
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE creator_address = ?1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(creator_address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn get_active_tokens(&self) -> Result<Vec<MintAddress>> {
+        let tokens = sqlx::query_as::<_, MintAddress>(
+            r#"
+            SELECT mint_address FROM tokens
+            WHERE status IN ('detected', 'monitoring')
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn get_tokens_by_status(&self, status: TokenStatus) -> Result<Vec<MintAddress>> {
+        let tokens = sqlx::query_as::<_, MintAddress>(
+            r#"
+            SELECT mint_address FROM tokens
+            WHERE status = ?1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(status.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn transition_token_status(&self, mint_address: &str, to: TokenStatus, reason: &str) -> Result<bool> {
+        let current: Option<String> = sqlx::query_scalar("SELECT status FROM tokens WHERE mint_address = ?1")
+            .bind(mint_address)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some(current) = current else {
+            return Ok(false);
+        };
+        let from = TokenStatus::from_str(&current)?;
+        if !from.can_transition_to(to) {
+            return Ok(false);
+        }
+
+        // SQLite has no SELECT ... FOR UPDATE, so the update only applies if
+        // the status is still the one that was validated.
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query(
+            r#"
+            UPDATE tokens
+            SET status = ?2, status_changed_at = ?4
+            WHERE mint_address = ?1 AND status = ?3
+            "#,
+        )
+        .bind(mint_address)
+        .bind(to.as_str())
+        .bind(from.as_str())
+        .bind(now)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            return Ok(false);
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO token_status_history (mint_address, from_status, to_status, reason, changed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(mint_address)
+        .bind(from.as_str())
+        .bind(to.as_str())
+        .bind(reason)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_status_history(&self, mint_address: &str) -> Result<Vec<TokenStatusChange>> {
+        let history = sqlx::query_as::<_, TokenStatusChange>(
+            r#"
+            SELECT mint_address, from_status, to_status, reason, changed_at
+            FROM token_status_history
+            WHERE mint_address = ?1
+            ORDER BY changed_at, id
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(history)
+    }
+
+    async fn store_trade(&self, trade: &Trade) -> Result<()> {
+        self.store_trade_batch(std::slice::from_ref(trade)).await?;
+        Ok(())
+    }
+
+    async fn store_trade_batch(&self, trades: &[Trade]) -> Result<u64> {
+        let mut inserted = 0;
+        let mut tx = self.pool.begin().await?;
+        for trade in trades {
+            inserted += insert_trade(&mut tx, trade).await?;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn store_candle_batch(&self, candles: &[Candle]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for candle in candles {
+            sqlx::query(
+                r#"
+                INSERT INTO candles (
+                    mint_address,
+                    resolution,
+                    bucket_start,
+                    open,
+                    high,
+                    low,
+                    close,
+                    sol_volume,
+                    token_volume,
+                    trade_count
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT (mint_address, resolution, bucket_start) DO UPDATE SET
+                    open = excluded.open,
+                    high = excluded.high,
+                    low = excluded.low,
+                    close = excluded.close,
+                    sol_volume = excluded.sol_volume,
+                    token_volume = excluded.token_volume,
+                    trade_count = excluded.trade_count
+                "#,
+            )
+            .bind(&candle.mint_address)
+            .bind(&candle.resolution)
+            .bind(candle.bucket_start)
+            .bind(candle.open)
+            .bind(candle.high)
+            .bind(candle.low)
+            .bind(candle.close)
+            .bind(candle.sol_volume)
+            .bind(candle.token_volume)
+            .bind(candle.trade_count)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store candles: {:?}", e))?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_candles(
+        &self,
+        mint_address: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Candle>> {
+        let candles = sqlx::query_as::<_, Candle>(
+            r#"
+            SELECT mint_address, resolution, bucket_start, open, high, low, close, sol_volume, token_volume, trade_count
+            FROM candles
+            WHERE mint_address = ?1 AND resolution = ?2 AND bucket_start >= ?3 AND bucket_start < ?4
+            ORDER BY bucket_start
+            "#,
+        )
+        .bind(mint_address)
+        .bind(interval.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candles)
+    }
+
+    async fn store_token_analysis(&self, analysis: &TokenAnalysis) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO token_analysis (
+                mint_address,
+                age_secs,
+                computed_at,
+                bonding_curve_progress,
+                ilv,
+                metadata_score,
+                creator_age,
+                sell_pressure,
+                holder_count,
+                top10_holder_share,
+                clone_count,
+                temporal_features,
+                risk_score
+            )
+            SELECT mint_address, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13
+            FROM tokens
+            WHERE mint_address = ?1
+            ON CONFLICT (mint_address, age_secs) DO UPDATE SET
+                computed_at = excluded.computed_at,
+                bonding_curve_progress = excluded.bonding_curve_progress,
+                ilv = excluded.ilv,
+                metadata_score = excluded.metadata_score,
+                creator_age = excluded.creator_age,
+                sell_pressure = excluded.sell_pressure,
+                holder_count = excluded.holder_count,
+                top10_holder_share = excluded.top10_holder_share,
+                clone_count = excluded.clone_count,
+                temporal_features = excluded.temporal_features,
+                risk_score = excluded.risk_score
+            "#,
+        )
+        .bind(&analysis.mint_address)
+        .bind(analysis.age_secs)
+        .bind(Utc::now())
+        .bind(analysis.bonding_curve_progress)
+        .bind(analysis.ilv)
+        .bind(analysis.metadata_score)
+        .bind(analysis.creator_age)
+        .bind(analysis.sell_pressure)
+        .bind(analysis.holder_count)
+        .bind(analysis.top10_holder_share)
+        .bind(analysis.clone_count)
+        .bind(serde_json::to_string(&analysis.temporal_features)?)
+        .bind(analysis.risk_score)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store token analysis: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn get_token_analyses(&self, mint_address: &str) -> Result<Vec<TokenAnalysis>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                mint_address,
+                age_secs,
+                bonding_curve_progress,
+                ilv,
+                metadata_score,
+                creator_age,
+                sell_pressure,
+                holder_count,
+                top10_holder_share,
+                clone_count,
+                temporal_features,
+                risk_score
+            FROM token_analysis
+            WHERE mint_address = ?1
+            ORDER BY age_secs
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(token_analysis_from_row).collect()
+    }
+
+    async fn get_unlabelled_tokens(&self, schema_version: i32, launched_before: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as::<_, TokenRecord>(
+            r#"
+            SELECT t.id, t.mint_address, t.creator_address, t.created_at, t.symbol, t.bonding_address, t.status, t.status_changed_at
+            FROM tokens t
+            WHERE COALESCE(t.created_at, t.status_changed_at) < ?2
+                AND EXISTS (SELECT 1 FROM token_analysis a WHERE a.mint_address = t.mint_address)
+                AND NOT EXISTS (
+                    SELECT 1 FROM model_training_data d
+                    WHERE d.mint_address = t.mint_address AND d.schema_version = ?1
+                )
+            ORDER BY t.id
+            LIMIT ?3
+            "#,
+        )
+        .bind(schema_version)
+        .bind(launched_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn store_training_data_batch(&self, rows: &[ModelTrainingData]) -> Result<u64> {
+        let mut inserted = 0;
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            inserted += sqlx::query(
+                r#"
+                INSERT INTO model_training_data (
+                    mint_address,
+                    age_secs,
+                    schema_version,
+                    features,
+                    outcome,
+                    outcome_label,
+                    peak_multiple,
+                    max_drawdown,
+                    created_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT (mint_address, age_secs, schema_version) DO NOTHING
+                "#,
+            )
+            .bind(&row.mint_address)
+            .bind(row.age_secs)
+            .bind(row.schema_version)
+            .bind(serde_json::to_string(&row.features)?)
+            .bind(row.outcome)
+            .bind(&row.outcome_label)
+            .bind(row.peak_multiple)
+            .bind(row.max_drawdown)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store training data: {:?}", e))?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn get_training_data(&self, schema_version: i32) -> Result<Vec<ModelTrainingData>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id,
+                mint_address,
+                age_secs,
+                schema_version,
+                features,
+                outcome,
+                outcome_label,
+                peak_multiple,
+                max_drawdown,
+                created_at
+            FROM model_training_data
+            WHERE schema_version = ?1
+            ORDER BY id
+            "#,
+        )
+        .bind(schema_version)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(training_data_from_row).collect()
+    }
+
+    async fn sync_creators(&self, since: DateTime<Utc>) -> Result<u64> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        // Outcomes never change once reached, and the first sell is kept even
+        // after the trade behind it has been rolled up. A rug the rug check
+        // itself flagged isn't an outcome: counting it would feed the verdict
+        // back into the creator's next check.
+        let mut creators: Vec<String> = sqlx::query_scalar(
+            r#"
+            INSERT INTO creator_launches (mint_address, creator_address, launched_at, outcome, ended_at, first_sell_at, updated_at)
+            SELECT
+                t.mint_address,
+                t.creator_address,
+                COALESCE(t.created_at, t.status_changed_at),
+                t.outcome,
+                CASE WHEN t.outcome IS NOT NULL THEN t.status_changed_at END,
+                (
+                    SELECT MIN(s.traded_at) FROM trades s
+                    WHERE s.mint_address = t.mint_address AND s.trader = t.creator_address AND NOT s.is_buy
+                ),
+                ?2
+            FROM (
+                SELECT
+                    mint_address,
+                    creator_address,
+                    created_at,
+                    status_changed_at,
+                    CASE
+                        WHEN status IN ('graduated', 'abandoned') THEN status
+                        WHEN status = 'rugged' AND NOT EXISTS (
+                            SELECT 1 FROM rug_verdicts v
+                            WHERE v.mint_address = t.mint_address AND v.status = 'rug' AND v.checked_at <= t.status_changed_at
+                        ) THEN status
+                    END AS outcome
+                FROM tokens t
+                WHERE creator_address IS NOT NULL
+                    AND (
+                        status_changed_at >= ?1
+                        OR (mint_address, creator_address) IN (
+                            SELECT s.mint_address, s.trader FROM trades s
+                            WHERE s.traded_at >= ?1 AND NOT s.is_buy
+                        )
+                    )
+            ) t
+            WHERE true
+            ON CONFLICT (mint_address) DO UPDATE SET
+                outcome = excluded.outcome,
+                ended_at = excluded.ended_at,
+                first_sell_at = COALESCE(creator_launches.first_sell_at, excluded.first_sell_at),
+                updated_at = excluded.updated_at
+            RETURNING creator_address
+            "#,
+        )
+        .bind(since)
+        .bind(now)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to sync creator launches: {:?}", e))?;
+        creators.sort_unstable();
+        creators.dedup();
+
+        let mut updated = 0;
+        for creator in &creators {
+            updated += sqlx::query(
+                r#"
+                INSERT INTO creators (
+                    address,
+                    launch_count,
+                    graduated_count,
+                    rugged_count,
+                    abandoned_count,
+                    avg_lifetime_secs,
+                    sold_count,
+                    avg_first_sell_secs,
+                    first_launch_at,
+                    last_launch_at,
+                    updated_at
+                )
+                SELECT
+                    creator_address,
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE outcome = 'graduated'),
+                    COUNT(*) FILTER (WHERE outcome = 'rugged'),
+                    COUNT(*) FILTER (WHERE outcome = 'abandoned'),
+                    AVG(MAX((julianday(ended_at) - julianday(launched_at)) * 86400.0, 0)),
+                    COUNT(first_sell_at),
+                    AVG(MAX((julianday(first_sell_at) - julianday(launched_at)) * 86400.0, 0)),
+                    MIN(launched_at),
+                    MAX(launched_at),
+                    ?2
+                FROM creator_launches
+                WHERE creator_address = ?1
+                GROUP BY creator_address
+                ON CONFLICT (address) DO UPDATE SET
+                    launch_count = excluded.launch_count,
+                    graduated_count = excluded.graduated_count,
+                    rugged_count = excluded.rugged_count,
+                    abandoned_count = excluded.abandoned_count,
+                    avg_lifetime_secs = excluded.avg_lifetime_secs,
+                    sold_count = excluded.sold_count,
+                    avg_first_sell_secs = excluded.avg_first_sell_secs,
+                    first_launch_at = excluded.first_launch_at,
+                    last_launch_at = excluded.last_launch_at,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(creator)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to update creator {}: {:?}", creator, e))?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn get_creator(&self, address: &str) -> Result<Option<CreatorRecord>> {
+        let creator = sqlx::query_as::<_, CreatorRecord>(
+            r#"
+            SELECT
+                address,
+                launch_count,
+                graduated_count,
+                rugged_count,
+                abandoned_count,
+                avg_lifetime_secs,
+                sold_count,
+                avg_first_sell_secs,
+                first_launch_at,
+                last_launch_at,
+                funding_source,
+                funded_at,
+                funding_checked_at,
+                updated_at
+            FROM creators
+            WHERE address = ?1
+            "#,
+        )
+        .bind(address)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(creator)
+    }
+
+    async fn get_creators_without_funding(&self, limit: i64) -> Result<Vec<String>> {
+        let creators = sqlx::query_scalar(
+            r#"
+            SELECT address FROM creators
+            WHERE funding_checked_at IS NULL
+            ORDER BY last_launch_at DESC
+            LIMIT ?1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(creators)
+    }
+
+    async fn set_creator_funding(&self, address: &str, funding_source: Option<&str>, funded_at: Option<DateTime<Utc>>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE creators
+            SET funding_source = ?2, funded_at = ?3, funding_checked_at = ?4
+            WHERE address = ?1
+            "#,
+        )
+        .bind(address)
+        .bind(funding_source)
+        .bind(funded_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_wallet_funding(&self, wallet: &str) -> Result<Option<WalletFunding>> {
+        let funding = sqlx::query_as::<_, WalletFunding>(
+            r#"
+            SELECT wallet, funder, funded_at, checked_at
+            FROM wallet_funding
+            WHERE wallet = ?1
+            "#,
+        )
+        .bind(wallet)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(funding)
+    }
+
+    async fn store_wallet_funding(&self, funding: &WalletFunding) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_funding (wallet, funder, funded_at, checked_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (wallet) DO UPDATE SET
+                funder = excluded.funder,
+                funded_at = excluded.funded_at,
+                checked_at = excluded.checked_at
+            "#,
+        )
+        .bind(&funding.wallet)
+        .bind(&funding.funder)
+        .bind(funding.funded_at)
+        .bind(funding.checked_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store funding of {}: {:?}", funding.wallet, e))?;
+        Ok(())
+    }
+
+    async fn get_funded_wallets(&self, funder: &str) -> Result<Vec<String>> {
+        let wallets = sqlx::query_scalar(
+            r#"
+            SELECT wallet FROM wallet_funding
+            WHERE funder = ?1
+            ORDER BY funded_at
+            "#,
+        )
+        .bind(funder)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(wallets)
+    }
+
+    async fn store_token_metadata(&self, metadata: &OffchainMetadata) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO token_metadata (
+                mint_address,
+                uri,
+                name,
+                symbol,
+                description,
+                description_hash,
+                image,
+                image_hash,
+                twitter,
+                telegram,
+                website,
+                metadata_score,
+                fetch_error,
+                fetched_at
+            )
+            SELECT mint_address, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14
+            FROM tokens
+            WHERE mint_address = ?1
+            ON CONFLICT (mint_address) DO UPDATE SET
+                uri = excluded.uri,
+                name = excluded.name,
+                symbol = excluded.symbol,
+                description = excluded.description,
+                description_hash = excluded.description_hash,
+                image = excluded.image,
+                image_hash = excluded.image_hash,
+                twitter = excluded.twitter,
+                telegram = excluded.telegram,
+                website = excluded.website,
+                metadata_score = excluded.metadata_score,
+                fetch_error = excluded.fetch_error,
+                fetched_at = excluded.fetched_at
+            "#,
+        )
+        .bind(&metadata.mint_address)
+        .bind(&metadata.uri)
+        .bind(&metadata.name)
+        .bind(&metadata.symbol)
+        .bind(&metadata.description)
+        .bind(metadata.description_hash)
+        .bind(&metadata.image)
+        .bind(metadata.image_hash)
+        .bind(&metadata.twitter)
+        .bind(&metadata.telegram)
+        .bind(&metadata.website)
+        .bind(metadata.metadata_score)
+        .bind(&metadata.fetch_error)
+        .bind(metadata.fetched_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store metadata of {}: {:?}", metadata.mint_address, e))?;
+        Ok(())
+    }
+
+    async fn get_token_metadata(&self, mint_address: &str) -> Result<Option<OffchainMetadata>> {
+        let metadata = sqlx::query_as::<_, OffchainMetadata>(
+            r#"
+            SELECT mint_address, uri, name, symbol, description, description_hash, image, image_hash, twitter, telegram,
+                website, metadata_score, fetch_error, fetched_at
+            FROM token_metadata
+            WHERE mint_address = ?1
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(metadata)
+    }
+
+    async fn count_metadata_reuse(&self, mint_address: &str, image: Option<&str>, description_hash: Option<i64>) -> Result<MetadataReuse> {
+        let reuse = sqlx::query_as::<_, MetadataReuse>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE image = ?2) AS image_count,
+                COUNT(*) FILTER (WHERE description_hash = ?3) AS description_count
+            FROM token_metadata
+            WHERE mint_address <> ?1 AND (image = ?2 OR description_hash = ?3)
+            "#,
+        )
+        .bind(mint_address)
+        .bind(image)
+        .bind(description_hash)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(reuse)
+    }
+
+    async fn store_token_copy(&self, copy: &TokenCopy) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO token_copies (mint_address, original_mint, matched_on, detected_at)
+            SELECT mint_address, ?2, ?3, ?4
+            FROM tokens
+            WHERE mint_address = ?1
+            ON CONFLICT (mint_address) DO NOTHING
+            "#,
+        )
+        .bind(&copy.mint_address)
+        .bind(&copy.original_mint)
+        .bind(&copy.matched_on)
+        .bind(copy.detected_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store copy of {}: {:?}", copy.mint_address, e))?;
+        Ok(())
+    }
+
+    async fn get_token_copy(&self, mint_address: &str) -> Result<Option<TokenCopy>> {
+        let copy = sqlx::query_as::<_, TokenCopy>(
+            r#"
+            SELECT mint_address, original_mint, matched_on, detected_at
+            FROM token_copies
+            WHERE mint_address = ?1
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(copy)
+    }
+
+    async fn count_clones(&self, original_mint: &str, detected_by: DateTime<Utc>) -> Result<i64> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM token_copies WHERE original_mint = ?1 AND detected_at <= ?2")
+            .bind(original_mint)
+            .bind(detected_by)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn get_launch_fingerprints(&self, since: DateTime<Utc>) -> Result<Vec<LaunchFingerprint>> {
+        let launches = sqlx::query_as::<_, LaunchFingerprint>(
+            r#"
+            SELECT
+                t.mint_address,
+                m.name,
+                t.symbol,
+                m.image_hash,
+                t.created_at,
+                c.original_mint AS copy_of
+            FROM tokens t
+            LEFT JOIN token_metadata m ON m.mint_address = t.mint_address
+            LEFT JOIN token_copies c ON c.mint_address = t.mint_address
+            WHERE t.created_at >= ?1
+            ORDER BY t.created_at, t.id
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(launches)
+    }
+
+    async fn rollup_trades(&self, older_than: DateTime<Utc>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        for interval in CandleInterval::ALL {
+            // Buckets are formatted the way sqlx writes `DateTime<Utc>` so
+            // they compare equal to candles written by the aggregator.
+            sqlx::query(
+                r#"
+                INSERT INTO candles (
+                    mint_address,
+                    resolution,
+                    bucket_start,
+                    open,
+                    high,
+                    low,
+                    close,
+                    sol_volume,
+                    token_volume,
+                    trade_count
+                )
+                SELECT
+                    mint_address,
+                    ?2,
+                    strftime('%Y-%m-%dT%H:%M:%S+00:00', bucket, 'unixepoch'),
+                    open,
+                    MAX(price),
+                    MIN(price),
+                    close,
+                    SUM(sol_amount),
+                    SUM(token_amount),
+                    COUNT(*)
+                FROM (
+                    SELECT
+                        mint_address,
+                        bucket,
+                        price,
+                        sol_amount,
+                        token_amount,
+                        FIRST_VALUE(price) OVER (PARTITION BY mint_address, bucket ORDER BY traded_at, id) AS open,
+                        FIRST_VALUE(price) OVER (PARTITION BY mint_address, bucket ORDER BY traded_at DESC, id DESC) AS close
+                    FROM (
+                        SELECT
+                            id,
+                            mint_address,
+                            traded_at,
+                            sol_amount,
+                            token_amount,
+                            (CAST(strftime('%s', traded_at) AS INTEGER) / ?3) * ?3 AS bucket,
+                            CAST(sol_amount AS REAL) / token_amount / 1000.0 AS price
+                        FROM trades
+                        WHERE traded_at < ?1 AND token_amount > 0
+                    )
+                )
+                WHERE true
+                GROUP BY mint_address, bucket
+                ON CONFLICT (mint_address, resolution, bucket_start) DO NOTHING
+                "#,
+            )
+            .bind(older_than)
+            .bind(interval.as_str())
+            .bind(interval.seconds())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to roll up {} candles: {:?}", interval, e))?;
+        }
+
+        let deleted = sqlx::query(
+            r#"
+            DELETE FROM trades
+            WHERE traded_at < ?1
+            "#,
+        )
+        .bind(older_than)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    async fn get_trades(&self, mint_address: &str) -> Result<Vec<Trade>> {
+        let trades = sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT
+                signature,
+                event_index,
+                slot,
+                mint_address,
+                trader,
+                is_buy,
+                sol_amount,
+                token_amount,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                traded_at,
+                venue
+            FROM trades
+            WHERE mint_address = ?1
+            ORDER BY traded_at, id
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(trades)
+    }
+
+    async fn get_token_metrics(&self, mint_address: &str) -> Result<Vec<TokenMetrics>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                t.mint_address,
+                m.sampled_at,
+                m.token_age_secs,
+                m.bonding_percent,
+                m.ilv,
+                m.safety_score,
+                m.liquidity,
+                m.holders,
+                m.volume,
+                m.buy_volume,
+                m.sell_volume,
+                m.organic_volume,
+                m.top_trader_share
+            FROM token_metrics m
+            JOIN tokens t ON t.id = m.token_id
+            WHERE t.mint_address = ?1
+            ORDER BY m.sampled_at
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(token_metrics_from_row).collect()
+    }
+
+    async fn get_expired_tokens(&self, status: TokenStatus, older_than: DateTime<Utc>, limit: i64) -> Result<Vec<TokenRecord>> {
+        let tokens = sqlx::query_as::<_, TokenRecord>(
+            r#"
+            SELECT id, mint_address, creator_address, created_at, symbol, bonding_address, status, status_changed_at
+            FROM tokens
+            WHERE status = ?1 AND status_changed_at < ?2
+            ORDER BY status_changed_at
+            LIMIT ?3
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(older_than)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn delete_tokens(&self, mint_addresses: &[String]) -> Result<u64> {
+        let mut deleted = 0;
+        let mut tx = self.pool.begin().await?;
+        for mint_address in mint_addresses {
+            sqlx::query("DELETE FROM trades WHERE mint_address = ?1")
+                .bind(mint_address)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM evaluation_failures WHERE mint_address = ?1")
+                .bind(mint_address)
+                .execute(&mut *tx)
+                .await?;
+            // Metrics and status history cascade.
+            deleted += sqlx::query("DELETE FROM tokens WHERE mint_address = ?1")
+                .bind(mint_address)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    async fn record_evaluation_failure(&self, mint_address: &str, stage: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO evaluation_failures (mint_address, stage, error, failed_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(mint_address)
+        .bind(stage)
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to record evaluation failure: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn get_evaluation_failures(&self, mint_address: &str) -> Result<Vec<EvaluationFailure>> {
+        let failures = sqlx::query_as::<_, EvaluationFailure>(
+            r#"
+            SELECT stage, error, failed_at
+            FROM evaluation_failures
+            WHERE mint_address = ?1
+            ORDER BY failed_at
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(failures)
+    }
+
+    async fn store_rug_verdict(&self, result: &RugCheckResult) -> Result<i64> {
+        let risk_factors = serde_json::to_value(&result.risk_factors)?;
+        let failures = serde_json::to_value(&result.failures)?;
+        let mut tx = self.pool.begin().await?;
+
+        let verdict_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO rug_verdicts (
+                mint_address,
+                checked_at,
+                status,
+                confidence,
+                risk_factors,
+                failures
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            RETURNING id
+            "#,
+        )
+        .bind(&result.mint_address)
+        .bind(result.checked_at)
+        .bind(result.token_status.as_str())
+        .bind(result.confidence)
+        .bind(risk_factors)
+        .bind(failures)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to store rug verdict: {:?}", e))?;
+
+        for response in &result.responses {
+            sqlx::query(
+                r#"
+                INSERT INTO provider_responses (
+                    verdict_id,
+                    mint_address,
+                    provider,
+                    http_status,
+                    latency_ms,
+                    body,
+                    error,
+                    fetched_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+            )
+            .bind(verdict_id)
+            .bind(&result.mint_address)
+            .bind(response.source.name())
+            .bind(response.http_status.map(i32::from))
+            .bind(response.latency_ms as i32)
+            .bind(&response.body)
+            .bind(&response.error)
+            .bind(result.checked_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store provider response: {:?}", e))?;
+        }
+
+        tx.commit().await?;
+        Ok(verdict_id)
+    }
+
+    async fn get_rug_verdicts(&self, mint_address: &str) -> Result<Vec<RugVerdictRecord>> {
+        let verdicts = sqlx::query_as::<_, RugVerdictRecord>(
+            r#"
+            SELECT id, mint_address, checked_at, status, confidence, risk_factors, failures
+            FROM rug_verdicts
+            WHERE mint_address = ?1
+            ORDER BY checked_at DESC
+            "#,
+        )
+        .bind(mint_address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(verdicts)
+    }
+
+    async fn get_rug_verdicts_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<RugVerdictRecord>> {
+        let verdicts = sqlx::query_as::<_, RugVerdictRecord>(
+            r#"
+            SELECT id, mint_address, checked_at, status, confidence, risk_factors, failures
+            FROM rug_verdicts
+            WHERE checked_at >= ?1 AND checked_at < ?2
+            ORDER BY checked_at
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(verdicts)
+    }
+
+    async fn get_provider_responses(&self, verdict_id: i64) -> Result<Vec<ProviderResponseRecord>> {
+        let responses = sqlx::query_as::<_, ProviderResponseRecord>(
+            r#"
+            SELECT provider, http_status, latency_ms, body, error, fetched_at
+            FROM provider_responses
+            WHERE verdict_id = ?1
+            ORDER BY provider
+            "#,
+        )
+        .bind(verdict_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(responses)
+    }
+}
+
+// SQLite has no array parameters, so batches are written row by row inside a
+// single transaction, which is where almost all of the per-row cost goes.
+
+async fn insert_token_info(conn: &mut SqliteConnection, token_info: &TokenInfo, now: DateTime<Utc>) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO tokens (
+            mint_address,
+            creator_address,
+            created_at,
+            symbol,
+            bonding_address,
+            status_changed_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT (mint_address) DO UPDATE SET
+            creator_address = excluded.creator_address,
+            symbol = excluded.symbol,
+            bonding_address = excluded.bonding_address
+        "#,
+    )
+    .bind(&token_info.mint_address)
+    .bind(&token_info.creator_address)
+    .bind(token_info.created_at)
+    .bind(&token_info.symbol)
+    .bind(&token_info.bonding_address)
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to store token info: {:?}", e))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO token_status_history (mint_address, from_status, to_status, reason, changed_at)
+        SELECT ?1, NULL, ?2, 'launch detected', ?3
+        WHERE NOT EXISTS (
+            SELECT 1 FROM token_status_history WHERE mint_address = ?1
+        )
+        "#,
+    )
+    .bind(&token_info.mint_address)
+    .bind(TokenStatus::Detected.as_str())
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to record token detection: {:?}", e))?;
+    Ok(())
+}
+
+async fn insert_token_metrics(conn: &mut SqliteConnection, token_metric: &TokenMetrics, now: DateTime<Utc>) -> Result<u64> {
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO token_metrics (
+            token_id,
+            recorded_at,
+            sampled_at,
+            token_age_secs,
+            bonding_percent,
+            ilv,
+            safety_score,
+            liquidity,
+            holders,
+            volume,
+            buy_volume,
+            sell_volume,
+            organic_volume,
+            top_trader_share
+        )
+        SELECT id, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14
+        FROM tokens
+        WHERE mint_address = ?1
+        "#,
+    )
+    .bind(&token_metric.mint_address)
+    .bind(now)
+    .bind(token_metric.sampled_at)
+    .bind(token_metric.token_age_secs)
+    .bind(token_metric.bonding_percent)
+    .bind(token_metric.ilv)
+    .bind(token_metric.safety_score)
+    .bind(token_metric.liquidity)
+    .bind(token_metric.holders)
+    .bind(token_metric.volume.as_ref().map(|v| v.to_string()))
+    .bind(token_metric.buy_volume.as_ref().map(|v| v.to_string()))
+    .bind(token_metric.sell_volume.as_ref().map(|v| v.to_string()))
+    .bind(token_metric.organic_volume.as_ref().map(|v| v.to_string()))
+    .bind(token_metric.top_trader_share)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to store token metrics: {:?}", e))?
+    .rows_affected();
+    Ok(inserted)
+}
+
+async fn insert_trade(conn: &mut SqliteConnection, trade: &Trade) -> Result<u64> {
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO trades (
+            signature,
+            event_index,
+            slot,
+            mint_address,
+            trader,
+            is_buy,
+            sol_amount,
+            token_amount,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            traded_at,
+            venue
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        ON CONFLICT (signature, event_index) DO NOTHING
+        "#,
+    )
+    .bind(&trade.signature)
+    .bind(trade.event_index)
+    .bind(trade.slot)
+    .bind(&trade.mint_address)
+    .bind(&trade.trader)
+    .bind(trade.is_buy)
+    .bind(trade.sol_amount)
+    .bind(trade.token_amount)
+    .bind(trade.virtual_sol_reserves)
+    .bind(trade.virtual_token_reserves)
+    .bind(trade.traded_at)
+    .bind(&trade.venue)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to store trade: {:?}", e))?
+    .rows_affected();
+    Ok(inserted)
+}
+
+// Volumes are stored as text since SQLite has no decimal type.
+fn token_metrics_from_row(row: &SqliteRow) -> Result<TokenMetrics> {
+    let decimal = |column: &str| -> Result<Option<BigDecimal>> {
+        let value: Option<String> = row.try_get(column)?;
+        Ok(value.map(|v| BigDecimal::from_str(&v)).transpose()?)
+    };
+    Ok(TokenMetrics {
+        mint_address: row.try_get("mint_address")?,
+        sampled_at: row.try_get("sampled_at")?,
+        token_age_secs: row.try_get("token_age_secs")?,
+        bonding_percent: row.try_get("bonding_percent")?,
+        ilv: row.try_get("ilv")?,
+        safety_score: row.try_get("safety_score")?,
+        liquidity: row.try_get("liquidity")?,
+        holders: row.try_get("holders")?,
+        volume: decimal("volume")?,
+        buy_volume: decimal("buy_volume")?,
+        sell_volume: decimal("sell_volume")?,
+        organic_volume: decimal("organic_volume")?,
+        top_trader_share: row.try_get("top_trader_share")?,
+    })
+}
+
+fn token_analysis_from_row(row: &SqliteRow) -> Result<TokenAnalysis> {
+    let temporal_features: String = row.try_get("temporal_features")?;
+    Ok(TokenAnalysis {
+        mint_address: row.try_get("mint_address")?,
+        age_secs: row.try_get("age_secs")?,
+        bonding_curve_progress: row.try_get("bonding_curve_progress")?,
+        ilv: row.try_get("ilv")?,
+        metadata_score: row.try_get("metadata_score")?,
+        creator_age: row.try_get("creator_age")?,
+        sell_pressure: row.try_get("sell_pressure")?,
+        holder_count: row.try_get("holder_count")?,
+        top10_holder_share: row.try_get("top10_holder_share")?,
+        clone_count: row.try_get("clone_count")?,
+        temporal_features: serde_json::from_str(&temporal_features)?,
+        risk_score: row.try_get("risk_score")?,
+    })
+}
+
+fn training_data_from_row(row: &SqliteRow) -> Result<ModelTrainingData> {
+    let features: String = row.try_get("features")?;
+    Ok(ModelTrainingData {
+        id: row.try_get("id")?,
+        mint_address: row.try_get("mint_address")?,
+        age_secs: row.try_get("age_secs")?,
+        schema_version: row.try_get("schema_version")?,
+        features: serde_json::from_str(&features)?,
+        outcome: row.try_get("outcome")?,
+        outcome_label: row.try_get("outcome_label")?,
+        peak_multiple: row.try_get("peak_multiple")?,
+        max_drawdown: row.try_get("max_drawdown")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::managers::store::suite};
+
+    #[tokio::test]
+    async fn passes_the_store_suite() {
+        let store = SqliteStore::connect("sqlite::memory:").await.unwrap();
+        suite::run(&store).await;
+    }
+}
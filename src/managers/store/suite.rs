@@ -0,0 +1,599 @@
+//! Behaviour every `TokenStore` backend must share, run by each backend's
+//! tests. Mint addresses are unique per run so the suite can share a
+//! Postgres database with earlier runs.
+
+use {
+    super::TokenStore,
+    crate::{
+        models::{
+            candle::{Candle, CandleInterval},
+            copycat::{CopyMatch, TokenCopy},
+            token::{ModelTrainingData, TokenAnalysis, TokenInfo, TokenMetrics, TokenStatus},
+            trade::Trade,
+        },
+        providers::Finding,
+        utils::rug_check::{
+            LiquidityAnalysis, ProviderResponse, RiskFactor, RiskSource, RugCheckResult, RugStatus, SourceFailure,
+        },
+    },
+    chrono::{DateTime, Duration, SubsecRound, Utc},
+    serde_json::json,
+    sqlx::types::BigDecimal,
+};
+
+/// Runs every check against `store`.
+pub async fn run(store: &dyn TokenStore) {
+    token_info(store).await;
+    status_transitions(store).await;
+    metrics(store).await;
+    trades(store).await;
+    candles(store).await;
+    trade_rollup(store).await;
+    verdicts(store).await;
+    training_rows(store).await;
+    unlabelled_tokens(store).await;
+    creators(store).await;
+    copies(store).await;
+}
+
+fn unique_mint(label: &str) -> String {
+    format!("{label}-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default())
+}
+
+/// Now, truncated to what every backend stores exactly.
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(0)
+}
+
+async fn store_token(store: &dyn TokenStore, label: &str) -> String {
+    let mint_address = unique_mint(label);
+    store
+        .store_token_info(&TokenInfo {
+            mint_address: mint_address.clone(),
+            creator_address: Some(format!("{mint_address}-creator")),
+            created_at: Some(now()),
+            symbol: Some("TEST".to_string()),
+            bonding_address: Some(format!("{mint_address}-curve")),
+            ..TokenInfo::default()
+        })
+        .await
+        .unwrap();
+    mint_address
+}
+
+async fn token_info(store: &dyn TokenStore) {
+    let launched_at = now();
+    let mint_address = unique_mint("info");
+    let creator = format!("{mint_address}-creator");
+    let info = TokenInfo {
+        mint_address: mint_address.clone(),
+        creator_address: Some(creator.clone()),
+        created_at: Some(launched_at),
+        symbol: Some("FIRST".to_string()),
+        bonding_address: Some(format!("{mint_address}-curve")),
+        ..TokenInfo::default()
+    };
+    store.store_token_info(&info).await.unwrap();
+    store
+        .store_token_info(&TokenInfo { symbol: Some("SECOND".to_string()), ..info })
+        .await
+        .unwrap();
+
+    let token = store.get_token(&mint_address).await.unwrap().expect("token stored");
+    assert_eq!(token.creator_address.as_deref(), Some(creator.as_str()));
+    assert_eq!(token.created_at, Some(launched_at));
+    assert_eq!(token.symbol.as_deref(), Some("SECOND"));
+    assert_eq!(token.status().unwrap(), TokenStatus::Detected);
+
+    let by_creator = store.get_tokens_by_creator(&creator).await.unwrap();
+    assert_eq!(by_creator.len(), 1);
+    assert_eq!(by_creator[0].mint_address, mint_address);
+
+    let batch = [unique_mint("batch-a"), unique_mint("batch-b")];
+    let infos: Vec<TokenInfo> = batch
+        .iter()
+        .map(|mint_address| TokenInfo { mint_address: mint_address.clone(), ..TokenInfo::default() })
+        .collect();
+    store.store_token_info_batch(&infos).await.unwrap();
+    for mint_address in &batch {
+        assert!(store.get_token(mint_address).await.unwrap().is_some());
+    }
+
+    assert!(store.get_token(&unique_mint("missing")).await.unwrap().is_none());
+}
+
+async fn status_transitions(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "status").await;
+    let active = |tokens: Vec<crate::managers::db_manager::MintAddress>| {
+        tokens.iter().any(|token| token.mint_address == mint_address)
+    };
+    assert!(active(store.get_active_tokens().await.unwrap()));
+
+    assert!(store
+        .transition_token_status(&mint_address, TokenStatus::Monitoring, "watching")
+        .await
+        .unwrap());
+    assert!(!store
+        .transition_token_status(&mint_address, TokenStatus::Detected, "backwards")
+        .await
+        .unwrap());
+    assert!(store
+        .transition_token_status(&mint_address, TokenStatus::Filtered, "too slow")
+        .await
+        .unwrap());
+    assert!(!active(store.get_active_tokens().await.unwrap()));
+    assert!(active(store.get_tokens_by_status(TokenStatus::Filtered).await.unwrap()));
+
+    assert!(store
+        .transition_token_status(&mint_address, TokenStatus::Rugged, "liquidity pulled")
+        .await
+        .unwrap());
+    assert!(!store
+        .transition_token_status(&mint_address, TokenStatus::Graduated, "after the fact")
+        .await
+        .unwrap());
+    assert!(!store
+        .transition_token_status(&unique_mint("missing"), TokenStatus::Monitoring, "unknown")
+        .await
+        .unwrap());
+
+    let token = store.get_token(&mint_address).await.unwrap().unwrap();
+    assert_eq!(token.status().unwrap(), TokenStatus::Rugged);
+
+    let history: Vec<_> = store
+        .get_status_history(&mint_address)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|change| (change.from_status, change.to_status, change.reason))
+        .collect();
+    let change = |from: Option<&str>, to: &str, reason: &str| {
+        (from.map(str::to_string), to.to_string(), reason.to_string())
+    };
+    assert_eq!(
+        history[1..],
+        [
+            change(Some("detected"), "monitoring", "watching"),
+            change(Some("monitoring"), "filtered", "too slow"),
+            change(Some("filtered"), "rugged", "liquidity pulled"),
+        ]
+    );
+    assert_eq!(history[0].1, "detected");
+
+    let expired = store
+        .get_expired_tokens(TokenStatus::Rugged, Utc::now() + Duration::minutes(1), i64::MAX)
+        .await
+        .unwrap();
+    assert!(expired.iter().any(|token| token.mint_address == mint_address));
+    assert_eq!(store.delete_tokens(std::slice::from_ref(&mint_address)).await.unwrap(), 1);
+    assert!(store.get_token(&mint_address).await.unwrap().is_none());
+    assert!(store.get_status_history(&mint_address).await.unwrap().is_empty());
+}
+
+fn sample(mint_address: &str, token_age_secs: i64, sampled_at: DateTime<Utc>) -> TokenMetrics {
+    TokenMetrics {
+        mint_address: mint_address.to_string(),
+        sampled_at,
+        token_age_secs,
+        bonding_percent: 12.5,
+        ilv: 0.75,
+        safety_score: 80.0,
+        liquidity: 3.5,
+        holders: 42,
+        volume: Some(BigDecimal::from(5)),
+        buy_volume: Some(BigDecimal::from(3)),
+        sell_volume: Some(BigDecimal::from(2)),
+        organic_volume: Some(BigDecimal::from(4)),
+        top_trader_share: Some(25.0),
+    }
+}
+
+async fn metrics(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "metrics").await;
+    let sampled_at = now();
+    store.store_token_metrics(&sample(&mint_address, 10, sampled_at)).await.unwrap();
+    let written = store
+        .store_token_metrics_batch(&[
+            sample(&mint_address, 60, sampled_at + Duration::seconds(50)),
+            sample(&unique_mint("missing"), 60, sampled_at),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(written, 1);
+    assert!(store.store_token_metrics(&sample(&unique_mint("missing"), 10, sampled_at)).await.is_err());
+
+    let samples = store.get_token_metrics(&mint_address).await.unwrap();
+    assert_eq!(samples.iter().map(|sample| sample.token_age_secs).collect::<Vec<_>>(), [10, 60]);
+    let first = &samples[0];
+    assert_eq!(first.sampled_at, sampled_at);
+    assert_eq!(first.holders, 42);
+    assert_eq!(first.bonding_percent, 12.5);
+    assert_eq!(first.volume, Some(BigDecimal::from(5)));
+    assert_eq!(first.organic_volume, Some(BigDecimal::from(4)));
+    assert_eq!(first.top_trader_share, Some(25.0));
+
+    let analysis = TokenAnalysis {
+        mint_address: mint_address.clone(),
+        age_secs: 60,
+        bonding_curve_progress: 12.5,
+        ilv: 0.75,
+        metadata_score: 0.5,
+        creator_age: 3600,
+        sell_pressure: 0.4,
+        holder_count: 42,
+        top10_holder_share: 0.6,
+        clone_count: 2,
+        temporal_features: vec![1.0, 2.0, 3.0],
+        risk_score: 0.1,
+    };
+    store.store_token_analysis(&analysis).await.unwrap();
+    store
+        .store_token_analysis(&TokenAnalysis { holder_count: 43, ..analysis.clone() })
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get_token_analyses(&mint_address).await.unwrap(),
+        [TokenAnalysis { holder_count: 43, ..analysis }]
+    );
+}
+
+fn trade(mint_address: &str, signature: &str, is_buy: bool, sol_amount: i64, traded_at: DateTime<Utc>) -> Trade {
+    let trade = Trade::test(mint_address)
+        .signature(signature)
+        .by(&format!("{signature}-trader"))
+        .at(traded_at);
+    if is_buy {
+        trade.buy(sol_amount, sol_amount * 1_000)
+    } else {
+        trade.sell(sol_amount, sol_amount * 1_000)
+    }
+}
+
+async fn trades(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "trades").await;
+    let traded_at = now();
+    let buy = trade(&mint_address, &format!("{mint_address}-buy"), true, 2_000, traded_at);
+    store.store_trade(&buy).await.unwrap();
+    store.store_trade(&buy).await.unwrap();
+    let stored = store
+        .store_trade_batch(&[
+            buy.clone(),
+            trade(&mint_address, &format!("{mint_address}-sell"), false, 500, traded_at + Duration::seconds(5)),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    let trades = store.get_trades(&mint_address).await.unwrap();
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].signature, buy.signature);
+    assert_eq!(trades[0].traded_at, traded_at);
+    assert_eq!(trades[0].token_amount, 2_000_000);
+    assert!(!trades[1].is_buy);
+}
+
+async fn candles(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "candles").await;
+    let start = now();
+    let minute = |n: i64| start + Duration::minutes(n);
+    let mut first = Candle::open_at(&mint_address, CandleInterval::OneMinute, minute(0), 1.0);
+    first.apply(2.0, 100, 50);
+    store
+        .store_candle_batch(&[
+            first.clone(),
+            Candle::open_at(&mint_address, CandleInterval::OneMinute, minute(1), 2.0),
+            Candle::open_at(&mint_address, CandleInterval::OneMinute, minute(2), 3.0),
+            Candle::open_at(&mint_address, CandleInterval::FiveMinutes, minute(0), 1.0),
+        ])
+        .await
+        .unwrap();
+    // A candle written again as its bucket fills replaces the earlier one.
+    first.apply(0.5, 10, 20);
+    store.store_candle_batch(&[first]).await.unwrap();
+
+    let candles = store
+        .get_candles(&mint_address, CandleInterval::OneMinute, minute(0), minute(2))
+        .await
+        .unwrap();
+    let starts: Vec<DateTime<Utc>> = candles.iter().map(|candle| candle.bucket_start).collect();
+    assert_eq!(starts, [minute(0), minute(1)]);
+    let first = &candles[0];
+    assert_eq!((first.open, first.high, first.low, first.close), (1.0, 2.0, 0.5, 0.5));
+    assert_eq!((first.sol_volume, first.token_volume, first.trade_count), (110, 70, 2));
+    assert_eq!(first.resolution, "1m");
+}
+
+async fn trade_rollup(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "rollup").await;
+    // Well before every other suite trade, so only this token's are rolled up.
+    let cutoff = CandleInterval::OneHour.bucket_start(now() - Duration::days(30));
+    let start = cutoff - Duration::hours(1);
+    let second = |n: i64| start + Duration::seconds(n);
+    // Prices are SOL per whole token: 1_000 base units for 1_000_000 lamports is 1.0.
+    let priced = |label: &str, lamports: i64, traded_at: DateTime<Utc>| {
+        trade(&mint_address, &format!("{mint_address}-{label}"), true, lamports, traded_at).buy(lamports, 1_000)
+    };
+    store
+        .store_trade_batch(&[
+            priced("a", 2_000_000, second(30)),
+            priced("b", 1_000_000, second(10)),
+            priced("c", 3_000_000, second(50)),
+            priced("d", 500_000, second(70)),
+            priced("kept", 1_000_000, cutoff + Duration::seconds(10)),
+        ])
+        .await
+        .unwrap();
+    // A candle the aggregator already wrote for the second minute is kept,
+    // which also checks that rolled-up buckets match its timestamps.
+    let written = Candle::open_at(&mint_address, CandleInterval::OneMinute, second(60), 9.0);
+    store.store_candle_batch(std::slice::from_ref(&written)).await.unwrap();
+
+    let deleted = store.rollup_trades(cutoff).await.unwrap();
+    assert!(deleted >= 4);
+
+    let minutes = store
+        .get_candles(&mint_address, CandleInterval::OneMinute, start, cutoff)
+        .await
+        .unwrap();
+    assert_eq!(minutes.len(), 2);
+    let first = &minutes[0];
+    assert_eq!(first.bucket_start, second(0));
+    assert_eq!((first.open, first.high, first.low, first.close), (1.0, 3.0, 1.0, 3.0));
+    assert_eq!((first.sol_volume, first.token_volume, first.trade_count), (6_000_000, 3_000, 3));
+    assert_eq!(minutes[1], written);
+
+    let hours = store
+        .get_candles(&mint_address, CandleInterval::OneHour, start, cutoff)
+        .await
+        .unwrap();
+    assert_eq!(hours.len(), 1);
+    assert_eq!((hours[0].open, hours[0].low, hours[0].close, hours[0].trade_count), (1.0, 0.5, 0.5, 4));
+
+    let remaining: Vec<String> = store
+        .get_trades(&mint_address)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|trade| trade.signature)
+        .collect();
+    assert_eq!(remaining, [format!("{mint_address}-kept")]);
+}
+
+async fn verdicts(store: &dyn TokenStore) {
+    let mint_address = unique_mint("verdict");
+    let checked_at = now();
+    let result = RugCheckResult {
+        mint_address: mint_address.clone(),
+        checked_at,
+        token_status: RugStatus::Rug,
+        risk_factors: vec![Finding::new(
+            RiskSource::OnChain,
+            RiskFactor::MintAuthorityEnabled,
+            json!("authority"),
+            json!({ "mint_authority": "authority" }),
+        )],
+        confidence: 60.0,
+        metadata: None,
+        liquidity: LiquidityAnalysis::default(),
+        failures: vec![SourceFailure { source: RiskSource::GoPlus, error: "HTTP 500".to_string() }],
+        responses: vec![
+            ProviderResponse {
+                source: RiskSource::OnChain,
+                http_status: None,
+                latency_ms: 12,
+                body: Some(json!({ "mint_authority": "authority" })),
+                error: None,
+            },
+            ProviderResponse {
+                source: RiskSource::GoPlus,
+                http_status: Some(500),
+                latency_ms: 80,
+                body: Some(json!({ "message": "down" })),
+                error: Some("HTTP 500".to_string()),
+            },
+        ],
+    };
+    let verdict_id = store.store_rug_verdict(&result).await.unwrap();
+
+    let verdicts = store.get_rug_verdicts(&mint_address).await.unwrap();
+    assert_eq!(verdicts.len(), 1);
+    let verdict = &verdicts[0];
+    assert_eq!(verdict.id, verdict_id);
+    assert_eq!(verdict.checked_at, checked_at);
+    assert_eq!(verdict.status, RugStatus::Rug.as_str());
+    assert_eq!(verdict.confidence, 60.0);
+    assert_eq!(verdict.risk_factors[0]["signal"], json!("MintAuthorityEnabled"));
+    assert_eq!(verdict.failures[0]["error"], json!("HTTP 500"));
+
+    let between = store
+        .get_rug_verdicts_between(checked_at, checked_at + Duration::seconds(1))
+        .await
+        .unwrap();
+    assert!(between.iter().any(|verdict| verdict.id == verdict_id));
+    let after = store
+        .get_rug_verdicts_between(checked_at + Duration::seconds(1), checked_at + Duration::seconds(2))
+        .await
+        .unwrap();
+    assert!(after.iter().all(|verdict| verdict.id != verdict_id));
+
+    let responses = store.get_provider_responses(verdict_id).await.unwrap();
+    assert_eq!(responses.len(), 2);
+    let goplus = responses.iter().find(|response| response.provider == RiskSource::GoPlus.name()).unwrap();
+    assert_eq!(goplus.http_status, Some(500));
+    assert_eq!(goplus.latency_ms, 80);
+    assert_eq!(goplus.body, Some(json!({ "message": "down" })));
+    assert_eq!(goplus.error.as_deref(), Some("HTTP 500"));
+    let onchain = responses.iter().find(|response| response.provider == RiskSource::OnChain.name()).unwrap();
+    assert_eq!(onchain.http_status, None);
+    assert_eq!(onchain.error, None);
+
+    let failed = store_token(store, "failure").await;
+    store.record_evaluation_failure(&failed, "rug_check", "timed out").await.unwrap();
+    let failures = store.get_evaluation_failures(&failed).await.unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!((failures[0].stage.as_str(), failures[0].error.as_str()), ("rug_check", "timed out"));
+}
+
+fn training_row(mint_address: &str, age_secs: i64, schema_version: i32) -> ModelTrainingData {
+    ModelTrainingData {
+        id: 0,
+        mint_address: mint_address.to_string(),
+        age_secs,
+        schema_version,
+        features: vec![0.5, 1.5, 2.5],
+        outcome: 1.0,
+        outcome_label: "graduated".to_string(),
+        peak_multiple: 3.0,
+        max_drawdown: 0.25,
+        created_at: Utc::now(),
+    }
+}
+
+async fn training_rows(store: &dyn TokenStore) {
+    let mint_address = unique_mint("training");
+    let stored = store
+        .store_training_data_batch(&[training_row(&mint_address, 10, 1), training_row(&mint_address, 60, 1)])
+        .await
+        .unwrap();
+    assert_eq!(stored, 2);
+    let stored = store
+        .store_training_data_batch(&[training_row(&mint_address, 10, 1), training_row(&mint_address, 10, 2)])
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    let rows: Vec<_> = store
+        .get_training_data(1)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|row| row.mint_address == mint_address)
+        .collect();
+    assert_eq!(rows.iter().map(|row| row.age_secs).collect::<Vec<_>>(), [10, 60]);
+    assert_eq!(rows[0].features, [0.5, 1.5, 2.5]);
+    assert_eq!(rows[0].outcome_label, "graduated");
+    assert_eq!((rows[0].peak_multiple, rows[0].max_drawdown), (3.0, 0.25));
+    assert!(rows[0].id < rows[1].id);
+}
+
+async fn unlabelled_tokens(store: &dyn TokenStore) {
+    let mint_address = store_token(store, "unlabelled").await;
+    store
+        .transition_token_status(&mint_address, TokenStatus::Rugged, "suite")
+        .await
+        .unwrap();
+    store
+        .store_token_analysis(&TokenAnalysis {
+            mint_address: mint_address.clone(),
+            age_secs: 10,
+            bonding_curve_progress: 1.0,
+            ilv: 0.1,
+            metadata_score: 0.0,
+            creator_age: 0,
+            sell_pressure: 0.0,
+            holder_count: 1,
+            top10_holder_share: 1.0,
+            clone_count: 0,
+            temporal_features: Vec::new(),
+            risk_score: 0.0,
+        })
+        .await
+        .unwrap();
+    // An outcome status alone doesn't make a token ready to label.
+    assert!(!is_unlabelled(store, &mint_address, now() - Duration::hours(1)).await);
+    assert!(is_unlabelled(store, &mint_address, now() + Duration::seconds(1)).await);
+
+    store
+        .store_training_data_batch(&[training_row(&mint_address, 10, 1)])
+        .await
+        .unwrap();
+    assert!(!is_unlabelled(store, &mint_address, now() + Duration::seconds(1)).await);
+}
+
+async fn is_unlabelled(store: &dyn TokenStore, mint_address: &str, launched_before: DateTime<Utc>) -> bool {
+    store
+        .get_unlabelled_tokens(1, launched_before, i64::MAX)
+        .await
+        .unwrap()
+        .iter()
+        .any(|token| token.mint_address == mint_address)
+}
+
+fn rug_verdict(mint_address: &str, checked_at: DateTime<Utc>) -> RugCheckResult {
+    RugCheckResult {
+        mint_address: mint_address.to_string(),
+        checked_at,
+        token_status: RugStatus::Rug,
+        risk_factors: vec![],
+        confidence: 80.0,
+        metadata: None,
+        liquidity: LiquidityAnalysis::default(),
+        failures: vec![],
+        responses: vec![],
+    }
+}
+
+async fn creators(store: &dyn TokenStore) {
+    let creator = unique_mint("creator");
+    let launched_at = now() - Duration::minutes(10);
+    let launch = |label: &str| {
+        let mint_address = format!("{creator}-{label}");
+        let token_info = TokenInfo {
+            mint_address: mint_address.clone(),
+            creator_address: Some(creator.clone()),
+            created_at: Some(launched_at),
+            ..TokenInfo::default()
+        };
+        async move {
+            store.store_token_info(&token_info).await.unwrap();
+            mint_address
+        }
+    };
+    let dumped = launch("dumped").await;
+    let flagged = launch("flagged").await;
+    let dumped_then_flagged = launch("late-verdict").await;
+    let graduated = launch("graduated").await;
+    let live = launch("live").await;
+
+    store.transition_token_status(&dumped, TokenStatus::Rugged, "creator sold 90% of their buys").await.unwrap();
+    // Rugged because the rug check said so: not evidence against the creator.
+    store.store_rug_verdict(&rug_verdict(&flagged, now() - Duration::minutes(1))).await.unwrap();
+    store.transition_token_status(&flagged, TokenStatus::Rugged, "rug check confidence 80").await.unwrap();
+    // A verdict after the dump doesn't undo it.
+    store.transition_token_status(&dumped_then_flagged, TokenStatus::Rugged, "creator sold 90% of their buys").await.unwrap();
+    store.store_rug_verdict(&rug_verdict(&dumped_then_flagged, now() + Duration::minutes(1))).await.unwrap();
+    store.transition_token_status(&graduated, TokenStatus::Graduated, "bonding curve complete").await.unwrap();
+    store
+        .store_trade(&trade(&live, &format!("{live}-dev-sell"), false, 1_000, launched_at + Duration::seconds(30)).by(&creator))
+        .await
+        .unwrap();
+
+    assert!(store.sync_creators(launched_at - Duration::minutes(1)).await.unwrap() >= 1);
+    let record = store.get_creator(&creator).await.unwrap().unwrap();
+    assert_eq!(record.launch_count, 5);
+    assert_eq!((record.graduated_count, record.rugged_count, record.abandoned_count), (1, 2, 0));
+    assert_eq!(record.sold_count, 1);
+    // SQLite computes it from Julian days, which aren't exact.
+    assert!(record.avg_first_sell_secs.is_some_and(|secs| (secs - 30.0).abs() < 0.01));
+    assert_eq!(record.first_launch_at, launched_at);
+}
+
+async fn copies(store: &dyn TokenStore) {
+    let original = store_token(store, "original").await;
+    let detected_at = now();
+    for (label, secs) in [("first-copy", 0), ("second-copy", 60)] {
+        let mint_address = store_token(store, label).await;
+        store
+            .store_token_copy(&TokenCopy {
+                mint_address,
+                original_mint: original.clone(),
+                matched_on: CopyMatch::Symbol.as_str().to_string(),
+                detected_at: detected_at + Duration::seconds(secs),
+            })
+            .await
+            .unwrap();
+    }
+    assert_eq!(store.count_clones(&original, detected_at - Duration::seconds(1)).await.unwrap(), 0);
+    assert_eq!(store.count_clones(&original, detected_at).await.unwrap(), 1);
+    assert_eq!(store.count_clones(&original, detected_at + Duration::minutes(1)).await.unwrap(), 2);
+}
@@ -0,0 +1,476 @@
+use {
+    super::TokenStore,
+    crate::models::{
+        copycat::TokenCopy,
+        metadata::OffchainMetadata,
+        token::{TokenInfo, TokenMetrics, TokenStatus},
+        trade::Trade,
+    },
+    anyhow::{anyhow, Result},
+    std::{
+        collections::HashMap,
+        env,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::{
+        sync::{mpsc, oneshot},
+        time::{Instant, MissedTickBehavior},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct WriteBufferConfig {
+    /// Flush once this many writes are buffered.
+    pub batch_size: usize,
+    /// Flush at least this often while anything is buffered.
+    pub flush_interval: Duration,
+    /// Writes that can be queued before callers have to wait. Together with
+    /// `batch_size` this bounds the buffer's memory.
+    pub capacity: usize,
+    /// Failed flushes a batch is kept for before its rows are written one at
+    /// a time and the ones that still fail are dropped.
+    pub max_attempts: u32,
+}
+
+impl WriteBufferConfig {
+    /// Reads `DB_BATCH_SIZE` (default 500), `DB_FLUSH_INTERVAL_MS` (default 250),
+    /// `DB_BUFFER_CAPACITY` (default 10000) and `DB_FLUSH_ATTEMPTS` (default 3).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        WriteBufferConfig {
+            batch_size: read("DB_BATCH_SIZE").unwrap_or(500).max(1) as usize,
+            flush_interval: Duration::from_millis(read("DB_FLUSH_INTERVAL_MS").unwrap_or(250)),
+            capacity: read("DB_BUFFER_CAPACITY").unwrap_or(10_000).max(1) as usize,
+            max_attempts: read("DB_FLUSH_ATTEMPTS").unwrap_or(3).max(1) as u32,
+        }
+    }
+}
+
+/// A status change waiting for its token row.
+struct StatusTransition {
+    mint_address: String,
+    to: TokenStatus,
+    reason: String,
+}
+
+enum BufferedWrite {
+    TokenInfo(TokenInfo),
+    Trade(Trade),
+    TokenMetrics(TokenMetrics),
+    TokenMetadata(OffchainMetadata),
+    TokenCopy(TokenCopy),
+    StatusTransition(StatusTransition),
+    Flush(oneshot::Sender<Result<()>>),
+}
+
+/// Queued trades that haven't been written yet, by mint, so reads can
+/// include them.
+#[derive(Default)]
+struct UnwrittenTrades(Mutex<HashMap<String, Vec<Trade>>>);
+
+impl UnwrittenTrades {
+    fn add(&self, trade: &Trade) {
+        let mut trades = self.0.lock().unwrap();
+        trades.entry(trade.mint_address.clone()).or_default().push(trade.clone());
+    }
+
+    fn remove(&self, written: &[Trade]) {
+        let mut trades = self.0.lock().unwrap();
+        for trade in written {
+            if let Some(pending) = trades.get_mut(&trade.mint_address) {
+                pending.retain(|t| (&t.signature, t.event_index) != (&trade.signature, trade.event_index));
+                if pending.is_empty() {
+                    trades.remove(&trade.mint_address);
+                }
+            }
+        }
+    }
+
+    fn get(&self, mint_address: &str) -> Vec<Trade> {
+        self.0.lock().unwrap().get(mint_address).cloned().unwrap_or_default()
+    }
+}
+
+/// Write-behind buffer in front of a `TokenStore`.
+///
+/// Writes are queued on a bounded channel and written by a single background
+/// task in batches, flushing whenever `batch_size` writes are pending or
+/// `flush_interval` has passed. Within a flush, launches are written before
+/// everything else so rows that reference a token always find it; metadata,
+/// copy tags and status changes go through the buffer for that reason alone.
+///
+/// A write that fails stays pending and is retried on the next flush. After
+/// `max_attempts` failed flushes its rows are written one at a time, and the
+/// ones that fail on their own are dropped so a bad row can't block the rest.
+/// Trades stay visible through `pending_trades` until they are written.
+pub struct WriteBuffer {
+    sender: mpsc::Sender<BufferedWrite>,
+    unwritten: Arc<UnwrittenTrades>,
+}
+
+impl WriteBuffer {
+    /// Spawns the flush task. It runs until every `WriteBuffer` handle is
+    /// dropped, then flushes whatever is left.
+    pub fn spawn(store: Arc<dyn TokenStore>, config: WriteBufferConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.capacity);
+        let unwritten = Arc::new(UnwrittenTrades::default());
+        tokio::spawn(run(store, config, receiver, unwritten.clone()));
+        WriteBuffer { sender, unwritten }
+    }
+
+    pub async fn queue_token_info(&self, token_info: TokenInfo) -> Result<()> {
+        self.send(BufferedWrite::TokenInfo(token_info)).await
+    }
+
+    pub async fn queue_trade(&self, trade: Trade) -> Result<()> {
+        self.unwritten.add(&trade);
+        let written = trade.clone();
+        let queued = self.send(BufferedWrite::Trade(trade)).await;
+        if queued.is_err() {
+            self.unwritten.remove(std::slice::from_ref(&written));
+        }
+        queued
+    }
+
+    pub async fn queue_token_metrics(&self, token_metrics: TokenMetrics) -> Result<()> {
+        self.send(BufferedWrite::TokenMetrics(token_metrics)).await
+    }
+
+    pub async fn queue_token_metadata(&self, metadata: OffchainMetadata) -> Result<()> {
+        self.send(BufferedWrite::TokenMetadata(metadata)).await
+    }
+
+    pub async fn queue_token_copy(&self, copy: TokenCopy) -> Result<()> {
+        self.send(BufferedWrite::TokenCopy(copy)).await
+    }
+
+    pub async fn queue_status_transition(&self, mint_address: &str, to: TokenStatus, reason: &str) -> Result<()> {
+        self.send(BufferedWrite::StatusTransition(StatusTransition {
+            mint_address: mint_address.to_string(),
+            to,
+            reason: reason.to_string(),
+        }))
+        .await
+    }
+
+    /// Trades on `mint_address` that are queued but not written yet.
+    pub fn pending_trades(&self, mint_address: &str) -> Vec<Trade> {
+        self.unwritten.get(mint_address)
+    }
+
+    /// Waits until everything queued before this call has been written.
+    /// Fails if any of it couldn't be, in which case it is retried or
+    /// dropped as described above.
+    pub async fn flush(&self) -> Result<()> {
+        let (done, flushed) = oneshot::channel();
+        self.send(BufferedWrite::Flush(done)).await?;
+        flushed.await.map_err(|_| anyhow!("Write buffer stopped before flushing"))?
+    }
+
+    async fn send(&self, write: BufferedWrite) -> Result<()> {
+        self.sender
+            .send(write)
+            .await
+            .map_err(|_| anyhow!("Write buffer is closed"))
+    }
+}
+
+#[derive(Default)]
+struct PendingWrites {
+    // Keyed by mint so a batch never upserts the same token twice.
+    token_infos: HashMap<String, TokenInfo>,
+    trades: Vec<Trade>,
+    token_metrics: Vec<TokenMetrics>,
+    token_metadata: Vec<OffchainMetadata>,
+    token_copies: Vec<TokenCopy>,
+    transitions: Vec<StatusTransition>,
+    /// Consecutive flushes that failed.
+    failures: u32,
+}
+
+impl PendingWrites {
+    fn len(&self) -> usize {
+        self.token_infos.len()
+            + self.trades.len()
+            + self.token_metrics.len()
+            + self.token_metadata.len()
+            + self.token_copies.len()
+            + self.transitions.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes everything pending. On failure the unwritten rows stay pending,
+    /// unless this was the last of `max_attempts`: then they are written one
+    /// at a time and the ones that still fail are dropped.
+    async fn flush(&mut self, store: &dyn TokenStore, unwritten: &UnwrittenTrades, max_attempts: u32) -> Result<()> {
+        let Err(e) = self.write_batches(store, unwritten).await else {
+            self.failures = 0;
+            return Ok(());
+        };
+        self.failures += 1;
+        if self.failures < max_attempts {
+            return Err(e.context(format!("Failed to write {} buffered rows; will retry", self.len())));
+        }
+        self.failures = 0;
+        let dropped = self.write_rows(store, unwritten).await;
+        Err(e.context(format!("Failed to write buffered rows {max_attempts} times; dropped {dropped}")))
+    }
+
+    /// Writes each kind of row in one batch, in dependency order. Kinds that
+    /// were written are cleared even if a later one fails.
+    async fn write_batches(&mut self, store: &dyn TokenStore, unwritten: &UnwrittenTrades) -> Result<()> {
+        if !self.token_infos.is_empty() {
+            let token_infos: Vec<TokenInfo> = self.token_infos.drain().map(|(_, info)| info).collect();
+            if let Err(e) = store.store_token_info_batch(&token_infos).await {
+                self.token_infos = token_infos.into_iter().map(|info| (info.mint_address.clone(), info)).collect();
+                return Err(e);
+            }
+        }
+        if !self.trades.is_empty() {
+            store.store_trade_batch(&self.trades).await?;
+            unwritten.remove(&self.trades);
+            self.trades.clear();
+        }
+        if !self.token_metrics.is_empty() {
+            store.store_token_metrics_batch(&self.token_metrics).await?;
+            self.token_metrics.clear();
+        }
+        while let Some(metadata) = self.token_metadata.first() {
+            store.store_token_metadata(metadata).await?;
+            self.token_metadata.remove(0);
+        }
+        while let Some(copy) = self.token_copies.first() {
+            store.store_token_copy(copy).await?;
+            self.token_copies.remove(0);
+        }
+        while let Some(transition) = self.transitions.first() {
+            store
+                .transition_token_status(&transition.mint_address, transition.to, &transition.reason)
+                .await?;
+            self.transitions.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Writes every pending row on its own and clears them all. Returns how
+    /// many failed and were dropped.
+    async fn write_rows(&mut self, store: &dyn TokenStore, unwritten: &UnwrittenTrades) -> usize {
+        let mut dropped = 0;
+        let mut log = |what: &str, e: anyhow::Error| {
+            eprintln!("Dropping {what}: {e:?}");
+            dropped += 1;
+        };
+        for (mint_address, token_info) in self.token_infos.drain() {
+            if let Err(e) = store.store_token_info(&token_info).await {
+                log(&format!("launch {mint_address}"), e);
+            }
+        }
+        for trade in self.trades.drain(..) {
+            if let Err(e) = store.store_trade(&trade).await {
+                log(&format!("trade {}", trade.signature), e);
+            }
+            unwritten.remove(std::slice::from_ref(&trade));
+        }
+        for token_metrics in self.token_metrics.drain(..) {
+            if let Err(e) = store.store_token_metrics(&token_metrics).await {
+                log(&format!("metrics sample of {}", token_metrics.mint_address), e);
+            }
+        }
+        for metadata in self.token_metadata.drain(..) {
+            if let Err(e) = store.store_token_metadata(&metadata).await {
+                log(&format!("metadata of {}", metadata.mint_address), e);
+            }
+        }
+        for copy in self.token_copies.drain(..) {
+            if let Err(e) = store.store_token_copy(&copy).await {
+                log(&format!("copy tag of {}", copy.mint_address), e);
+            }
+        }
+        for transition in self.transitions.drain(..) {
+            if let Err(e) = store
+                .transition_token_status(&transition.mint_address, transition.to, &transition.reason)
+                .await
+            {
+                log(&format!("status change of {}", transition.mint_address), e);
+            }
+        }
+        dropped
+    }
+}
+
+async fn run(
+    store: Arc<dyn TokenStore>,
+    config: WriteBufferConfig,
+    mut receiver: mpsc::Receiver<BufferedWrite>,
+    unwritten: Arc<UnwrittenTrades>,
+) {
+    let mut pending = PendingWrites::default();
+    // The first tick would otherwise fire immediately, with nothing queued.
+    let mut ticker = tokio::time::interval_at(Instant::now() + config.flush_interval, config.flush_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            write = receiver.recv() => {
+                let Some(write) = write else {
+                    break;
+                };
+                match write {
+                    BufferedWrite::TokenInfo(token_info) => {
+                        pending.token_infos.insert(token_info.mint_address.clone(), token_info);
+                    }
+                    BufferedWrite::Trade(trade) => pending.trades.push(trade),
+                    BufferedWrite::TokenMetrics(token_metrics) => pending.token_metrics.push(token_metrics),
+                    BufferedWrite::TokenMetadata(metadata) => pending.token_metadata.push(metadata),
+                    BufferedWrite::TokenCopy(copy) => pending.token_copies.push(copy),
+                    BufferedWrite::StatusTransition(transition) => pending.transitions.push(transition),
+                    BufferedWrite::Flush(done) => {
+                        let _ = done.send(pending.flush(&*store, &unwritten, config.max_attempts).await);
+                        continue;
+                    }
+                }
+                // After a failure, retries wait for the ticker instead of
+                // running on every new write.
+                if pending.len() >= config.batch_size && pending.failures == 0 {
+                    if let Err(e) = pending.flush(&*store, &unwritten, config.max_attempts).await {
+                        eprintln!("{e:?}");
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                if !pending.is_empty() {
+                    if let Err(e) = pending.flush(&*store, &unwritten, config.max_attempts).await {
+                        eprintln!("{e:?}");
+                    }
+                }
+            }
+        }
+    }
+    // Nothing can queue writes any more; retry until everything is written
+    // or dropped.
+    while !pending.is_empty() {
+        if let Err(e) = pending.flush(&*store, &unwritten, config.max_attempts).await {
+            eprintln!("{e:?}");
+            tokio::time::sleep(config.flush_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{managers::store::sqlite::SqliteStore, models::copycat::CopyMatch},
+        chrono::Utc,
+        sqlx::SqlitePool,
+    };
+
+    /// Never flushes on its own, so tests decide when writes happen.
+    fn config() -> WriteBufferConfig {
+        WriteBufferConfig {
+            batch_size: 1_000,
+            flush_interval: Duration::from_secs(3_600),
+            capacity: 100,
+            max_attempts: 2,
+        }
+    }
+
+    fn token_info(mint_address: &str) -> TokenInfo {
+        TokenInfo {
+            mint_address: mint_address.to_string(),
+            ..TokenInfo::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn pending_trades_are_readable_until_written() {
+        let store = Arc::new(SqliteStore::connect("sqlite::memory:").await.unwrap());
+        let buffer = WriteBuffer::spawn(store.clone(), config());
+        buffer.queue_token_info(token_info("mint")).await.unwrap();
+        buffer.queue_trade(Trade::test("mint").signature("sig").buy(1_000, 1_000_000)).await.unwrap();
+
+        assert_eq!(buffer.pending_trades("mint").len(), 1);
+        assert!(buffer.pending_trades("other").is_empty());
+        assert!(store.get_trades("mint").await.unwrap().is_empty());
+
+        buffer.flush().await.unwrap();
+        assert!(buffer.pending_trades("mint").is_empty());
+        assert_eq!(store.get_trades("mint").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_that_need_a_token_row_follow_it() {
+        let store = Arc::new(SqliteStore::connect("sqlite::memory:").await.unwrap());
+        let buffer = WriteBuffer::spawn(store.clone(), config());
+        buffer
+            .queue_status_transition("copy", TokenStatus::Rugged, "creator sold")
+            .await
+            .unwrap();
+        buffer
+            .queue_token_copy(TokenCopy {
+                mint_address: "copy".to_string(),
+                original_mint: "original".to_string(),
+                matched_on: CopyMatch::Name.as_str().to_string(),
+                detected_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        buffer.queue_token_info(token_info("copy")).await.unwrap();
+        buffer.flush().await.unwrap();
+
+        let token = store.get_token("copy").await.unwrap().unwrap();
+        assert_eq!(token.status().unwrap(), TokenStatus::Rugged);
+        assert_eq!(store.get_token_copy("copy").await.unwrap().unwrap().original_mint, "original");
+    }
+
+    #[tokio::test]
+    async fn failed_writes_are_retried_then_dropped() {
+        let path = env::temp_dir().join(format!("write_buffer_{}.db", std::process::id()));
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let store = Arc::new(SqliteStore::connect(&url).await.unwrap());
+        // A second connection makes inserts into trades fail and recover.
+        let admin = SqlitePool::connect(&url).await.unwrap();
+        let fail_trades = |fail: bool| {
+            let admin = admin.clone();
+            async move {
+                let sql = if fail {
+                    "CREATE TRIGGER fail_trades BEFORE INSERT ON trades BEGIN SELECT RAISE(ABORT, 'down'); END"
+                } else {
+                    "DROP TRIGGER fail_trades"
+                };
+                sqlx::query(sql).execute(&admin).await.unwrap();
+            }
+        };
+        let buffer = WriteBuffer::spawn(store.clone(), config());
+        buffer.queue_token_info(token_info("mint")).await.unwrap();
+        buffer.queue_trade(Trade::test("mint").signature("first").buy(1_000, 1_000_000)).await.unwrap();
+
+        fail_trades(true).await;
+        assert!(buffer.flush().await.is_err());
+        // The launch was written; the trade is kept for the next flush.
+        assert!(store.get_token("mint").await.unwrap().is_some());
+        assert_eq!(buffer.pending_trades("mint").len(), 1);
+
+        fail_trades(false).await;
+        buffer.flush().await.unwrap();
+        assert_eq!(store.get_trades("mint").await.unwrap().len(), 1);
+
+        fail_trades(true).await;
+        buffer.queue_trade(Trade::test("mint").signature("second").buy(1_000, 1_000_000)).await.unwrap();
+        assert!(buffer.flush().await.is_err());
+        let dropped = buffer.flush().await.unwrap_err();
+        assert!(format!("{dropped:#}").contains("dropped 1"), "{dropped:#}");
+        assert!(buffer.pending_trades("mint").is_empty());
+
+        fail_trades(false).await;
+        buffer.flush().await.unwrap();
+        assert_eq!(store.get_trades("mint").await.unwrap().len(), 1);
+        admin.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+}
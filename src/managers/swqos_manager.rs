@@ -1,23 +1,37 @@
 use {
-    crate::utils::rug_check::check_liquidity_pools, solana_client::nonblocking::rpc_client::RpcClient, solana_sdk::{commitment_config::CommitmentConfig, program_pack::Pack, pubkey::Pubkey}, std::{mem, sync::Arc, time::Duration}
-    
+    crate::utils::pump_fun::{bonding_curve_pda, BondingCurve},
+    anyhow::{anyhow, Result},
+    futures::{future::join_all, stream::StreamExt},
+    solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
+    solana_client::{
+        nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig, RpcSignatureSubscribeConfig},
+        rpc_filter::{Memcmp, RpcFilterType},
+        rpc_response::RpcSignatureResult,
+    },
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        compute_budget::{self, ComputeBudgetInstruction},
+        instruction::Instruction,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        transaction::Transaction,
+    },
+    spl_token::state::Account as TokenAccount,
+    std::{
+        collections::HashMap,
+        env,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::sync::oneshot,
 };
 
-
-
-
 pub struct SwqosRpcClient{
     inner: Arc<RpcClient>,
 
 }
-#[repr(C)]
-#[derive(Clone, Copy, Debug, bytemuck::AnyBitPattern)]
-struct BondingCurveState {
-    virtual_sol_reserve: u64,   // SOL liquidity in lamports
-    virtual_token_reserve: u64, // Token supply in base units
-    progress_percent: u8,       // Bonding curve completion %
-    last_updated: i64,          // UNIX timestamp of last update
-}
 
 impl SwqosRpcClient {
     pub fn new(rpc_endpoint: &str) -> Self {
@@ -29,6 +43,50 @@ impl SwqosRpcClient {
         }
     }
 
+    /// Shared handle to the underlying nonblocking RPC client.
+    pub fn rpc(&self) -> Arc<RpcClient> {
+        self.inner.clone()
+    }
+
+    /// Current state of the token's bonding curve, or `None` if the account
+    /// doesn't exist or isn't a pump.fun curve.
+    pub async fn get_bonding_curve(&self, mint: &Pubkey) -> Result<Option<BondingCurve>, Box<dyn std::error::Error + Send + Sync>> {
+        let account = self
+            .inner
+            .get_account_with_commitment(&bonding_curve_pda(mint), CommitmentConfig::confirmed())
+            .await?
+            .value;
+        Ok(account.and_then(|account| BondingCurve::parse(&account.data)))
+    }
+
+    /// Number of token accounts holding a non-zero balance of `mint`,
+    /// including the bonding curve's own account.
+    pub async fn count_holders(&self, mint: &Pubkey) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        // Only the 8-byte amount (offset 64) of each account is fetched.
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(TokenAccount::LEN as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig { offset: 64, length: 8 }),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = self
+            .inner
+            .get_program_accounts_with_config(&spl_token::id(), config)
+            .await?;
+        let holders = accounts
+            .iter()
+            .filter(|(_, account)| account.data.iter().any(|byte| *byte != 0))
+            .count();
+        Ok(holders as u64)
+    }
+
     pub async fn validate_token(&self, mint: &Pubkey) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         // let mint_account = self.inner.get_account(&mint).await?;
         // let mint_state = spl_token::state::Mint::unpack(&mint_account.data)
@@ -38,11 +96,9 @@ impl SwqosRpcClient {
         //     })?;
         let holders = self.inner.get_token_largest_accounts(mint).await?;
         // Assume that the bonding curve is the largest holder
-        if let  Some(bc) = holders.get(0) {
+        if let  Some(bc) = holders.first() {
             if let Some(tokens) = bc.amount.ui_amount {
-                let prog = 100.0 as f64 - (tokens - 206900000 as f64) * (100.0 as f64)/(793100000 as f64) as f64;
-                let ilv = (32190005730.0 as f64) * ((1073000191.0 as f64 - tokens) / (tokens * 1073000191.0 as f64)) as f64;
-                // println!("ILV: {:?}", ilv);
+                let prog = 100.0 - (tokens - 206_900_000.0) * 100.0 / 793_100_000.0;
                 if prog > 5.0 {
                     return Ok(true);
                 }
@@ -54,25 +110,554 @@ impl SwqosRpcClient {
 
         Ok(false)
     }
-    
-    pub async fn get_largest_token_holders(&self, mint: &Pubkey) -> Result<(), Box<dyn std::error::Error>> {
-        let total_supply = self.inner.
-        get_token_supply_with_commitment(mint, CommitmentConfig::confirmed())
-        .await
-        .unwrap()
-        .value;
-        let holder_data = self.inner
-        .get_token_largest_accounts_with_commitment(mint, CommitmentConfig::confirmed())
-        .await.unwrap().value;
-        
-        let mut top_ten_pct : f64 = 0.0;
-        for (i, val) in holder_data.iter().enumerate() {
-            if i > 10 {
+}
+
+/// How the priority fee of a transaction is chosen, in micro-lamports per
+/// compute unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeStrategy {
+    /// Always the same price.
+    Fixed(u64),
+    /// A percentile, 0-100, of the fees recently paid to write the
+    /// transaction's accounts.
+    Percentile(u8),
+    /// The percentile fee, raised by up to `boost_bps` as more of the recent
+    /// slots had someone paying to write the same accounts.
+    Dynamic { percentile: u8, boost_bps: u64 },
+}
+
+impl FeeStrategy {
+    /// Price for a transaction, before the configured bounds.
+    ///
+    /// # Arguments
+    /// * `recent_fees` - The lowest fee paid in each recent slot to write the
+    ///   transaction's accounts, as `getRecentPrioritizationFees` returns them
+    pub fn price(&self, recent_fees: &[u64]) -> u64 {
+        match *self {
+            FeeStrategy::Fixed(price) => price,
+            FeeStrategy::Percentile(p) => percentile(recent_fees, p),
+            FeeStrategy::Dynamic { percentile: p, boost_bps } => {
+                let base = percentile(recent_fees, p);
+                if recent_fees.is_empty() {
+                    return base;
+                }
+                let contested = recent_fees.iter().filter(|fee| **fee > 0).count() as u128;
+                let boost = boost_bps as u128 * contested / recent_fees.len() as u128;
+                (base as u128 * (10_000 + boost) / 10_000) as u64
+            }
+        }
+    }
+}
+
+fn percentile(values: &[u64], p: u8) -> u64 {
+    if values.is_empty() {
+        return 0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    sorted[(sorted.len() - 1) * p.min(100) as usize / 100]
+}
+
+#[derive(Debug, Clone)]
+pub struct SenderConfig {
+    pub fee_strategy: FeeStrategy,
+    /// Bounds on the priority fee, in micro-lamports per compute unit.
+    pub min_price: u64,
+    pub max_price: u64,
+    pub compute_unit_limit: u32,
+    /// RPC endpoints sent to besides the main one, e.g. stake-weighted or
+    /// block-engine relays.
+    pub endpoints: Vec<String>,
+    /// Websocket endpoint for signature subscriptions; without one,
+    /// confirmations come from Geyser and status polling.
+    pub ws_endpoint: Option<String>,
+    /// How often an unconfirmed transaction is sent again.
+    pub rebroadcast_interval: Duration,
+    /// Longest a single send may take on one endpoint.
+    pub send_timeout: Duration,
+}
+
+impl SenderConfig {
+    /// Reads `SEND_FEE_STRATEGY` (`fixed`, `percentile` or `dynamic`;
+    /// default `percentile`), `SEND_FEE_MICRO_LAMPORTS` (fixed price,
+    /// default 10000), `SEND_FEE_PERCENTILE` (default 75),
+    /// `SEND_FEE_BOOST_BPS` (default 10000), `SEND_FEE_MIN` (default 0),
+    /// `SEND_FEE_MAX` (default 1000000), `SEND_COMPUTE_UNIT_LIMIT` (default
+    /// 200000), `SEND_ENDPOINTS` (comma-separated), `SEND_WS_ENDPOINT`,
+    /// `SEND_REBROADCAST_MS` (default 2000) and `SEND_TIMEOUT_MS` (default
+    /// 1000).
+    pub fn from_env() -> Result<Self> {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let percentile = read("SEND_FEE_PERCENTILE").unwrap_or(75).min(100) as u8;
+        let fee_strategy = match env::var("SEND_FEE_STRATEGY").as_deref().unwrap_or("percentile") {
+            "fixed" => FeeStrategy::Fixed(read("SEND_FEE_MICRO_LAMPORTS").unwrap_or(10_000)),
+            "percentile" => FeeStrategy::Percentile(percentile),
+            "dynamic" => FeeStrategy::Dynamic {
+                percentile,
+                boost_bps: read("SEND_FEE_BOOST_BPS").unwrap_or(10_000),
+            },
+            other => return Err(anyhow!("Unknown fee strategy: {other:?}")),
+        };
+        Ok(SenderConfig {
+            fee_strategy,
+            min_price: read("SEND_FEE_MIN").unwrap_or(0),
+            max_price: read("SEND_FEE_MAX").unwrap_or(1_000_000),
+            compute_unit_limit: read("SEND_COMPUTE_UNIT_LIMIT").unwrap_or(200_000) as u32,
+            endpoints: env::var("SEND_ENDPOINTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|endpoint| !endpoint.is_empty())
+                .map(String::from)
+                .collect(),
+            ws_endpoint: env::var("SEND_WS_ENDPOINT").ok().filter(|endpoint| !endpoint.is_empty()),
+            rebroadcast_interval: Duration::from_millis(read("SEND_REBROADCAST_MS").unwrap_or(2000)),
+            send_timeout: Duration::from_millis(read("SEND_TIMEOUT_MS").unwrap_or(1000)),
+        })
+    }
+}
+
+/// A sent transaction that made it into a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Landing {
+    pub slot: u64,
+    /// Why the transaction failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Hands out confirmations of the transactions we sent. The Geyser stream
+/// reports every transaction it sees through `observe`; signature
+/// subscriptions and status polls through `resolve`.
+#[derive(Default)]
+pub struct ConfirmationTracker {
+    pending: Mutex<HashMap<Signature, oneshot::Sender<Landing>>>,
+}
+
+impl ConfirmationTracker {
+    /// Starts waiting for `signature`. Only the latest watch of a
+    /// signature is told.
+    pub fn watch(&self, signature: Signature) -> oneshot::Receiver<Landing> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(signature, sender);
+        receiver
+    }
+
+    pub fn forget(&self, signature: &Signature) {
+        self.pending.lock().unwrap().remove(signature);
+    }
+
+    /// Called for each transaction on the Geyser stream, which only carries
+    /// successful transactions.
+    ///
+    /// # Arguments
+    /// * `signature` - The raw transaction signature
+    /// * `slot` - The slot the transaction landed in
+    pub fn observe(&self, signature: &[u8], slot: u64) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.is_empty() {
+            return;
+        }
+        let Ok(signature) = Signature::try_from(signature) else {
+            return;
+        };
+        if let Some(sender) = pending.remove(&signature) {
+            let _ = sender.send(Landing { slot, error: None });
+        }
+    }
+
+    pub fn resolve(&self, signature: &Signature, landing: Landing) {
+        if let Some(sender) = self.pending.lock().unwrap().remove(signature) {
+            let _ = sender.send(landing);
+        }
+    }
+}
+
+/// How a sent transaction ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendStatus {
+    Landed(Landing),
+    /// The blockhash expired before the transaction was seen in a block.
+    Expired,
+}
+
+#[derive(Debug, Clone)]
+pub struct SendReport {
+    pub signature: Signature,
+    pub status: SendStatus,
+    /// Priority fee paid, in micro-lamports per compute unit.
+    pub compute_unit_price: u64,
+    /// Times the transaction was broadcast.
+    pub broadcasts: u32,
+}
+
+/// Signs and sends transactions to every configured endpoint at once, sends
+/// them again until they land or their blockhash expires, and reports how
+/// they ended.
+///
+/// Point `RPC_ENDPOINT` and `SEND_WS_ENDPOINT` at `solana-test-validator`
+/// (`http://127.0.0.1:8899`, `ws://127.0.0.1:8900`) to exercise it locally.
+pub struct TransactionSender {
+    rpc: Arc<RpcClient>,
+    endpoints: Vec<Arc<RpcClient>>,
+    confirmations: Arc<ConfirmationTracker>,
+    config: SenderConfig,
+}
+
+impl TransactionSender {
+    /// # Arguments
+    /// * `rpc_manager` - Used for blockhashes, fees and statuses, and sent to
+    /// * `confirmations` - Shared with the Geyser stream, if there is one
+    /// * `config` - Fee strategy, extra endpoints and timings
+    pub fn new(rpc_manager: &SwqosRpcClient, confirmations: Arc<ConfirmationTracker>, config: SenderConfig) -> Self {
+        let rpc = rpc_manager.rpc();
+        let mut endpoints = vec![rpc.clone()];
+        endpoints.extend(
+            config
+                .endpoints
+                .iter()
+                .map(|endpoint| Arc::new(RpcClient::new_with_timeout(endpoint.clone(), config.send_timeout))),
+        );
+        TransactionSender {
+            rpc,
+            endpoints,
+            confirmations,
+            config,
+        }
+    }
+
+    /// Priority fee for `instructions`, from the fees recently paid to write
+    /// the same accounts.
+    pub async fn compute_unit_price(&self, instructions: &[Instruction]) -> Result<u64> {
+        let recent_fees = match self.config.fee_strategy {
+            FeeStrategy::Fixed(_) => Vec::new(),
+            _ => {
+                let mut accounts: Vec<Pubkey> = instructions
+                    .iter()
+                    .flat_map(|instruction| &instruction.accounts)
+                    .filter(|account| account.is_writable)
+                    .map(|account| account.pubkey)
+                    .collect();
+                accounts.sort();
+                accounts.dedup();
+                // The RPC accepts at most 128 accounts.
+                accounts.truncate(128);
+                self.rpc
+                    .get_recent_prioritization_fees(&accounts)
+                    .await?
+                    .iter()
+                    .map(|fee| fee.prioritization_fee)
+                    .collect()
+            }
+        };
+        Ok(self
+            .config
+            .fee_strategy
+            .price(&recent_fees)
+            .clamp(self.config.min_price, self.config.max_price.max(self.config.min_price)))
+    }
+
+    /// Signs `instructions` with the sender's compute budget, replacing any
+    /// compute budget instructions they already have, and sends them until
+    /// they land or the blockhash expires.
+    ///
+    /// # Arguments
+    /// * `instructions` - The transaction's instructions
+    /// * `payer` - Pays the fees and signs; the only signer
+    pub async fn send(&self, instructions: &[Instruction], payer: &Keypair) -> Result<SendReport> {
+        let compute_unit_price = self.compute_unit_price(instructions).await?;
+        let budgeted = with_compute_budget(instructions, self.config.compute_unit_limit, compute_unit_price);
+        let (blockhash, last_valid_block_height) = self
+            .rpc
+            .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+            .await?;
+        let transaction = Transaction::new_signed_with_payer(&budgeted, Some(&payer.pubkey()), &[payer], blockhash);
+        let signature = transaction.signatures[0];
+
+        let landed = self.confirmations.watch(signature);
+        let subscription = self.config.ws_endpoint.clone().map(|ws_endpoint| {
+            let confirmations = self.confirmations.clone();
+            tokio::spawn(async move {
+                if let Err(e) = subscribe(&ws_endpoint, signature, &confirmations).await {
+                    eprintln!("Signature subscription for {signature} failed: {e:?}");
+                }
+            })
+        });
+        let status = self
+            .broadcast_until_landed(&transaction, last_valid_block_height, Some(landed))
+            .await;
+        self.confirmations.forget(&signature);
+        if let Some(subscription) = subscription {
+            subscription.abort();
+        }
+        let (status, broadcasts) = status?;
+        Ok(SendReport {
+            signature,
+            status,
+            compute_unit_price,
+            broadcasts,
+        })
+    }
+
+    async fn broadcast_until_landed(
+        &self,
+        transaction: &Transaction,
+        last_valid_block_height: u64,
+        mut landed: Option<oneshot::Receiver<Landing>>,
+    ) -> Result<(SendStatus, u32)> {
+        let signature = transaction.signatures[0];
+        let mut broadcasts = 0;
+        loop {
+            let accepted = self.broadcast(transaction).await;
+            broadcasts += 1;
+            if accepted == 0 && broadcasts == 1 {
+                return Err(anyhow!("No endpoint accepted {signature}"));
+            }
+            match landed.as_mut() {
+                Some(receiver) => match tokio::time::timeout(self.config.rebroadcast_interval, receiver).await {
+                    Ok(Ok(landing)) => return Ok((SendStatus::Landed(landing), broadcasts)),
+                    // The watch was replaced; polling carries on alone.
+                    Ok(Err(_)) => landed = None,
+                    Err(_) => {}
+                },
+                None => tokio::time::sleep(self.config.rebroadcast_interval).await,
+            }
+            // Polling backs up the stream and subscription, which can miss
+            // a transaction across a reconnect.
+            match self.poll_status(&signature).await {
+                Ok(Some(landing)) => return Ok((SendStatus::Landed(landing), broadcasts)),
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to poll status of {signature}: {e:?}"),
+            }
+            match self.rpc.get_block_height_with_commitment(CommitmentConfig::confirmed()).await {
+                Ok(height) if height > last_valid_block_height => {
+                    // Anything still in flight can no longer land; one last
+                    // look in case it did.
+                    return Ok(match self.poll_status(&signature).await {
+                        Ok(Some(landing)) => (SendStatus::Landed(landing), broadcasts),
+                        _ => (SendStatus::Expired, broadcasts),
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to fetch block height: {e:?}"),
+            }
+        }
+    }
+
+    /// Sends `transaction` to every endpoint at once. Returns how many
+    /// accepted it.
+    async fn broadcast(&self, transaction: &Transaction) -> usize {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            // Rebroadcasting is ours to do.
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        let sends = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.send_transaction_with_config(transaction, config));
+        join_all(sends)
+            .await
+            .into_iter()
+            .zip(&self.endpoints)
+            .filter(|(sent, endpoint)| match sent {
+                Ok(_) => true,
+                Err(e) => {
+                    eprintln!("Failed to send {} to {}: {e}", transaction.signatures[0], endpoint.url());
+                    false
+                }
+            })
+            .count()
+    }
+
+    async fn poll_status(&self, signature: &Signature) -> Result<Option<Landing>> {
+        let status = self.rpc.get_signature_statuses(&[*signature]).await?.value.remove(0);
+        Ok(status
+            .filter(|status| status.satisfies_commitment(CommitmentConfig::confirmed()))
+            .map(|status| Landing {
+                slot: status.slot,
+                error: status.err.map(|e| e.to_string()),
+            }))
+    }
+}
+
+/// `instructions` behind a compute unit limit and price, with any compute
+/// budget instructions they had dropped.
+fn with_compute_budget(instructions: &[Instruction], compute_unit_limit: u32, compute_unit_price: u64) -> Vec<Instruction> {
+    let mut budgeted = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+    ];
+    budgeted.extend(
+        instructions
+            .iter()
+            .filter(|instruction| instruction.program_id != compute_budget::id())
+            .cloned(),
+    );
+    budgeted
+}
+
+/// Waits for `signature` to be confirmed over a websocket subscription and
+/// passes the result to `confirmations`.
+async fn subscribe(ws_endpoint: &str, signature: Signature, confirmations: &ConfirmationTracker) -> Result<()> {
+    let client = PubsubClient::new(ws_endpoint).await?;
+    let config = RpcSignatureSubscribeConfig {
+        commitment: Some(CommitmentConfig::confirmed()),
+        enable_received_notification: Some(false),
+    };
+    let (mut updates, unsubscribe) = client.signature_subscribe(&signature, Some(config)).await?;
+    while let Some(update) = updates.next().await {
+        if let RpcSignatureResult::ProcessedSignature(result) = update.value {
+            confirmations.resolve(
+                &signature,
+                Landing {
+                    slot: update.context.slot,
+                    error: result.err.map(|e| e.to_string()),
+                },
+            );
+            break;
+        }
+    }
+    unsubscribe().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::system_instruction};
+
+    fn config(fee_strategy: FeeStrategy) -> SenderConfig {
+        SenderConfig {
+            fee_strategy,
+            min_price: 0,
+            max_price: 1_000_000,
+            compute_unit_limit: 200_000,
+            endpoints: Vec::new(),
+            ws_endpoint: None,
+            rebroadcast_interval: Duration::from_millis(500),
+            send_timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn fixed_fees_ignore_recent_fees() {
+        assert_eq!(FeeStrategy::Fixed(5_000).price(&[]), 5_000);
+        assert_eq!(FeeStrategy::Fixed(5_000).price(&[1, 1_000_000]), 5_000);
+    }
+
+    #[test]
+    fn percentile_fees_pick_from_the_sorted_recent_fees() {
+        let fees = [500, 0, 100, 400, 200, 300, 0, 0, 0, 0, 600];
+        assert_eq!(FeeStrategy::Percentile(0).price(&fees), 0);
+        assert_eq!(FeeStrategy::Percentile(50).price(&fees), 100);
+        assert_eq!(FeeStrategy::Percentile(75).price(&fees), 300);
+        assert_eq!(FeeStrategy::Percentile(100).price(&fees), 600);
+        // Out of range percentiles are the maximum.
+        assert_eq!(FeeStrategy::Percentile(200).price(&fees), 600);
+        assert_eq!(FeeStrategy::Percentile(75).price(&[]), 0);
+    }
+
+    #[test]
+    fn dynamic_fees_rise_with_the_share_of_contested_slots() {
+        let strategy = FeeStrategy::Dynamic {
+            percentile: 100,
+            boost_bps: 10_000,
+        };
+        // Half the slots had a fee, so the maximum is raised by half.
+        assert_eq!(strategy.price(&[0, 0, 100, 1_000]), 1_500);
+        assert_eq!(strategy.price(&[1_000; 4]), 2_000);
+        assert_eq!(strategy.price(&[0; 4]), 0);
+        assert_eq!(strategy.price(&[]), 0);
+    }
+
+    #[tokio::test]
+    async fn prices_are_clamped_to_the_configured_bounds() {
+        // Fixed prices never reach the RPC.
+        let rpc_manager = SwqosRpcClient::new("http://127.0.0.1:9");
+        let sender = |fee_strategy, min_price, max_price| {
+            TransactionSender::new(
+                &rpc_manager,
+                Arc::new(ConfirmationTracker::default()),
+                SenderConfig { min_price, max_price, ..config(fee_strategy) },
+            )
+        };
+        assert_eq!(sender(FeeStrategy::Fixed(50), 100, 1_000).compute_unit_price(&[]).await.unwrap(), 100);
+        assert_eq!(sender(FeeStrategy::Fixed(5_000), 100, 1_000).compute_unit_price(&[]).await.unwrap(), 1_000);
+        assert_eq!(sender(FeeStrategy::Fixed(500), 100, 1_000).compute_unit_price(&[]).await.unwrap(), 500);
+        // A maximum below the minimum gives way to the minimum.
+        assert_eq!(sender(FeeStrategy::Fixed(500), 100, 10).compute_unit_price(&[]).await.unwrap(), 100);
+    }
+
+    #[test]
+    fn compute_budget_instructions_are_replaced() {
+        let from = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&from, &Pubkey::new_unique(), 1);
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(1),
+            ComputeBudgetInstruction::set_compute_unit_price(2),
+            transfer.clone(),
+        ];
+        let budgeted = with_compute_budget(&instructions, 200_000, 7);
+        assert_eq!(
+            budgeted,
+            [
+                ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+                ComputeBudgetInstruction::set_compute_unit_price(7),
+                transfer,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn observed_signatures_wake_their_waiter() {
+        let tracker = ConfirmationTracker::default();
+        let signature = Signature::from([7; 64]);
+        let other = Signature::from([8; 64]);
+        let landed = tracker.watch(signature);
+        let forgotten = tracker.watch(other);
+        tracker.forget(&other);
+
+        // Garbage and unwatched signatures are ignored.
+        tracker.observe(&[1, 2, 3], 10);
+        tracker.observe(other.as_ref(), 11);
+        tracker.observe(signature.as_ref(), 12);
+        assert_eq!(landed.await.unwrap(), Landing { slot: 12, error: None });
+        assert!(forgotten.await.is_err());
+
+        // Only the latest watch is told.
+        let replaced = tracker.watch(signature);
+        let latest = tracker.watch(signature);
+        tracker.resolve(&signature, Landing { slot: 13, error: Some("failed".to_string()) });
+        assert!(replaced.await.is_err());
+        assert_eq!(latest.await.unwrap().error.as_deref(), Some("failed"));
+    }
+
+    /// Needs `solana-test-validator` running; point `TEST_VALIDATOR_RPC`
+    /// elsewhere than `http://127.0.0.1:8899` if needed.
+    #[tokio::test]
+    #[ignore]
+    async fn transfers_land_on_a_test_validator() {
+        let endpoint = env::var("TEST_VALIDATOR_RPC").unwrap_or_else(|_| "http://127.0.0.1:8899".to_string());
+        let rpc_manager = SwqosRpcClient {
+            inner: Arc::new(RpcClient::new_with_timeout(endpoint, Duration::from_secs(5))),
+        };
+        let payer = Keypair::new();
+        let airdrop = rpc_manager.rpc().request_airdrop(&payer.pubkey(), 1_000_000_000).await.unwrap();
+        for _ in 0..60 {
+            if rpc_manager.rpc().confirm_transaction(&airdrop).await.unwrap() {
                 break;
             }
-            top_ten_pct += val.amount.ui_amount.unwrap() / total_supply.ui_amount.unwrap()
+            tokio::time::sleep(Duration::from_millis(500)).await;
         }
-        println!("The top ten holders hold {top_ten_pct}%");
-        Ok(())
+
+        let sender = TransactionSender::new(&rpc_manager, Arc::new(ConfirmationTracker::default()), config(FeeStrategy::Fixed(1_000)));
+        let recipient = Pubkey::new_unique();
+        let report = sender
+            .send(&[system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000)], &payer)
+            .await
+            .unwrap();
+        assert!(matches!(report.status, SendStatus::Landed(Landing { error: None, .. })), "{report:?}");
+        assert_eq!(report.compute_unit_price, 1_000);
+        assert_eq!(rpc_manager.rpc().get_balance(&recipient).await.unwrap(), 1_000_000);
     }
-}
\ No newline at end of file
+}
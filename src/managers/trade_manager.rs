@@ -0,0 +1,49 @@
+use {
+    super::{candle_manager::CandleAggregator, db_manager::DbManager, dev_sell_manager::DevSellDetector},
+    crate::models::{signal::DevSold, token::TokenStatus, trade::Trade},
+    std::sync::Arc,
+    tokio::sync::mpsc,
+};
+
+/// Persists trades decoded from the gRPC stream through the database write
+/// buffer, feeds them to the candle aggregator and watches them for dev
+/// sells.
+pub struct TradeRecorder {
+    db_manager: Arc<DbManager>,
+    candles: Arc<CandleAggregator>,
+    dev_sells: Arc<DevSellDetector>,
+}
+
+impl TradeRecorder {
+    pub fn new(db_manager: Arc<DbManager>, candles: Arc<CandleAggregator>, dev_sells: Arc<DevSellDetector>) -> Self {
+        TradeRecorder {
+            db_manager,
+            candles,
+            dev_sells,
+        }
+    }
+
+    /// Runs until the trade channel closes.
+    pub async fn run(self, mut trades: mpsc::Receiver<Trade>) {
+        while let Some(trade) = trades.recv().await {
+            self.candles.apply(&trade);
+            if let Some(sold) = self.dev_sells.observe(&trade).filter(|sold| sold.dumped) {
+                // Off the stream's path: the signal is already out.
+                tokio::spawn(mark_dumped(self.db_manager.clone(), sold));
+            }
+            if let Err(e) = self.db_manager.queue_trade(trade).await {
+                eprintln!("Failed to queue trade: {e:?}");
+            }
+        }
+    }
+}
+
+async fn mark_dumped(db_manager: Arc<DbManager>, sold: DevSold) {
+    let reason = format!("creator sold {:.0}% of their buys", sold.total_sold_percent);
+    if let Err(e) = db_manager
+        .queue_status_transition(&sold.mint_address, TokenStatus::Rugged, &reason)
+        .await
+    {
+        eprintln!("Failed to mark {} rugged after dev sell: {e:?}", sold.mint_address);
+    }
+}
@@ -0,0 +1,114 @@
+use {
+    chrono::{DateTime, Utc},
+    std::{fmt, str::FromStr},
+};
+
+/// Candle resolutions that are aggregated and stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    OneSecond,
+    FiveSeconds,
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 5] = [
+        CandleInterval::OneSecond,
+        CandleInterval::FiveSeconds,
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::OneHour,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::OneSecond => "1s",
+            CandleInterval::FiveSeconds => "5s",
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::OneHour => "1h",
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            CandleInterval::OneSecond => 1,
+            CandleInterval::FiveSeconds => 5,
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 300,
+            CandleInterval::OneHour => 3600,
+        }
+    }
+
+    /// Start of the bucket `at` falls in.
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = at.timestamp();
+        let start = seconds - seconds.rem_euclid(self.seconds());
+        DateTime::from_timestamp(start, 0).unwrap_or(at)
+    }
+}
+
+impl fmt::Display for CandleInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CandleInterval {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CandleInterval::ALL
+            .into_iter()
+            .find(|interval| interval.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown candle interval: {s}"))
+    }
+}
+
+/// A row of `candles`. Prices are SOL per whole token; volumes are raw base
+/// units like `trades`.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Candle {
+    pub mint_address: String,
+    /// A `CandleInterval` as text.
+    pub resolution: String,
+    pub bucket_start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Lamports traded.
+    pub sol_volume: i64,
+    pub token_volume: i64,
+    pub trade_count: i32,
+}
+
+impl Candle {
+    /// An empty candle whose open is `price`; trades are added with `apply`.
+    pub fn open_at(mint_address: &str, interval: CandleInterval, bucket_start: DateTime<Utc>, price: f64) -> Self {
+        Candle {
+            mint_address: mint_address.to_string(),
+            resolution: interval.as_str().to_string(),
+            bucket_start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            sol_volume: 0,
+            token_volume: 0,
+            trade_count: 0,
+        }
+    }
+
+    /// Folds a trade into the candle as its latest trade.
+    pub fn apply(&mut self, price: f64, sol_amount: i64, token_amount: i64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.sol_volume += sol_amount;
+        self.token_volume += token_amount;
+        self.trade_count += 1;
+    }
+}
@@ -0,0 +1,74 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::Serialize,
+    std::str::FromStr,
+};
+
+/// What a copy shares with the launch it copies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyMatch {
+    Name,
+    Symbol,
+    Image,
+}
+
+impl CopyMatch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CopyMatch::Name => "name",
+            CopyMatch::Symbol => "symbol",
+            CopyMatch::Image => "image",
+        }
+    }
+}
+
+impl FromStr for CopyMatch {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(CopyMatch::Name),
+            "symbol" => Ok(CopyMatch::Symbol),
+            "image" => Ok(CopyMatch::Image),
+            other => Err(anyhow::anyhow!("Unknown copy match: {other}")),
+        }
+    }
+}
+
+/// A launch found to copy an earlier one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CopyTag {
+    /// The first launch in the copy chain.
+    pub original_mint: String,
+    pub matched_on: CopyMatch,
+    /// Copies of the original seen so far, this one included.
+    pub clone_count: usize,
+}
+
+/// A row of `token_copies`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TokenCopy {
+    pub mint_address: String,
+    pub original_mint: String,
+    pub matched_on: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl TokenCopy {
+    pub fn matched_on(&self) -> anyhow::Result<CopyMatch> {
+        CopyMatch::from_str(&self.matched_on)
+    }
+}
+
+/// What the copycat index needs to know about a recent launch, as stored.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LaunchFingerprint {
+    pub mint_address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub image_hash: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    /// The original, if the launch was already found to be a copy.
+    pub copy_of: Option<String>,
+}
@@ -0,0 +1,86 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::Serialize,
+};
+
+/// A row of `creators`: a wallet's track record across every launch we saw.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CreatorRecord {
+    pub address: String,
+    pub launch_count: i32,
+    pub graduated_count: i32,
+    /// Launches marked rugged on evidence of their own, such as the creator
+    /// dumping. Tokens the rug check flagged as rugs don't count.
+    pub rugged_count: i32,
+    pub abandoned_count: i32,
+    /// Mean seconds from launch to outcome, over launches with an outcome.
+    pub avg_lifetime_secs: Option<f64>,
+    /// Launches the creator sold into.
+    pub sold_count: i32,
+    /// Mean seconds from launch to the creator's first sell, over `sold_count`.
+    pub avg_first_sell_secs: Option<f64>,
+    pub first_launch_at: DateTime<Utc>,
+    pub last_launch_at: DateTime<Utc>,
+    /// The wallet that sent the creator its first SOL, if it was found.
+    pub funding_source: Option<String>,
+    pub funded_at: Option<DateTime<Utc>>,
+    /// When the funding lookup ran; `None` until it has.
+    pub funding_checked_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CreatorRecord {
+    /// Launches that reached graduated, rugged or abandoned.
+    pub fn ended_count(&self) -> i32 {
+        self.graduated_count + self.rugged_count + self.abandoned_count
+    }
+
+    /// Share of ended launches that graduated; `None` before any ended.
+    pub fn graduation_rate(&self) -> Option<f64> {
+        let ended = self.ended_count();
+        (ended > 0).then(|| self.graduated_count as f64 / ended as f64)
+    }
+
+    /// Share of ended launches that rugged; `None` before any ended.
+    pub fn rug_rate(&self) -> Option<f64> {
+        let ended = self.ended_count();
+        (ended > 0).then(|| self.rugged_count as f64 / ended as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creator(graduated_count: i32, rugged_count: i32, abandoned_count: i32) -> CreatorRecord {
+        let now = Utc::now();
+        CreatorRecord {
+            address: "creator".to_string(),
+            launch_count: graduated_count + rugged_count + abandoned_count + 1,
+            graduated_count,
+            rugged_count,
+            abandoned_count,
+            avg_lifetime_secs: None,
+            sold_count: 0,
+            avg_first_sell_secs: None,
+            first_launch_at: now,
+            last_launch_at: now,
+            funding_source: None,
+            funded_at: None,
+            funding_checked_at: None,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn rates_are_shares_of_ended_launches() {
+        let record = creator(1, 2, 1);
+        assert_eq!(record.ended_count(), 4);
+        assert_eq!(record.graduation_rate(), Some(0.25));
+        assert_eq!(record.rug_rate(), Some(0.5));
+
+        let fresh = creator(0, 0, 0);
+        assert_eq!(fresh.graduation_rate(), None);
+        assert_eq!(fresh.rug_rate(), None);
+    }
+}
@@ -0,0 +1,35 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::Serialize,
+};
+
+/// A row of `token_metadata`: what a token's metadata URI pointed at.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OffchainMetadata {
+    pub mint_address: String,
+    pub uri: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    /// See `metadata_manager::description_hash`.
+    pub description_hash: Option<i64>,
+    pub image: Option<String>,
+    /// See `copycat::image_hash`; `None` if the image couldn't be fetched
+    /// or decoded.
+    pub image_hash: Option<i64>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+    /// 0-1; see `metadata_manager::metadata_score`.
+    pub metadata_score: f64,
+    /// Why the JSON couldn't be fetched or parsed, if it couldn't.
+    pub fetch_error: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// How many other tokens use the same image or description.
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct MetadataReuse {
+    pub image_count: i64,
+    pub description_count: i64,
+}
@@ -1 +1,9 @@
-pub mod token;
\ No newline at end of file
+pub mod token;
+pub mod rug_verdict;
+pub mod trade;
+pub mod candle;
+pub mod creator;
+pub mod signal;
+pub mod wallet;
+pub mod metadata;
+pub mod copycat;
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// A stored rug-check verdict.
+#[derive(Debug, sqlx::FromRow)]
+pub struct RugVerdictRecord {
+    pub id: i64,
+    pub mint_address: String,
+    pub checked_at: DateTime<Utc>,
+    pub status: String,
+    pub confidence: f64,
+    /// The serialized `Finding`s behind the verdict.
+    pub risk_factors: Value,
+    pub failures: Value,
+}
+
+/// A raw provider response recorded alongside a verdict.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ProviderResponseRecord {
+    pub provider: String,
+    pub http_status: Option<i32>,
+    pub latency_ms: i32,
+    pub body: Option<Value>,
+    pub error: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+
+/// Something a strategy or alert may want to react to as soon as it lands,
+/// published on the broadcast channel from `DevSellDetector::subscribe`.
+#[derive(Debug, Clone)]
+pub enum TokenSignal {
+    DevSold(DevSold),
+}
+
+/// The creator of a tracked token, or a wallet linked to it, sold.
+#[derive(Debug, Clone)]
+pub struct DevSold {
+    pub mint_address: String,
+    pub creator_address: String,
+    /// The wallet that sold: the creator or one linked to it.
+    pub wallet: String,
+    pub signature: String,
+    pub slot: i64,
+    /// Raw token amount sold.
+    pub token_amount: i64,
+    /// Share of the wallet's holdings this sell disposed of, 0-100.
+    pub sold_percent: f64,
+    /// Share of everything the creator's wallets bought that has now been
+    /// sold, 0-100.
+    pub total_sold_percent: f64,
+    /// Whether this sell took `total_sold_percent` past the dump threshold.
+    /// Only one sell per token has it set.
+    pub dumped: bool,
+    pub traded_at: DateTime<Utc>,
+}
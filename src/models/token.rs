@@ -1,5 +1,7 @@
 use chrono::Utc;
+use crate::models::copycat::CopyTag;
 use sqlx::types::BigDecimal;
+use std::{fmt, str::FromStr};
 
 #[derive(Debug)]
 pub struct TokenInfo {
@@ -8,7 +10,14 @@ pub struct TokenInfo {
     pub created_at: Option<chrono::DateTime<Utc>>,
     pub symbol: Option<String>,
     pub bonding_address: Option<String>,
-    
+    /// From the create instruction; kept with the off-chain metadata rather
+    /// than in `tokens`.
+    pub name: Option<String>,
+    /// Where the off-chain metadata JSON lives.
+    pub uri: Option<String>,
+    /// The earlier launch this one copies, if its name or symbol gives it
+    /// away at detection.
+    pub copy: Option<CopyTag>,
 }
 
 impl Default for TokenInfo {
@@ -19,23 +28,146 @@ impl Default for TokenInfo {
             symbol: None,
             bonding_address: None,
             creator_address: None,
+            name: None,
+            uri: None,
+            copy: None,
         }
     }
 }
 
 
+/// Where a token is in its lifecycle:
+/// detected → monitoring → graduated / abandoned / rugged, with filtered as
+/// a side branch for tokens we stop considering but keep watching until
+/// they reach one of those outcomes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenStatus {
+    Detected,
+    Monitoring,
+    Graduated,
+    Abandoned,
+    Rugged,
+    Filtered,
+}
+
+impl TokenStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenStatus::Detected => "detected",
+            TokenStatus::Monitoring => "monitoring",
+            TokenStatus::Graduated => "graduated",
+            TokenStatus::Abandoned => "abandoned",
+            TokenStatus::Rugged => "rugged",
+            TokenStatus::Filtered => "filtered",
+        }
+    }
+
+    /// Terminal statuses are outcomes and never change again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TokenStatus::Graduated | TokenStatus::Abandoned | TokenStatus::Rugged)
+    }
+
+    /// Statuses only move forward: a detected token may go anywhere else, a
+    /// monitored one may be filtered or reach an outcome, and a filtered one
+    /// may only reach an outcome.
+    pub fn can_transition_to(&self, next: TokenStatus) -> bool {
+        match self {
+            TokenStatus::Detected => next != TokenStatus::Detected,
+            TokenStatus::Monitoring => next == TokenStatus::Filtered || next.is_terminal(),
+            TokenStatus::Filtered => next.is_terminal(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for TokenStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TokenStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "detected" => Ok(TokenStatus::Detected),
+            "monitoring" => Ok(TokenStatus::Monitoring),
+            "graduated" => Ok(TokenStatus::Graduated),
+            "abandoned" => Ok(TokenStatus::Abandoned),
+            "rugged" => Ok(TokenStatus::Rugged),
+            "filtered" => Ok(TokenStatus::Filtered),
+            other => Err(anyhow::anyhow!("Unknown token status: {other}")),
+        }
+    }
+}
+
+/// A row of `tokens`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TokenRecord {
+    pub id: i64,
+    pub mint_address: String,
+    pub creator_address: Option<String>,
+    pub created_at: Option<chrono::DateTime<Utc>>,
+    pub symbol: Option<String>,
+    pub bonding_address: Option<String>,
+    pub status: String,
+    pub status_changed_at: chrono::DateTime<Utc>,
+}
+
+impl TokenRecord {
+    pub fn status(&self) -> anyhow::Result<TokenStatus> {
+        TokenStatus::from_str(&self.status)
+    }
+}
+
+/// A row of `token_status_history`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct TokenStatusChange {
+    pub mint_address: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub reason: String,
+    pub changed_at: chrono::DateTime<Utc>,
+}
+
+/// A launch evaluation that failed, kept so the token isn't silently dropped.
+#[derive(Debug, sqlx::FromRow)]
+pub struct EvaluationFailure {
+    pub stage: String,
+    pub error: String,
+    pub failed_at: chrono::DateTime<Utc>,
+}
+
 // Expanded models.rs
 
-#[derive(Debug, Clone)]
+/// Features of a token as of `age_secs` after its launch, computed by
+/// `analysis_manager::FeatureExtractor` and stored in `token_analysis`.
+#[derive(Debug, Clone, PartialEq)]
 pub struct TokenAnalysis {
     pub mint_address: String,
+    /// Seconds after launch the features describe.
+    pub age_secs: i64,
+    /// Percent of the sellable supply bought, 0-100.
     pub bonding_curve_progress: f32,
-    pub ilv: f32, // Initial Liquidity Velocity
-    pub social_replies: u32,
+    pub ilv: f32, // Initial Liquidity Velocity, SOL per minute
+    /// Quality of the off-chain metadata, 0-1.
     pub metadata_score: f32,
-    pub creator_age: i64, // Block time delta
+    pub creator_age: i64, // Seconds since the creator's first launch we saw
+    /// Share of SOL volume that was sells, 0-1.
     pub sell_pressure: f32,
+    /// Wallets with a positive net token balance from trades.
+    pub holder_count: u32,
+    /// Share of the held supply in the ten largest balances, 0-1.
+    pub top10_holder_share: f32,
+    /// Copies of the launch this one copies, this one included, or of this
+    /// launch if it is an original.
+    pub clone_count: u32,
+    /// `TEMPORAL_SLICES` equal slices of the token's life so far, each
+    /// contributing trade count, buy SOL and sell SOL in that order.
     pub temporal_features: Vec<f32>,
+    /// Risk model's probability that buying at this age doesn't pay off,
+    /// 0-1; 0 when no model is loaded. Not a model input.
     pub risk_score: f32,
 }
 
@@ -43,21 +175,121 @@ pub struct TokenAnalysis {
 pub struct TokenMetrics {
     /// The token's mint address; used to look up the token id.
     pub mint_address: String,
+    /// When the sample was taken, as opposed to when the row was written.
+    pub sampled_at: chrono::DateTime<Utc>,
+    /// Seconds between the launch and `sampled_at`.
+    pub token_age_secs: i64,
     pub bonding_percent: f64,
     pub ilv: f64,
-    pub social_replies: i32,
     pub safety_score: f64,
     pub liquidity: f64,
     pub holders: i32,
     pub volume: Option<BigDecimal>,
     pub buy_volume: Option<BigDecimal>,
     pub sell_volume: Option<BigDecimal>,
+    /// `volume` less the round trips of wash-trading wallets.
+    pub organic_volume: Option<BigDecimal>,
+    /// Share of volume, 0-100, traded by the busiest wallets.
+    pub top_trader_share: Option<f64>,
+}
+
+/// How a token turned out, seen from one feature snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingOutcome {
+    /// The curve completed.
+    Graduated,
+    /// The price reached the target multiple of the snapshot's price.
+    PeakMultiple,
+    Rugged,
+    Abandoned,
 }
 
-#[derive(Debug, sqlx::FromRow)] 
+impl TrainingOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrainingOutcome::Graduated => "graduated",
+            TrainingOutcome::PeakMultiple => "peak_multiple",
+            TrainingOutcome::Rugged => "rugged",
+            TrainingOutcome::Abandoned => "abandoned",
+        }
+    }
+
+    /// Whether buying at the snapshot would have paid off.
+    pub fn is_positive(&self) -> bool {
+        matches!(self, TrainingOutcome::Graduated | TrainingOutcome::PeakMultiple)
+    }
+}
+
+impl fmt::Display for TrainingOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `TokenAnalysis` snapshot labelled with the token's outcome. A row of
+/// `model_training_data`.
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct ModelTrainingData {
     pub id: i64,
+    pub mint_address: String,
+    /// Age of the snapshot the features come from.
+    pub age_secs: i64,
+    /// Layout of `features`; see `analysis_manager::FEATURE_SCHEMA_VERSION`.
+    pub schema_version: i32,
     pub features: Vec<f32>,
+    /// 1 if `outcome_label` is positive, otherwise 0.
     pub outcome: f32,
+    pub outcome_label: String,
+    /// Highest price after the snapshot over the price at the snapshot.
+    pub peak_multiple: f32,
+    /// Largest fall from a running high after the snapshot, 0-1.
+    pub max_drawdown: f32,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [TokenStatus; 6] = [
+        TokenStatus::Detected,
+        TokenStatus::Monitoring,
+        TokenStatus::Graduated,
+        TokenStatus::Abandoned,
+        TokenStatus::Rugged,
+        TokenStatus::Filtered,
+    ];
+
+    #[test]
+    fn filtered_tokens_can_still_reach_an_outcome() {
+        assert!(!TokenStatus::Filtered.is_terminal());
+        for next in [TokenStatus::Graduated, TokenStatus::Rugged, TokenStatus::Abandoned] {
+            assert!(TokenStatus::Filtered.can_transition_to(next));
+            assert!(TokenStatus::Monitoring.can_transition_to(next));
+        }
+        for next in [TokenStatus::Detected, TokenStatus::Monitoring, TokenStatus::Filtered] {
+            assert!(!TokenStatus::Filtered.can_transition_to(next));
+        }
+    }
+
+    #[test]
+    fn statuses_never_move_backwards() {
+        assert!(TokenStatus::Monitoring.can_transition_to(TokenStatus::Filtered));
+        assert!(!TokenStatus::Monitoring.can_transition_to(TokenStatus::Detected));
+        assert!(!TokenStatus::Monitoring.can_transition_to(TokenStatus::Monitoring));
+        for status in ALL {
+            assert!(!status.can_transition_to(TokenStatus::Detected));
+            if status.is_terminal() {
+                assert!(ALL.iter().all(|next| !status.can_transition_to(*next)), "{status} moved on");
+            }
+        }
+    }
+
+    #[test]
+    fn statuses_round_trip_through_strings() {
+        for status in ALL {
+            assert_eq!(TokenStatus::from_str(status.as_str()).unwrap(), status);
+        }
+        assert!(TokenStatus::from_str("unknown").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use {
+    crate::utils::pump_fun::{AmmSwapEvent, TradeEvent, TOKEN_DECIMALS},
+    chrono::{DateTime, Utc},
+    solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey},
+};
+
+/// Where a trade executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TradeVenue {
+    /// The pump.fun bonding curve.
+    Curve,
+    /// The pump.fun AMM pool a completed curve migrates to.
+    Amm,
+}
+
+impl TradeVenue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeVenue::Curve => "curve",
+            TradeVenue::Amm => "amm",
+        }
+    }
+}
+
+/// A pump.fun trade, decoded from the curve's `TradeEvent` or an AMM swap.
+/// Amounts are raw base units: lamports for SOL, 10^-6 for tokens.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Trade {
+    pub signature: String,
+    /// Position of the event within its transaction.
+    pub event_index: i32,
+    pub slot: i64,
+    pub mint_address: String,
+    pub trader: String,
+    pub is_buy: bool,
+    pub sol_amount: i64,
+    pub token_amount: i64,
+    /// Curve reserves after the trade.
+    pub virtual_sol_reserves: i64,
+    pub virtual_token_reserves: i64,
+    pub traded_at: DateTime<Utc>,
+    /// A `TradeVenue` as text.
+    pub venue: String,
+}
+
+impl Trade {
+    /// # Arguments
+    /// * `event` - The decoded trade event
+    /// * `signature` - Signature of the transaction that emitted it
+    /// * `event_index` - Position of the event within the transaction
+    /// * `slot` - Slot the transaction landed in
+    pub fn from_event(event: &TradeEvent, signature: String, event_index: i32, slot: u64) -> Self {
+        Trade {
+            signature,
+            event_index,
+            slot: slot as i64,
+            mint_address: event.mint.to_string(),
+            trader: event.user.to_string(),
+            is_buy: event.is_buy,
+            sol_amount: event.sol_amount as i64,
+            token_amount: event.token_amount as i64,
+            virtual_sol_reserves: event.virtual_sol_reserves as i64,
+            virtual_token_reserves: event.virtual_token_reserves as i64,
+            traded_at: DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(Utc::now),
+            venue: TradeVenue::Curve.as_str().to_string(),
+        }
+    }
+
+    /// # Arguments
+    /// * `event` - The decoded AMM swap
+    /// * `mint` - The pool's base token
+    /// * `signature` - Signature of the transaction that emitted it
+    /// * `event_index` - Position of the event within the transaction
+    /// * `slot` - Slot the transaction landed in
+    pub fn from_amm_swap(event: &AmmSwapEvent, mint: &Pubkey, signature: String, event_index: i32, slot: u64) -> Self {
+        // The event carries pre-swap reserves; store post-swap ones like the
+        // curve does. Fees are ignored, so these are approximate.
+        let (base_reserves, quote_reserves) = if event.is_buy {
+            (
+                event.pool_base_reserves.saturating_sub(event.base_amount),
+                event.pool_quote_reserves.saturating_add(event.quote_amount),
+            )
+        } else {
+            (
+                event.pool_base_reserves.saturating_add(event.base_amount),
+                event.pool_quote_reserves.saturating_sub(event.quote_amount),
+            )
+        };
+        Trade {
+            signature,
+            event_index,
+            slot: slot as i64,
+            mint_address: mint.to_string(),
+            trader: event.user.to_string(),
+            is_buy: event.is_buy,
+            sol_amount: event.quote_amount as i64,
+            token_amount: event.base_amount as i64,
+            virtual_sol_reserves: quote_reserves as i64,
+            virtual_token_reserves: base_reserves as i64,
+            traded_at: DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(Utc::now),
+            venue: TradeVenue::Amm.as_str().to_string(),
+        }
+    }
+
+    /// Execution price of one whole token in SOL, or `None` for a trade
+    /// that moved no tokens.
+    pub fn price_sol(&self) -> Option<f64> {
+        if self.token_amount <= 0 {
+            return None;
+        }
+        let sol = self.sol_amount as f64 / LAMPORTS_PER_SOL as f64;
+        let tokens = self.token_amount as f64 / 10f64.powi(TOKEN_DECIMALS as i32);
+        Some(sol / tokens)
+    }
+}
+
+/// Builder for trades in tests. `Trade::test` is a buy of nothing against
+/// the initial curve reserves, stamped now, with a unique signature.
+#[cfg(test)]
+impl Trade {
+    pub fn test(mint_address: &str) -> Self {
+        use {
+            crate::utils::pump_fun::{INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES},
+            std::sync::atomic::{AtomicU64, Ordering},
+        };
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Trade {
+            signature: format!("test-{}", NEXT.fetch_add(1, Ordering::Relaxed)),
+            event_index: 0,
+            slot: 1,
+            mint_address: mint_address.to_string(),
+            trader: "trader".to_string(),
+            is_buy: true,
+            sol_amount: 0,
+            token_amount: 0,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES as i64,
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES as i64,
+            traded_at: Utc::now(),
+            venue: TradeVenue::Curve.as_str().to_string(),
+        }
+    }
+
+    pub fn signature(mut self, signature: &str) -> Self {
+        self.signature = signature.to_string();
+        self
+    }
+
+    pub fn by(mut self, trader: &str) -> Self {
+        self.trader = trader.to_string();
+        self
+    }
+
+    pub fn buy(mut self, sol_amount: i64, token_amount: i64) -> Self {
+        self.is_buy = true;
+        self.sol_amount = sol_amount;
+        self.token_amount = token_amount;
+        self
+    }
+
+    pub fn sell(mut self, sol_amount: i64, token_amount: i64) -> Self {
+        self.is_buy = false;
+        self.sol_amount = sol_amount;
+        self.token_amount = token_amount;
+        self
+    }
+
+    /// A buy of one whole token at `price` SOL.
+    pub fn priced(self, price: f64) -> Self {
+        self.buy((price * LAMPORTS_PER_SOL as f64) as i64, 10i64.pow(TOKEN_DECIMALS))
+    }
+
+    pub fn at(mut self, traded_at: DateTime<Utc>) -> Self {
+        self.traded_at = traded_at;
+        self
+    }
+
+    pub fn in_slot(mut self, slot: i64) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    /// Curve reserves after the trade.
+    pub fn reserves(mut self, virtual_sol_reserves: i64, virtual_token_reserves: i64) -> Self {
+        self.virtual_sol_reserves = virtual_sol_reserves;
+        self.virtual_token_reserves = virtual_token_reserves;
+        self
+    }
+
+    pub fn on(mut self, venue: TradeVenue) -> Self {
+        self.venue = venue.as_str().to_string();
+        self
+    }
+}
+
+/// Traded volume for a token, in lamports.
+#[derive(Debug, Default, Clone)]
+pub struct TradeVolume {
+    pub buy_volume: i64,
+    pub sell_volume: i64,
+}
+
+impl TradeVolume {
+    /// Sums the buys and sells in `trades`.
+    pub fn of(trades: &[Trade]) -> Self {
+        let mut volume = TradeVolume::default();
+        for trade in trades {
+            if trade.is_buy {
+                volume.buy_volume += trade.sol_amount;
+            } else {
+                volume.sell_volume += trade.sol_amount;
+            }
+        }
+        volume
+    }
+}
@@ -0,0 +1,48 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::Serialize,
+};
+
+/// A row of `wallet_funding`: one edge of the funding graph.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WalletFunding {
+    pub wallet: String,
+    /// The wallet that sent `wallet` its first SOL; `None` if it couldn't
+    /// be found.
+    pub funder: Option<String>,
+    pub funded_at: Option<DateTime<Utc>>,
+    /// When the lookup ran.
+    pub checked_at: DateTime<Utc>,
+}
+
+/// How a wallet returned by `FundingGraph::related_wallets` is reached from
+/// the one asked about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// Funded the wallet, directly or through others.
+    Funder,
+    /// Was funded by the wallet, directly or through others.
+    Funded,
+    /// Shares a funder with the wallet somewhere up the graph.
+    Sibling,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedWallet {
+    pub address: String,
+    pub relation: Relation,
+    /// Funding edges between the two wallets.
+    pub hops: usize,
+}
+
+/// The closest wallet both sides of a `FundingGraph::common_funder` query
+/// trace back to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommonFunder {
+    pub funder: String,
+    /// Funding edges from each wallet up to `funder`; 0 if the wallet is
+    /// the funder itself.
+    pub hops_a: usize,
+    pub hops_b: usize,
+}
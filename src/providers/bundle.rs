@@ -0,0 +1,133 @@
+use {
+    super::{Finding, ProviderError, ProviderReport, RiskProvider},
+    crate::{
+        managers::{db_manager::DbManager, funding_manager::FundingGraph},
+        models::wallet::Relation,
+        utils::{
+            bundle_check::{detect_bundle, early_buyers, BundleConfig},
+            rug_check::{RiskFactor, RiskSource},
+        },
+    },
+    async_trait::async_trait,
+    futures::future::join_all,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::HashMap, sync::Arc, time::Duration},
+};
+
+/// Longest a single funding lookup may take. Wallets that miss it are
+/// treated as having an unknown funder, so a slow RPC only weakens the
+/// funding signals instead of failing the provider.
+const FUNDING_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Bundled launches and sniper clusters, from the token's stored trades in
+/// the first slots after launch and where the early buyers got their SOL.
+/// Lookups go through the funding graph, so the edges they find are cached
+/// for the dev sell detector and later launches.
+///
+/// Besides direct funding, early buyers anywhere near the creator in the
+/// graph count towards the bundle, and each bundle wallet records the
+/// closest funder it shares with the creator.
+pub struct BundleProvider {
+    db_manager: Arc<DbManager>,
+    funding: Arc<FundingGraph>,
+    config: BundleConfig,
+}
+
+impl BundleProvider {
+    pub fn new(db_manager: Arc<DbManager>, funding: Arc<FundingGraph>, config: BundleConfig) -> Self {
+        BundleProvider {
+            db_manager,
+            funding,
+            config,
+        }
+    }
+
+    /// First funder of each wallet that has one and answered in time.
+    async fn funders(&self, wallets: Vec<String>) -> HashMap<String, String> {
+        let lookups = wallets.into_iter().map(|wallet| async move {
+            match tokio::time::timeout(FUNDING_LOOKUP_TIMEOUT, self.funding.funder(&wallet)).await {
+                Ok(Ok(Some(funder))) => Some((wallet, funder)),
+                _ => None,
+            }
+        });
+        join_all(lookups).await.into_iter().flatten().collect()
+    }
+
+    /// How wallets within `max_hops` of the creator relate to it, or nothing
+    /// if the lookup fails or runs out of time.
+    async fn relatives(&self, creator_address: &str) -> HashMap<String, Relation> {
+        match tokio::time::timeout(FUNDING_LOOKUP_TIMEOUT, self.funding.related_wallets(creator_address)).await {
+            Ok(Ok(related)) => related.into_iter().map(|wallet| (wallet.address, wallet.relation)).collect(),
+            _ => HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for BundleProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::Bundle
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let mut report = ProviderReport::default();
+        let mint_address = mint.to_string();
+        let trades = self.db_manager.get_trades(&mint_address).await?;
+        if trades.is_empty() {
+            return Ok(report);
+        }
+        let creator_address = self
+            .db_manager
+            .get_token(&mint_address)
+            .await?
+            .and_then(|token| token.creator_address);
+
+        // The creator's funder is usually cached by the creator tracker.
+        let lookups: Vec<String> = early_buyers(&trades, &self.config)
+            .into_iter()
+            .filter(|buyer| Some(buyer) != creator_address.as_ref())
+            .take(self.config.funding_lookups)
+            .chain(creator_address.clone())
+            .collect();
+        let funders = self.funders(lookups).await;
+        // Read after the lookups above, so buyers they traced are included.
+        let relatives = match &creator_address {
+            Some(creator_address) => self.relatives(creator_address).await,
+            None => HashMap::new(),
+        };
+
+        let mut bundle = detect_bundle(&trades, creator_address.as_deref(), &funders, &relatives, &self.config);
+        if let Some(creator_address) = &creator_address {
+            let lookups = bundle
+                .bundle
+                .iter_mut()
+                .filter(|wallet| &wallet.address != creator_address)
+                .map(|wallet| async move {
+                    let lookup = self.funding.common_funder(creator_address, &wallet.address);
+                    if let Ok(Ok(common)) = tokio::time::timeout(FUNDING_LOOKUP_TIMEOUT, lookup).await {
+                        wallet.common_funder = common;
+                    }
+                });
+            join_all(lookups).await;
+        }
+        if !bundle.bundle.is_empty() && bundle.bundle_supply_percent >= self.config.supply_percent {
+            report.findings.push(Finding::new(
+                RiskSource::Bundle,
+                RiskFactor::BundledLaunch,
+                json!(bundle.bundle_supply_percent),
+                serde_json::to_value(&bundle.bundle)?,
+            ));
+        }
+        if !bundle.snipers.is_empty() && bundle.sniper_supply_percent >= self.config.supply_percent {
+            report.findings.push(Finding::new(
+                RiskSource::Bundle,
+                RiskFactor::SniperCluster,
+                json!(bundle.sniper_supply_percent),
+                serde_json::to_value(&bundle.snipers)?,
+            ));
+        }
+        report.raw = serde_json::to_value(&bundle)?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,92 @@
+use {
+    super::{Finding, ProviderError, ProviderReport, RiskProvider},
+    crate::{
+        managers::db_manager::DbManager,
+        utils::rug_check::{RiskFactor, RiskSource},
+    },
+    async_trait::async_trait,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+/// Ended launches needed before a creator's rug rate counts.
+const MIN_ENDED_LAUNCHES: i32 = 2;
+/// Share of ended launches that rugged above which a creator is flagged.
+const RUG_RATE_THRESHOLD: f64 = 0.5;
+/// Launches without a single graduation that make a serial launcher.
+const SERIAL_LAUNCH_COUNT: i32 = 10;
+/// Launches the creator must have sold into before its sell speed counts.
+const MIN_SOLD_LAUNCHES: i32 = 2;
+/// Average seconds from launch to the creator's first sell below which a
+/// creator is flagged.
+const EARLY_SELL_SECS: f64 = 300.0;
+
+/// The creator's record across earlier launches, from the `creators` table
+/// kept by `creator_manager::CreatorTracker`. Tokens from creators we haven't
+/// seen before get no findings.
+pub struct CreatorProvider {
+    db_manager: Arc<DbManager>,
+}
+
+impl CreatorProvider {
+    pub fn new(db_manager: Arc<DbManager>) -> Self {
+        CreatorProvider { db_manager }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for CreatorProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::Creator
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let mut report = ProviderReport::default();
+        let Some(creator_address) = self
+            .db_manager
+            .get_token(&mint.to_string())
+            .await?
+            .and_then(|token| token.creator_address)
+        else {
+            return Ok(report);
+        };
+        let Some(creator) = self.db_manager.get_creator(&creator_address).await? else {
+            return Ok(report);
+        };
+        let raw = serde_json::to_value(&creator)?;
+
+        if let Some(rug_rate) = creator
+            .rug_rate()
+            .filter(|rate| creator.ended_count() >= MIN_ENDED_LAUNCHES && *rate >= RUG_RATE_THRESHOLD)
+        {
+            report.findings.push(Finding::new(
+                RiskSource::Creator,
+                RiskFactor::CreatorRugHistory,
+                json!(rug_rate),
+                json!({ "rugged_count": creator.rugged_count, "ended_count": creator.ended_count() }),
+            ));
+        }
+        if creator.launch_count >= SERIAL_LAUNCH_COUNT && creator.graduated_count == 0 {
+            report.findings.push(Finding::new(
+                RiskSource::Creator,
+                RiskFactor::SerialLauncher,
+                json!(creator.launch_count),
+                raw["launch_count"].clone(),
+            ));
+        }
+        if let Some(first_sell_secs) = creator
+            .avg_first_sell_secs
+            .filter(|secs| creator.sold_count >= MIN_SOLD_LAUNCHES && *secs < EARLY_SELL_SECS)
+        {
+            report.findings.push(Finding::new(
+                RiskSource::Creator,
+                RiskFactor::CreatorSellsEarly,
+                json!(first_sell_secs),
+                json!({ "sold_count": creator.sold_count, "avg_first_sell_secs": first_sell_secs }),
+            ));
+        }
+        report.raw = raw;
+        Ok(report)
+    }
+}
@@ -0,0 +1,79 @@
+use {
+    super::{fetch_json, Finding, LiquidityObservation, ProviderError, ProviderReport, RiskProvider},
+    crate::utils::{http_client::HttpClient, rug_check::{GoPlusResponse, RiskFactor, RiskSource}},
+    async_trait::async_trait,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+const GOPLUS_URL: &str = "https://api.gopluslabs.io/api/v1/solana/token_security";
+
+/// GoPlus Security token audit.
+pub struct GoPlusProvider {
+    http: Arc<HttpClient>,
+}
+
+impl GoPlusProvider {
+    pub fn new(http: Arc<HttpClient>) -> Self {
+        GoPlusProvider { http }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for GoPlusProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::GoPlus
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let mint_address = mint.to_string();
+        let (http_status, raw) =
+            fetch_json(&self.http, GOPLUS_URL, &[("contract_addresses", mint_address.clone())], &[]).await?;
+        let response: GoPlusResponse = serde_json::from_value(raw.clone())?;
+
+        let mut report = ProviderReport::default();
+        let finding = |signal, value, raw| Finding::new(RiskSource::GoPlus, signal, value, raw);
+        match response.result.get(&mint_address) {
+            Some(security) => {
+                let token_raw = raw["result"][&mint_address].clone();
+                if security.mintable.status == "1" {
+                    report.findings.push(finding(
+                        RiskFactor::MintAuthorityEnabled,
+                        json!(security.mintable.authority),
+                        token_raw["mintable"].clone(),
+                    ));
+                }
+                if security.freezable.status == "1" {
+                    report.findings.push(finding(
+                        RiskFactor::FreezeAuthorityEnabled,
+                        json!(security.freezable.authority),
+                        token_raw["freezable"].clone(),
+                    ));
+                }
+                if let Some(lp_holders) = security.lp_holders.as_ref().filter(|h| !h.is_empty()) {
+                    let pool_locked = lp_holders.iter().any(|holder| holder.is_locked == 1);
+                    report.liquidity = LiquidityObservation {
+                        pool_locked: Some(pool_locked),
+                        ..Default::default()
+                    };
+                    if !pool_locked {
+                        report.findings.push(finding(
+                            RiskFactor::LiquidityNotLocked,
+                            json!(false),
+                            token_raw["lp_holders"].clone(),
+                        ));
+                    }
+                }
+            }
+            None => report.findings.push(finding(
+                RiskFactor::NoGoPlusData,
+                json!(response.message),
+                raw.clone(),
+            )),
+        }
+        report.http_status = Some(http_status);
+        report.raw = raw;
+        Ok(report)
+    }
+}
@@ -0,0 +1,317 @@
+pub mod bundle;
+pub mod creator;
+pub mod goplus;
+pub mod onchain;
+pub mod rugcheck;
+pub mod shyft;
+pub mod solsniffer;
+pub mod wash;
+
+use {
+    crate::{
+        managers::{db_manager::DbManager, funding_manager::FundingGraph},
+        utils::{
+            bundle_check::BundleConfig,
+            http_client::HttpClient,
+            rug_check::{LiquidityAnalysis, RiskFactor, RiskSource, TokenMetadata},
+            wash_check::WashConfig,
+        },
+    },
+    async_trait::async_trait,
+    futures::future::join_all,
+    serde::Serialize,
+    serde_json::Value,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::pubkey::Pubkey,
+    std::{env, error::Error, fmt, sync::Arc, time::{Duration, Instant}},
+};
+
+pub type ProviderError = Box<dyn Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// A normalized risk signal reported by a provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub source: RiskSource,
+    pub signal: RiskFactor,
+    pub severity: Severity,
+    /// The provider value that triggered the signal (a score, an authority, ...).
+    pub value: Value,
+    /// The part of the provider payload the signal was derived from.
+    pub raw: Value,
+}
+
+impl Finding {
+    pub fn new(source: RiskSource, signal: RiskFactor, value: Value, raw: Value) -> Self {
+        Finding {
+            source,
+            signal,
+            severity: signal.severity(),
+            value,
+            raw,
+        }
+    }
+}
+
+/// Partial liquidity information; each provider fills in what it knows.
+#[derive(Debug, Default, Clone)]
+pub struct LiquidityObservation {
+    pub pool_locked: Option<bool>,
+    pub liquidity_amount: Option<f64>,
+    pub creator_holdings: Option<f64>,
+    pub burn_status: Option<bool>,
+}
+
+impl LiquidityAnalysis {
+    pub fn merge(&mut self, observation: &LiquidityObservation) {
+        if let Some(pool_locked) = observation.pool_locked {
+            self.pool_locked = pool_locked;
+        }
+        if let Some(liquidity_amount) = observation.liquidity_amount {
+            self.liquidity_amount = liquidity_amount;
+        }
+        if let Some(creator_holdings) = observation.creator_holdings {
+            self.creator_holdings = creator_holdings;
+        }
+        if let Some(burn_status) = observation.burn_status {
+            self.burn_status = burn_status;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProviderReport {
+    pub findings: Vec<Finding>,
+    pub metadata: Option<TokenMetadata>,
+    pub liquidity: LiquidityObservation,
+    /// HTTP status of the provider call; `None` for on-chain providers.
+    pub http_status: Option<u16>,
+    /// The full provider response.
+    pub raw: Value,
+}
+
+#[async_trait]
+pub trait RiskProvider: Send + Sync {
+    fn source(&self) -> RiskSource;
+
+    /// Evaluates `mint` and returns the provider's normalized findings.
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProviderConfig {
+    pub enabled: bool,
+    pub timeout: Duration,
+    /// Multiplier applied to the weight of every finding from this provider.
+    pub weight: f64,
+}
+
+impl ProviderConfig {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Reads `RISK_<SOURCE>_ENABLED`, `RISK_<SOURCE>_TIMEOUT_MS` and
+    /// `RISK_<SOURCE>_WEIGHT`, falling back to defaults.
+    pub fn from_env(source: RiskSource) -> Self {
+        let prefix = format!("RISK_{}", source.name().to_uppercase());
+        let read = |suffix: &str| env::var(format!("{prefix}_{suffix}")).ok();
+        ProviderConfig {
+            enabled: read("ENABLED").map(|v| v != "false" && v != "0").unwrap_or(true),
+            timeout: read("TIMEOUT_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(Self::DEFAULT_TIMEOUT),
+            weight: read("WEIGHT").and_then(|v| v.parse().ok()).unwrap_or(1.0),
+        }
+    }
+}
+
+/// The result of running a single provider.
+pub struct ProviderOutcome {
+    pub source: RiskSource,
+    pub weight: f64,
+    pub latency: Duration,
+    pub result: Result<ProviderReport, ProviderFailure>,
+}
+
+/// Why a provider produced no report, with whatever the API sent back.
+#[derive(Debug, Clone)]
+pub struct ProviderFailure {
+    pub error: String,
+    pub http_status: Option<u16>,
+    pub body: Option<Value>,
+}
+
+impl ProviderFailure {
+    fn from_error(error: &(dyn Error + Send + Sync + 'static)) -> Self {
+        let response = error.downcast_ref::<HttpStatusError>();
+        ProviderFailure {
+            error: error.to_string(),
+            http_status: response.map(|response| response.status),
+            body: response.map(|response| response.body.clone()),
+        }
+    }
+}
+
+/// A provider API answered with a non-2xx status.
+#[derive(Debug)]
+pub struct HttpStatusError {
+    pub status: u16,
+    /// The response body: its JSON, or the raw text if it isn't JSON.
+    pub body: Value,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        };
+        let snippet: String = body.chars().take(200).collect();
+        write!(f, "HTTP {}: {}", self.status, snippet)
+    }
+}
+
+impl Error for HttpStatusError {}
+
+/// Runs every enabled provider concurrently, each under its own timeout.
+#[derive(Default)]
+pub struct RiskAggregator {
+    providers: Vec<(Arc<dyn RiskProvider>, ProviderConfig)>,
+}
+
+impl RiskAggregator {
+    pub fn new() -> Self {
+        RiskAggregator::default()
+    }
+
+    /// Registers `provider`, unless its config disables it.
+    pub fn with_provider(mut self, provider: Arc<dyn RiskProvider>, config: ProviderConfig) -> Self {
+        if config.enabled {
+            self.providers.push((provider, config));
+        }
+        self
+    }
+
+    /// Builds an aggregator with every built-in provider, configured from the environment.
+    pub fn from_env(rpc: Arc<RpcClient>, http: Arc<HttpClient>, db_manager: Arc<DbManager>, funding: Arc<FundingGraph>) -> Self {
+        let providers: Vec<Arc<dyn RiskProvider>> = vec![
+            Arc::new(onchain::OnChainProvider::new(rpc)),
+            Arc::new(creator::CreatorProvider::new(db_manager.clone())),
+            Arc::new(bundle::BundleProvider::new(db_manager.clone(), funding, BundleConfig::from_env())),
+            Arc::new(wash::WashProvider::new(db_manager, WashConfig::from_env())),
+            Arc::new(shyft::ShyftProvider::from_env(http.clone())),
+            Arc::new(rugcheck::RugCheckProvider::new(http.clone())),
+            Arc::new(goplus::GoPlusProvider::new(http.clone())),
+            Arc::new(solsniffer::SolSnifferProvider::from_env(http)),
+        ];
+        providers.into_iter().fold(RiskAggregator::new(), |aggregator, provider| {
+            let config = ProviderConfig::from_env(provider.source());
+            aggregator.with_provider(provider, config)
+        })
+    }
+
+    pub fn provider_count(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub async fn run(&self, mint: &Pubkey) -> Vec<ProviderOutcome> {
+        join_all(self.providers.iter().map(|(provider, config)| async move {
+            let started = Instant::now();
+            let result = match tokio::time::timeout(config.timeout, provider.assess(mint)).await {
+                Ok(Ok(report)) => Ok(report),
+                Ok(Err(e)) => Err(ProviderFailure::from_error(e.as_ref())),
+                Err(_) => Err(ProviderFailure {
+                    error: format!("timed out after {:?}", config.timeout),
+                    http_status: None,
+                    body: None,
+                }),
+            };
+            ProviderOutcome {
+                source: provider.source(),
+                weight: config.weight,
+                latency: started.elapsed(),
+                result,
+            }
+        }))
+        .await
+    }
+}
+
+/// GETs `url` and returns the HTTP status alongside the decoded JSON body.
+/// A non-2xx response fails with an `HttpStatusError` carrying its status
+/// and body, so the rug check can record them.
+pub(crate) async fn fetch_json(
+    http: &HttpClient,
+    url: &str,
+    query: &[(&str, String)],
+    headers: &[(&str, String)],
+) -> Result<(u16, Value), ProviderError> {
+    let response = http.get(url, query, headers).await?;
+    let status = response.status.as_u16();
+    if !response.is_success() {
+        let body = response
+            .json()
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&response.body).into_owned()));
+        return Err(Box::new(HttpStatusError { status, body }));
+    }
+    Ok((status, response.json()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::utils::{
+            http_client::HttpClientConfig,
+            test_server::{serve, Reply},
+        },
+        serde_json::json,
+    };
+
+    fn client() -> Arc<HttpClient> {
+        HttpClient::new(HttpClientConfig { max_retries: 0, ..HttpClientConfig::default() }).unwrap()
+    }
+
+    #[tokio::test]
+    async fn failed_calls_keep_status_and_json_body() {
+        let base = serve(|_: &str| Reply::json(500, &json!({ "error": "upstream down" }))).await;
+        let error = fetch_json(&client(), &format!("{base}/report"), &[], &[]).await.unwrap_err();
+        let failure = ProviderFailure::from_error(error.as_ref());
+        assert_eq!(failure.http_status, Some(500));
+        assert_eq!(failure.body, Some(json!({ "error": "upstream down" })));
+        assert!(failure.error.starts_with("HTTP 500: "));
+    }
+
+    #[tokio::test]
+    async fn failed_calls_keep_non_json_bodies_as_text() {
+        let base = serve(|_: &str| Reply::new(403, "Forbidden")).await;
+        let error = fetch_json(&client(), &format!("{base}/report"), &[], &[]).await.unwrap_err();
+        let failure = ProviderFailure::from_error(error.as_ref());
+        assert_eq!(failure.http_status, Some(403));
+        assert_eq!(failure.body, Some(Value::String("Forbidden".to_string())));
+    }
+
+    #[tokio::test]
+    async fn successful_calls_return_status_and_json() {
+        let base = serve(|_: &str| Reply::json(200, &json!({ "score": 3 }))).await;
+        let (status, body) = fetch_json(&client(), &format!("{base}/report"), &[], &[]).await.unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "score": 3 }));
+    }
+
+    #[test]
+    fn other_errors_have_no_status_or_body() {
+        let error: ProviderError = "connection reset".into();
+        let failure = ProviderFailure::from_error(error.as_ref());
+        assert_eq!(failure.error, "connection reset");
+        assert_eq!(failure.http_status, None);
+        assert_eq!(failure.body, None);
+    }
+}
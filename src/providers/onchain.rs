@@ -0,0 +1,53 @@
+use {
+    super::{Finding, ProviderError, ProviderReport, RiskProvider},
+    crate::utils::{onchain_check::OnChainChecker, rug_check::{RiskFactor, RiskSource, TokenMetadata}},
+    async_trait::async_trait,
+    serde_json::{json, Value},
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+/// Mint and Metaplex metadata checks read directly from the chain.
+pub struct OnChainProvider {
+    checker: OnChainChecker,
+}
+
+impl OnChainProvider {
+    pub fn new(rpc: Arc<RpcClient>) -> Self {
+        OnChainProvider {
+            checker: OnChainChecker::new(rpc),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for OnChainProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::OnChain
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let (metadata, risk_factors) = self.checker.check(mint).await?;
+        let raw = serde_json::to_value(&metadata)?;
+        let findings = risk_factors
+            .into_iter()
+            .map(|factor| Finding::new(RiskSource::OnChain, factor, factor_value(factor, &metadata), raw.clone()))
+            .collect();
+        Ok(ProviderReport {
+            findings,
+            metadata: Some(metadata),
+            raw,
+            ..Default::default()
+        })
+    }
+}
+
+fn factor_value(factor: RiskFactor, metadata: &TokenMetadata) -> Value {
+    match factor {
+        RiskFactor::MintAuthorityEnabled => json!(metadata.mint_authority),
+        RiskFactor::FreezeAuthorityEnabled => json!(metadata.freeze_authority),
+        RiskFactor::MutableMetadata => json!(metadata.update_authority),
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,59 @@
+use {
+    super::{fetch_json, Finding, ProviderError, ProviderReport, RiskProvider},
+    crate::utils::{http_client::HttpClient, rug_check::{RiskFactor, RiskSource, RugCheckApiResponse}},
+    async_trait::async_trait,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+/// Summary score above which a token is flagged.
+const RUGCHECK_SCORE_THRESHOLD: u32 = 400;
+
+/// rugcheck.xyz token report summary.
+pub struct RugCheckProvider {
+    http: Arc<HttpClient>,
+}
+
+impl RugCheckProvider {
+    pub fn new(http: Arc<HttpClient>) -> Self {
+        RugCheckProvider { http }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for RugCheckProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::RugCheck
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let url = format!("https://api.rugcheck.xyz/v1/tokens/{mint}/report/summary");
+        let (http_status, raw) =
+            fetch_json(&self.http, &url, &[], &[("accept", "application/json".to_string())]).await?;
+        let response: RugCheckApiResponse = serde_json::from_value(raw.clone())?;
+
+        let mut report = ProviderReport::default();
+        if let Some(score) = response.score.filter(|score| *score > RUGCHECK_SCORE_THRESHOLD) {
+            report.findings.push(Finding::new(
+                RiskSource::RugCheck,
+                RiskFactor::HighRugCheckScore,
+                json!(score),
+                raw["score"].clone(),
+            ));
+        }
+        for (i, risk) in response.risks.iter().flatten().enumerate() {
+            if risk.level == "danger" {
+                report.findings.push(Finding::new(
+                    RiskSource::RugCheck,
+                    RiskFactor::RugCheckDanger,
+                    json!(risk.name),
+                    raw["risks"][i].clone(),
+                ));
+            }
+        }
+        report.http_status = Some(http_status);
+        report.raw = raw;
+        Ok(report)
+    }
+}
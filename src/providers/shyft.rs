@@ -0,0 +1,73 @@
+use {
+    super::{fetch_json, Finding, LiquidityObservation, ProviderError, ProviderReport, RiskProvider},
+    crate::utils::{
+        http_client::HttpClient,
+        rug_check::{LiquidityResult, RiskFactor, RiskSource, ShyftLiquidityResponse},
+    },
+    async_trait::async_trait,
+    serde_json::Value,
+    solana_sdk::pubkey::Pubkey,
+    std::{env, sync::Arc},
+};
+
+const SHYFT_URL: &str = "https://defi.shyft.to/v0/pools/get_liquidity_details";
+const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Shyft DeFi API liquidity pool lookup.
+pub struct ShyftProvider {
+    http: Arc<HttpClient>,
+    api_key: Option<String>,
+}
+
+impl ShyftProvider {
+    pub fn from_env(http: Arc<HttpClient>) -> Self {
+        ShyftProvider {
+            http,
+            api_key: env::var("SHYFT_API_KEY").ok(),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for ShyftProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::Shyft
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let api_key = self.api_key.clone().ok_or("Missing SHYFT API Key for LP analysis")?;
+        let (http_status, raw) =
+            fetch_json(&self.http, SHYFT_URL, &[("address", mint.to_string())], &[("x-api-key", api_key)]).await?;
+        let response: ShyftLiquidityResponse = serde_json::from_value(raw.clone())?;
+
+        let mut report = ProviderReport::default();
+        match response.result {
+            Some(pool) => {
+                report.liquidity = LiquidityObservation {
+                    liquidity_amount: Some(sol_side_liquidity(&pool)),
+                    ..Default::default()
+                }
+            }
+            None => report.findings.push(Finding::new(
+                RiskSource::Shyft,
+                RiskFactor::NoLiquidityPool,
+                Value::Null,
+                raw.clone(),
+            )),
+        }
+        report.http_status = Some(http_status);
+        report.raw = raw;
+        Ok(report)
+    }
+}
+
+/// SOL held by the pool, in whole SOL.
+fn sol_side_liquidity(pool: &LiquidityResult) -> f64 {
+    pool.liquidity
+        .iter()
+        .flat_map(|liquidity| [&liquidity.token_a, &liquidity.token_b])
+        .flatten()
+        .filter(|token| token.address.as_deref() == Some(WRAPPED_SOL_MINT))
+        .filter_map(|token| Some(token.amount? as f64 / 10f64.powi(token.decimals.unwrap_or(9) as i32)))
+        .sum()
+}
@@ -0,0 +1,74 @@
+use {
+    super::{fetch_json, Finding, LiquidityObservation, ProviderError, ProviderReport, RiskProvider},
+    crate::utils::{http_client::HttpClient, rug_check::{RiskFactor, RiskSource, SolSnifferResponse}},
+    async_trait::async_trait,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::{env, sync::Arc},
+};
+
+/// SolSniffer scores tokens 0-100, higher is safer.
+const SOLSNIFFER_SCORE_THRESHOLD: i32 = 50;
+
+/// SolSniffer token audit.
+pub struct SolSnifferProvider {
+    http: Arc<HttpClient>,
+    api_key: Option<String>,
+}
+
+impl SolSnifferProvider {
+    pub fn from_env(http: Arc<HttpClient>) -> Self {
+        SolSnifferProvider {
+            http,
+            api_key: env::var("TOKEN_SNIFFER_KEY").ok(),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for SolSnifferProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::SolSniffer
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let api_key = self.api_key.clone().ok_or("Missing TOKEN_SNIFFER_KEY environment variable")?;
+        let url = format!("https://solsniffer.com/api/v2/token/{mint}");
+        let (http_status, raw) = fetch_json(&self.http, &url, &[], &[("X-API-KEY", api_key)]).await?;
+        let response: SolSnifferResponse = serde_json::from_value(raw.clone())?;
+        let token_data = &response.token_data;
+        let token_raw = &raw["tokenData"];
+
+        let mut report = ProviderReport::default();
+        if token_data.score < SOLSNIFFER_SCORE_THRESHOLD {
+            report.findings.push(Finding::new(
+                RiskSource::SolSniffer,
+                RiskFactor::LowSolSnifferScore,
+                json!(token_data.score),
+                token_raw["score"].clone(),
+            ));
+        }
+        if token_data.audit_risk.top10_holders {
+            report.findings.push(Finding::new(
+                RiskSource::SolSniffer,
+                RiskFactor::ConcentratedHolders,
+                json!(true),
+                token_raw["auditRisk"].clone(),
+            ));
+        }
+        let creator_holdings = token_data
+            .owners_list
+            .iter()
+            .filter(|owner| owner.address == token_data.token_overview.deployer)
+            .filter_map(|owner| owner.percentage.parse::<f64>().ok())
+            .sum();
+        report.liquidity = LiquidityObservation {
+            burn_status: Some(token_data.audit_risk.lp_burned),
+            creator_holdings: Some(creator_holdings),
+            ..Default::default()
+        };
+        report.http_status = Some(http_status);
+        report.raw = raw;
+        Ok(report)
+    }
+}
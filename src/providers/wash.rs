@@ -0,0 +1,61 @@
+use {
+    super::{Finding, ProviderError, ProviderReport, RiskProvider},
+    crate::{
+        managers::db_manager::DbManager,
+        utils::{
+            rug_check::{RiskFactor, RiskSource},
+            wash_check::{analyze_wash_trading, WashConfig},
+        },
+    },
+    async_trait::async_trait,
+    serde_json::json,
+    solana_sdk::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+/// Manufactured volume, from the token's stored trades: wallets cycling buys
+/// and sells, and volume concentrated in a handful of wallets. Tokens with
+/// fewer than `min_trades` trades get no findings.
+pub struct WashProvider {
+    db_manager: Arc<DbManager>,
+    config: WashConfig,
+}
+
+impl WashProvider {
+    pub fn new(db_manager: Arc<DbManager>, config: WashConfig) -> Self {
+        WashProvider { db_manager, config }
+    }
+}
+
+#[async_trait]
+impl RiskProvider for WashProvider {
+    fn source(&self) -> RiskSource {
+        RiskSource::Wash
+    }
+
+    async fn assess(&self, mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+        let mut report = ProviderReport::default();
+        let trades = self.db_manager.get_trades(&mint.to_string()).await?;
+        let wash = analyze_wash_trading(&trades, &self.config);
+        if wash.trade_count >= self.config.min_trades {
+            if wash.wash_percent() >= self.config.wash_percent {
+                report.findings.push(Finding::new(
+                    RiskSource::Wash,
+                    RiskFactor::WashTrading,
+                    json!(wash.wash_percent()),
+                    serde_json::to_value(&wash.wash_wallets)?,
+                ));
+            }
+            if wash.top_trader_share >= self.config.concentration_percent {
+                report.findings.push(Finding::new(
+                    RiskSource::Wash,
+                    RiskFactor::ConcentratedVolume,
+                    json!(wash.top_trader_share),
+                    json!({ "unique_traders": wash.unique_traders, "trades_per_trader": wash.trades_per_trader }),
+                ));
+            }
+        }
+        report.raw = serde_json::to_value(&wash)?;
+        Ok(report)
+    }
+}
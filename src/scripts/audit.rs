@@ -0,0 +1,131 @@
+use {
+    crate::{
+        managers::db_manager::DbManager,
+        models::{candle::CandleInterval, rug_verdict::RugVerdictRecord},
+    },
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+};
+
+/// Prints everything stored about `mint_address`: the token, its creator's
+/// record, its status history, failed evaluations, copy tag, 5-minute
+/// candles and every rug-check verdict with the provider responses behind
+/// it.
+pub async fn audit_token(db_manager: &DbManager, mint_address: &str) -> Result<()> {
+    let token = db_manager.get_token(mint_address).await?;
+    match &token {
+        Some(token) => println!(
+            "Token {} (#{}) by {}, launched {}, status {} since {}",
+            token.mint_address,
+            token.id,
+            token.creator_address.as_deref().unwrap_or("unknown creator"),
+            token.created_at.map(|at| at.to_rfc3339()).unwrap_or_else(|| "at an unknown time".to_string()),
+            token.status,
+            token.status_changed_at.to_rfc3339()
+        ),
+        None => println!("Token {mint_address} is not stored"),
+    }
+
+    let creator_address = token.as_ref().and_then(|token| token.creator_address.as_deref());
+    if let Some(address) = creator_address {
+        if let Some(creator) = db_manager.get_creator(address).await? {
+            let rate = |rate: Option<f64>| rate.map(|rate| format!("{:.0}%", rate * 100.0)).unwrap_or_else(|| "-".to_string());
+            println!(
+                "  Creator: {} launches since {}, {} ended: {} graduated, {} rugged",
+                creator.launch_count,
+                creator.first_launch_at.to_rfc3339(),
+                creator.ended_count(),
+                rate(creator.graduation_rate()),
+                rate(creator.rug_rate())
+            );
+        }
+    }
+
+    for change in db_manager.get_status_history(mint_address).await? {
+        println!(
+            "  {} {} -> {}: {}",
+            change.changed_at.to_rfc3339(),
+            change.from_status.as_deref().unwrap_or("-"),
+            change.to_status,
+            change.reason
+        );
+    }
+
+    for failure in db_manager.get_evaluation_failures(mint_address).await? {
+        println!("  {} {} failed: {}", failure.failed_at.to_rfc3339(), failure.stage, failure.error);
+    }
+
+    if let Some(copy) = db_manager.get_token_copy(mint_address).await? {
+        let clones = db_manager.count_clones(&copy.original_mint, Utc::now()).await?;
+        println!(
+            "  Copies {} on {} (detected {}, {} clones of the original)",
+            copy.original_mint,
+            copy.matched_on()?.as_str(),
+            copy.detected_at.to_rfc3339(),
+            clones
+        );
+    }
+
+    if let Some(launched_at) = token.as_ref().and_then(|token| token.created_at) {
+        let candles = db_manager
+            .get_candles(mint_address, CandleInterval::FiveMinutes, launched_at, Utc::now())
+            .await?;
+        for candle in candles {
+            println!(
+                "  {} open {:.10} high {:.10} low {:.10} close {:.10}, {} trades, {} lamports",
+                candle.bucket_start.to_rfc3339(),
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.trade_count,
+                candle.sol_volume
+            );
+        }
+    }
+
+    for verdict in db_manager.get_rug_verdicts(mint_address).await? {
+        print_verdict(db_manager, &verdict).await?;
+    }
+    Ok(())
+}
+
+/// Prints every rug-check verdict recorded between `from` and `to`.
+pub async fn audit_verdicts(db_manager: &DbManager, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<()> {
+    let verdicts = db_manager.get_rug_verdicts_between(from, to).await?;
+    println!("{} verdicts between {} and {}", verdicts.len(), from.to_rfc3339(), to.to_rfc3339());
+    for verdict in &verdicts {
+        print_verdict(db_manager, verdict).await?;
+    }
+    Ok(())
+}
+
+async fn print_verdict(db_manager: &DbManager, verdict: &RugVerdictRecord) -> Result<()> {
+    println!(
+        "Verdict #{} for {} at {}: {} ({:.1}% confidence)",
+        verdict.id,
+        verdict.mint_address,
+        verdict.checked_at.to_rfc3339(),
+        verdict.status,
+        verdict.confidence
+    );
+    println!("  Risk factors: {}", verdict.risk_factors);
+    println!("  Failures: {}", verdict.failures);
+    for response in db_manager.get_provider_responses(verdict.id).await? {
+        let status = response.http_status.map(|status| status.to_string()).unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {} at {}: HTTP {} in {}ms",
+            response.provider,
+            response.fetched_at.to_rfc3339(),
+            status,
+            response.latency_ms
+        );
+        if let Some(error) = &response.error {
+            println!("    Error: {error}");
+        }
+        if let Some(body) = &response.body {
+            println!("    Body: {body}");
+        }
+    }
+    Ok(())
+}
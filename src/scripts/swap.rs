@@ -0,0 +1,483 @@
+use {
+    crate::{
+        managers::swqos_manager::{Landing, SendReport, SendStatus, SwqosRpcClient, TransactionSender},
+        utils::pump_fun::{bonding_curve_pda, BondingCurve, PUMP_FUN_PROGRAM_ID},
+    },
+    anyhow::{anyhow, Result},
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair, Signer},
+        system_program, sysvar,
+    },
+    std::{
+        collections::HashSet,
+        env,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The pump.fun global config account.
+pub const PUMP_FUN_GLOBAL: Pubkey = pubkey!("4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf");
+
+/// The account pump.fun trading fees are paid to.
+pub const PUMP_FUN_FEE_RECIPIENT: Pubkey = pubkey!("CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM");
+
+/// The PDA pump.fun emits its Anchor events through.
+pub const PUMP_FUN_EVENT_AUTHORITY: Pubkey = pubkey!("Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1");
+
+/// The SPL associated token account program.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Anchor discriminators: the first 8 bytes of sha256("global:<name>").
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+#[derive(Debug, Clone)]
+pub struct SwapConfig {
+    /// Most the price may move against a swap, in basis points.
+    pub slippage_bps: u64,
+    /// Fee pump.fun takes on each trade, in basis points.
+    pub fee_bps: u64,
+    pub fee_recipient: Pubkey,
+    /// Lamports spent on each buy the monitor makes.
+    pub buy_lamports: u64,
+    /// Whether buys create the user's token account if it doesn't exist.
+    pub create_ata: bool,
+    /// Whether a token we hold is sold as soon as its creator dumps it.
+    pub sell_on_dev_dump: bool,
+}
+
+impl SwapConfig {
+    /// Reads `SWAP_SLIPPAGE_BPS` (default 500), `PUMP_FEE_BPS` (default
+    /// 100), `PUMP_FEE_RECIPIENT` (default the pump.fun fee account),
+    /// `SWAP_BUY_LAMPORTS` (default 10000000), `SWAP_CREATE_ATA` (default
+    /// true) and `SWAP_SELL_ON_DEV_DUMP` (default true). The compute budget
+    /// is the sender's to set.
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        SwapConfig {
+            slippage_bps: read("SWAP_SLIPPAGE_BPS").unwrap_or(500).min(10_000),
+            fee_bps: read("PUMP_FEE_BPS").unwrap_or(100),
+            fee_recipient: env::var("PUMP_FEE_RECIPIENT")
+                .ok()
+                .and_then(|v| Pubkey::from_str(&v).ok())
+                .unwrap_or(PUMP_FUN_FEE_RECIPIENT),
+            buy_lamports: read("SWAP_BUY_LAMPORTS").unwrap_or(10_000_000),
+            create_ata: env::var("SWAP_CREATE_ATA")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+            sell_on_dev_dump: env::var("SWAP_SELL_ON_DEV_DUMP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// What a buy asks the program for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// Raw tokens bought.
+    pub token_amount: u64,
+    /// Most lamports the program may take, fee included.
+    pub max_sol_cost: u64,
+}
+
+/// What a sell asks the program for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SellQuote {
+    /// Raw tokens sold.
+    pub token_amount: u64,
+    /// Lamports the sell returns at the current price, after the fee.
+    pub expected_sol_output: u64,
+    /// Fewest lamports the seller accepts, after the fee.
+    pub min_sol_output: u64,
+}
+
+/// Tokens `lamports` buys on `curve` now, and the most the buy may cost
+/// once `slippage_bps` of price movement is allowed for.
+pub fn quote_buy(curve: &BondingCurve, lamports: u64, config: &SwapConfig) -> BuyQuote {
+    BuyQuote {
+        token_amount: curve.buy_quote(lamports, config.fee_bps),
+        max_sol_cost: (lamports as u128 * (10_000 + config.slippage_bps) as u128 / 10_000) as u64,
+    }
+}
+
+/// Lamports selling `token_amount` on `curve` returns now, less
+/// `slippage_bps`.
+pub fn quote_sell(curve: &BondingCurve, token_amount: u64, config: &SwapConfig) -> SellQuote {
+    let expected = curve.sell_quote(token_amount, config.fee_bps);
+    SellQuote {
+        token_amount,
+        expected_sol_output: expected,
+        min_sol_output: (expected as u128 * (10_000 - config.slippage_bps) as u128 / 10_000) as u64,
+    }
+}
+
+/// The associated token account of `owner` for `mint`.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Creates `owner`'s token account for `mint` unless it already exists.
+pub fn create_ata_idempotent_instruction(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        // CreateIdempotent
+        data: vec![1],
+    }
+}
+
+/// pump.fun `buy`: `token_amount` raw tokens for at most `max_sol_cost`
+/// lamports.
+pub fn buy_instruction(user: &Pubkey, mint: &Pubkey, fee_recipient: &Pubkey, quote: BuyQuote) -> Instruction {
+    let bonding_curve = bonding_curve_pda(mint);
+    let mut data = BUY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&quote.token_amount.to_le_bytes());
+    data.extend_from_slice(&quote.max_sol_cost.to_le_bytes());
+    Instruction {
+        program_id: PUMP_FUN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(PUMP_FUN_GLOBAL, false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(associated_token_address(&bonding_curve, mint), false),
+            AccountMeta::new(associated_token_address(user, mint), false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(PUMP_FUN_EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(PUMP_FUN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// pump.fun `sell`: `token_amount` raw tokens for at least
+/// `min_sol_output` lamports.
+pub fn sell_instruction(user: &Pubkey, mint: &Pubkey, fee_recipient: &Pubkey, quote: SellQuote) -> Instruction {
+    let bonding_curve = bonding_curve_pda(mint);
+    let mut data = SELL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&quote.token_amount.to_le_bytes());
+    data.extend_from_slice(&quote.min_sol_output.to_le_bytes());
+    Instruction {
+        program_id: PUMP_FUN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(PUMP_FUN_GLOBAL, false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(associated_token_address(&bonding_curve, mint), false),
+            AccountMeta::new(associated_token_address(user, mint), false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(PUMP_FUN_EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(PUMP_FUN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Every instruction of a buy of `lamports` worth of `mint`: token account
+/// creation if enabled, then the buy. The sender adds the compute budget.
+///
+/// # Arguments
+/// * `user` - The wallet paying and receiving the tokens
+/// * `mint` - The token to buy
+/// * `curve` - The token's bonding curve as of now
+/// * `lamports` - SOL to spend before slippage
+/// * `config` - Slippage and fee settings
+pub fn build_buy(
+    user: &Pubkey,
+    mint: &Pubkey,
+    curve: &BondingCurve,
+    lamports: u64,
+    config: &SwapConfig,
+) -> Result<Vec<Instruction>> {
+    let quote = quote_buy(curve, lamports, config);
+    if quote.token_amount == 0 {
+        return Err(anyhow!("Nothing to buy: the curve of {mint} is complete or the amount is too small"));
+    }
+    let mut instructions = Vec::new();
+    if config.create_ata {
+        instructions.push(create_ata_idempotent_instruction(user, user, mint));
+    }
+    instructions.push(buy_instruction(user, mint, &config.fee_recipient, quote));
+    Ok(instructions)
+}
+
+/// The sell of `token_amount` raw tokens of `mint`. The sender adds the
+/// compute budget.
+pub fn build_sell(
+    user: &Pubkey,
+    mint: &Pubkey,
+    curve: &BondingCurve,
+    token_amount: u64,
+    config: &SwapConfig,
+) -> Result<Vec<Instruction>> {
+    let quote = quote_sell(curve, token_amount, config);
+    // `min_sol_output` is 0 at 100% slippage, which is a valid sell.
+    if quote.expected_sol_output == 0 {
+        return Err(anyhow!("Nothing to sell: the curve of {mint} is complete or the amount is too small"));
+    }
+    Ok(vec![sell_instruction(user, mint, &config.fee_recipient, quote)])
+}
+
+/// Buys and sells on pump.fun curves from the wallet in `SWAP_KEYPAIR_PATH`.
+pub struct PumpSwapper {
+    rpc_manager: Arc<SwqosRpcClient>,
+    sender: Arc<TransactionSender>,
+    payer: Keypair,
+    /// Tokens bought since startup and not sold since.
+    held: Mutex<HashSet<Pubkey>>,
+    config: SwapConfig,
+}
+
+impl PumpSwapper {
+    pub fn new(
+        rpc_manager: Arc<SwqosRpcClient>,
+        sender: Arc<TransactionSender>,
+        payer: Keypair,
+        config: SwapConfig,
+    ) -> Self {
+        PumpSwapper {
+            rpc_manager,
+            sender,
+            payer,
+            held: Mutex::new(HashSet::new()),
+            config,
+        }
+    }
+
+    /// A swapper for the keypair file in `SWAP_KEYPAIR_PATH`, or `None` if
+    /// it isn't set and the monitor shouldn't trade.
+    pub fn from_env(rpc_manager: Arc<SwqosRpcClient>, sender: Arc<TransactionSender>) -> Result<Option<Self>> {
+        let Ok(path) = env::var("SWAP_KEYPAIR_PATH") else {
+            return Ok(None);
+        };
+        let payer = read_keypair_file(&path).map_err(|e| anyhow!("Failed to read keypair {path}: {e}"))?;
+        Ok(Some(PumpSwapper::new(rpc_manager, sender, payer, SwapConfig::from_env())))
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Buys `buy_lamports` worth of `mint` through the transaction sender.
+    pub async fn buy(&self, mint: &Pubkey) -> Result<SendReport> {
+        let curve = self.bonding_curve(mint).await?;
+        let instructions = build_buy(&self.payer(), mint, &curve, self.config.buy_lamports, &self.config)?;
+        let report = self.sender.send(&instructions, &self.payer).await?;
+        if succeeded(&report) {
+            self.held.lock().unwrap().insert(*mint);
+        }
+        Ok(report)
+    }
+
+    /// Sells `token_amount` raw tokens of `mint` through the transaction
+    /// sender.
+    pub async fn sell(&self, mint: &Pubkey, token_amount: u64) -> Result<SendReport> {
+        let curve = self.bonding_curve(mint).await?;
+        let instructions = build_sell(&self.payer(), mint, &curve, token_amount, &self.config)?;
+        self.sender.send(&instructions, &self.payer).await
+    }
+
+    /// Sells the whole balance of a token bought since startup. Returns
+    /// `None` without sending anything if we don't hold it.
+    pub async fn sell_all(&self, mint: &Pubkey) -> Result<Option<SendReport>> {
+        if !self.holds(mint) {
+            return Ok(None);
+        }
+        let balance = self.token_balance(mint).await?;
+        if balance == 0 {
+            self.held.lock().unwrap().remove(mint);
+            return Ok(None);
+        }
+        let report = self.sell(mint, balance).await?;
+        if succeeded(&report) {
+            self.held.lock().unwrap().remove(mint);
+        }
+        Ok(Some(report))
+    }
+
+    /// Whether `mint` was bought since startup and not sold since.
+    pub fn holds(&self, mint: &Pubkey) -> bool {
+        self.held.lock().unwrap().contains(mint)
+    }
+
+    /// Whether held tokens are sold when their creator dumps them.
+    pub fn sells_on_dev_dump(&self) -> bool {
+        self.config.sell_on_dev_dump
+    }
+
+    /// Raw tokens of `mint` in the payer's token account.
+    async fn token_balance(&self, mint: &Pubkey) -> Result<u64> {
+        let account = associated_token_address(&self.payer(), mint);
+        let balance = self.rpc_manager.rpc().get_token_account_balance(&account).await?;
+        Ok(balance.amount.parse()?)
+    }
+
+    async fn bonding_curve(&self, mint: &Pubkey) -> Result<BondingCurve> {
+        self.rpc_manager
+            .get_bonding_curve(mint)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch the bonding curve of {mint}: {e}"))?
+            .ok_or_else(|| anyhow!("{mint} has no pump.fun bonding curve"))
+    }
+}
+
+/// Whether a swap landed without error.
+fn succeeded(report: &SendReport) -> bool {
+    matches!(report.status, SendStatus::Landed(Landing { error: None, .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            managers::swqos_manager::{ConfirmationTracker, FeeStrategy, SenderConfig},
+            utils::pump_fun::{INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES},
+        },
+        std::time::Duration,
+    };
+
+    /// Raw tokens 1 SOL buys on a fresh curve with a 1% fee.
+    const ONE_SOL_OF_TOKENS: u64 = 34_281_150_129_545;
+
+    fn config() -> SwapConfig {
+        SwapConfig {
+            slippage_bps: 500,
+            fee_bps: 100,
+            fee_recipient: PUMP_FUN_FEE_RECIPIENT,
+            buy_lamports: 1_000_000_000,
+            create_ata: true,
+            sell_on_dev_dump: true,
+        }
+    }
+
+    fn fresh_curve() -> BondingCurve {
+        BondingCurve::from_virtual_reserves(INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES)
+    }
+
+    fn amounts(data: &[u8]) -> (u64, u64) {
+        (
+            u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            u64::from_le_bytes(data[16..24].try_into().unwrap()),
+        )
+    }
+
+    #[test]
+    fn buy_quotes_allow_for_slippage_above_the_spend() {
+        let quote = quote_buy(&fresh_curve(), 1_000_000_000, &config());
+        assert_eq!(quote.token_amount, ONE_SOL_OF_TOKENS);
+        assert_eq!(quote.max_sol_cost, 1_050_000_000);
+
+        let exact = quote_buy(&fresh_curve(), 1_000_000_000, &SwapConfig { slippage_bps: 0, ..config() });
+        assert_eq!(exact.max_sol_cost, 1_000_000_000);
+    }
+
+    #[test]
+    fn sell_quotes_accept_slippage_below_the_proceeds() {
+        // 10M tokens on a fresh curve return 277008310 lamports, 274238227
+        // after the 1% fee.
+        let quote = quote_sell(&fresh_curve(), 10_000_000_000_000, &config());
+        assert_eq!(quote.token_amount, 10_000_000_000_000);
+        assert_eq!(quote.expected_sol_output, 274_238_227);
+        assert_eq!(quote.min_sol_output, 274_238_227 * 9_500 / 10_000);
+
+        let all = quote_sell(&fresh_curve(), 10_000_000_000_000, &SwapConfig { slippage_bps: 10_000, ..config() });
+        assert_eq!((all.expected_sol_output, all.min_sol_output), (274_238_227, 0));
+    }
+
+    #[test]
+    fn buy_transactions_create_the_account_then_buy() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let instructions = build_buy(&user, &mint, &fresh_curve(), 1_000_000_000, &config()).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+        let buy = &instructions[1];
+        assert_eq!((buy.program_id, &buy.data[..8]), (PUMP_FUN_PROGRAM_ID, &BUY_DISCRIMINATOR[..]));
+        assert_eq!(amounts(&buy.data), (ONE_SOL_OF_TOKENS, 1_050_000_000));
+        assert_eq!(buy.accounts[3].pubkey, bonding_curve_pda(&mint));
+        assert_eq!(buy.accounts[5].pubkey, associated_token_address(&user, &mint));
+        assert!(buy.accounts[6].is_signer);
+
+        let without_ata = SwapConfig { create_ata: false, ..config() };
+        assert_eq!(build_buy(&user, &mint, &fresh_curve(), 1_000_000_000, &without_ata).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn sell_transactions_carry_the_minimum_output() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let instructions = build_sell(&user, &mint, &fresh_curve(), 10_000_000_000_000, &config()).unwrap();
+        assert_eq!(instructions.len(), 1);
+        let sell = &instructions[0];
+        assert_eq!(&sell.data[..8], &SELL_DISCRIMINATOR[..]);
+        assert_eq!(amounts(&sell.data), (10_000_000_000_000, 274_238_227 * 9_500 / 10_000));
+        assert_eq!(sell.accounts[8].pubkey, ASSOCIATED_TOKEN_PROGRAM_ID);
+
+        // Any price goes: the sell still builds, with no minimum.
+        let any_price = SwapConfig { slippage_bps: 10_000, ..config() };
+        let instructions = build_sell(&user, &mint, &fresh_curve(), 10_000_000_000_000, &any_price).unwrap();
+        assert_eq!(amounts(&instructions[0].data), (10_000_000_000_000, 0));
+    }
+
+    #[test]
+    fn swaps_on_complete_curves_are_refused() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let complete = BondingCurve { complete: true, ..fresh_curve() };
+        assert!(build_buy(&user, &mint, &complete, 1_000_000_000, &config()).is_err());
+        assert!(build_sell(&user, &mint, &complete, 1_000_000, &config()).is_err());
+        // Too few tokens to be worth a lamport.
+        assert!(build_sell(&user, &mint, &fresh_curve(), 1, &config()).is_err());
+    }
+
+    #[tokio::test]
+    async fn tokens_not_bought_are_not_sold() {
+        // Nothing listens on the discard port, so any RPC call would fail.
+        let rpc_manager = Arc::new(SwqosRpcClient::new("http://127.0.0.1:9"));
+        let sender = TransactionSender::new(
+            &rpc_manager,
+            Arc::new(ConfirmationTracker::default()),
+            SenderConfig {
+                fee_strategy: FeeStrategy::Fixed(0),
+                min_price: 0,
+                max_price: 0,
+                compute_unit_limit: 0,
+                endpoints: Vec::new(),
+                ws_endpoint: None,
+                rebroadcast_interval: Duration::from_secs(1),
+                send_timeout: Duration::from_secs(1),
+            },
+        );
+        let swapper = PumpSwapper::new(rpc_manager, Arc::new(sender), Keypair::new(), config());
+        let mint = Pubkey::new_unique();
+        assert!(!swapper.holds(&mint));
+        assert!(swapper.sell_all(&mint).await.unwrap().is_none());
+        assert!(swapper.sell(&mint, 1_000).await.is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use {
+    crate::{
+        models::{
+            trade::Trade,
+            wallet::{CommonFunder, Relation},
+        },
+        utils::pump_fun::TOKEN_TOTAL_SUPPLY,
+    },
+    serde::Serialize,
+    std::{
+        collections::{BTreeSet, HashMap},
+        env,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct BundleConfig {
+    /// Slots after the launch slot whose buys count as early.
+    pub window_slots: i64,
+    /// Share of supply, 0-100, a bundle or sniper cluster must hold to be
+    /// flagged.
+    pub supply_percent: f64,
+    /// Early buyers whose funding source is looked up, in buy order.
+    pub funding_lookups: usize,
+}
+
+impl BundleConfig {
+    /// Reads `BUNDLE_WINDOW_SLOTS` (default 3), `BUNDLE_SUPPLY_PERCENT`
+    /// (default 20) and `BUNDLE_FUNDING_LOOKUPS` (default 20).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        BundleConfig {
+            window_slots: read("BUNDLE_WINDOW_SLOTS").unwrap_or(3) as i64,
+            supply_percent: env::var("BUNDLE_SUPPLY_PERCENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20.0),
+            funding_lookups: read("BUNDLE_FUNDING_LOOKUPS").unwrap_or(20) as usize,
+        }
+    }
+}
+
+/// Why an early buyer is suspected of acting with others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleReason {
+    /// The wallet is the token's creator.
+    Creator,
+    /// Bought in the slot the token was created in.
+    CreateSlot,
+    /// Funded by the creator, directly or through others.
+    FundedByCreator,
+    /// Funded the creator, directly or through others.
+    FundedCreator,
+    /// Shares a funder with the creator somewhere up the funding graph.
+    SharesCreatorFunder,
+    /// First funded by the same wallet as another early buyer.
+    SharedFunder,
+    /// Bought exactly the same token or SOL amount as another early buyer.
+    IdenticalAmount,
+    /// Bought in the same slot as another early buyer.
+    SameSlot,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspectWallet {
+    pub address: String,
+    /// Slot of the wallet's first buy.
+    pub first_slot: i64,
+    pub reasons: Vec<BundleReason>,
+    pub funder: Option<String>,
+    /// Raw tokens the wallet holds now, from its trades.
+    pub holding: i64,
+    /// For bundle wallets other than the creator, the closest wallet both
+    /// trace back to, if it was found. Not set by `detect_bundle`.
+    pub common_funder: Option<CommonFunder>,
+}
+
+/// The early buyers of a token that look coordinated, split into the
+/// creator's bundle and other sniper clusters.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BundleReport {
+    /// Slot of the first trade, taken as the launch slot.
+    pub launch_slot: i64,
+    pub early_buyers: usize,
+    /// The creator and wallets tied to it by slot or funding.
+    pub bundle: Vec<SuspectWallet>,
+    /// Share of supply, 0-100, the bundle holds now.
+    pub bundle_supply_percent: f64,
+    /// Other early buyers sharing a funder or a buy amount.
+    pub snipers: Vec<SuspectWallet>,
+    /// Share of supply, 0-100, the snipers hold now.
+    pub sniper_supply_percent: f64,
+}
+
+/// Wallets that bought within `window_slots` of the launch, in the order of
+/// their first buy.
+///
+/// # Arguments
+/// * `trades` - Every stored trade on the token
+/// * `config` - Detection settings
+pub fn early_buyers(trades: &[Trade], config: &BundleConfig) -> Vec<String> {
+    let Some(launch_slot) = trades.iter().map(|t| t.slot).min() else {
+        return Vec::new();
+    };
+    let mut buys: Vec<&Trade> = trades
+        .iter()
+        .filter(|t| t.is_buy && t.slot <= launch_slot + config.window_slots)
+        .collect();
+    buys.sort_by_key(|t| (t.slot, t.traded_at, t.event_index));
+    let mut buyers: Vec<String> = Vec::new();
+    for trade in buys {
+        if !buyers.contains(&trade.trader) {
+            buyers.push(trade.trader.clone());
+        }
+    }
+    buyers
+}
+
+/// Looks for a bundled launch and sniper clusters among a token's early
+/// buyers: wallets buying with the create, sharing a funding source with
+/// each other or the creator, or buying identical amounts.
+///
+/// Buying in the same slot as another early buyer is recorded but isn't
+/// enough on its own; busy launches fill their first slots with unrelated
+/// bots.
+///
+/// # Arguments
+/// * `trades` - Every stored trade on the token
+/// * `creator_address` - The token's creator, if known
+/// * `funders` - First funder of each wallet looked up, creator included
+/// * `creator_relatives` - How wallets near the creator in the funding graph
+///   relate to it, from `FundingGraph::related_wallets`
+/// * `config` - Detection settings
+pub fn detect_bundle(
+    trades: &[Trade],
+    creator_address: Option<&str>,
+    funders: &HashMap<String, String>,
+    creator_relatives: &HashMap<String, Relation>,
+    config: &BundleConfig,
+) -> BundleReport {
+    let Some(launch_slot) = trades.iter().map(|t| t.slot).min() else {
+        return BundleReport::default();
+    };
+    let buyers = early_buyers(trades, config);
+    let early: Vec<&Trade> = trades
+        .iter()
+        .filter(|t| t.is_buy && t.slot <= launch_slot + config.window_slots)
+        .collect();
+
+    let mut holdings: HashMap<&str, i64> = HashMap::new();
+    for trade in trades {
+        let holding = holdings.entry(trade.trader.as_str()).or_default();
+        *holding += if trade.is_buy { trade.token_amount } else { -trade.token_amount };
+    }
+
+    // Distinct early buyers per slot, per amount and per funder.
+    let mut by_slot: HashMap<i64, BTreeSet<&str>> = HashMap::new();
+    let mut by_token_amount: HashMap<i64, BTreeSet<&str>> = HashMap::new();
+    let mut by_sol_amount: HashMap<i64, BTreeSet<&str>> = HashMap::new();
+    for trade in &early {
+        by_slot.entry(trade.slot).or_default().insert(&trade.trader);
+        by_token_amount.entry(trade.token_amount).or_default().insert(&trade.trader);
+        by_sol_amount.entry(trade.sol_amount).or_default().insert(&trade.trader);
+    }
+    let mut by_funder: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for buyer in &buyers {
+        if let Some(funder) = funders.get(buyer) {
+            by_funder.entry(funder).or_default().insert(buyer);
+        }
+    }
+    let creator_funder = creator_address.and_then(|creator| funders.get(creator));
+    let shares_group = |groups: &HashMap<i64, BTreeSet<&str>>, wallet: &str| {
+        groups.values().any(|wallets| wallets.len() > 1 && wallets.contains(wallet))
+    };
+
+    let mut report = BundleReport {
+        launch_slot,
+        early_buyers: buyers.len(),
+        ..BundleReport::default()
+    };
+    let mut bundle_holding = 0;
+    let mut sniper_holding = 0;
+    for buyer in &buyers {
+        let first_slot = early
+            .iter()
+            .filter(|t| &t.trader == buyer)
+            .map(|t| t.slot)
+            .min()
+            .unwrap_or(launch_slot);
+        let funder = funders.get(buyer);
+        let relation = creator_relatives.get(buyer);
+        let mut reasons = Vec::new();
+        if creator_address == Some(buyer.as_str()) {
+            reasons.push(BundleReason::Creator);
+        }
+        if first_slot == launch_slot {
+            reasons.push(BundleReason::CreateSlot);
+        }
+        if (funder.is_some() && funder.map(String::as_str) == creator_address) || relation == Some(&Relation::Funded) {
+            reasons.push(BundleReason::FundedByCreator);
+        }
+        if relation == Some(&Relation::Funder) {
+            reasons.push(BundleReason::FundedCreator);
+        }
+        if (funder.is_some() && funder == creator_funder && creator_address != Some(buyer.as_str()))
+            || relation == Some(&Relation::Sibling)
+        {
+            reasons.push(BundleReason::SharesCreatorFunder);
+        }
+        if funder.is_some_and(|funder| by_funder.get(funder.as_str()).is_some_and(|wallets| wallets.len() > 1)) {
+            reasons.push(BundleReason::SharedFunder);
+        }
+        if shares_group(&by_token_amount, buyer) || shares_group(&by_sol_amount, buyer) {
+            reasons.push(BundleReason::IdenticalAmount);
+        }
+        if shares_group(&by_slot, buyer) {
+            reasons.push(BundleReason::SameSlot);
+        }
+
+        let in_bundle = reasons.iter().any(|reason| {
+            matches!(
+                reason,
+                BundleReason::Creator
+                    | BundleReason::CreateSlot
+                    | BundleReason::FundedByCreator
+                    | BundleReason::FundedCreator
+                    | BundleReason::SharesCreatorFunder
+            )
+        });
+        let is_sniper = reasons
+            .iter()
+            .any(|reason| matches!(reason, BundleReason::SharedFunder | BundleReason::IdenticalAmount));
+        if !in_bundle && !is_sniper {
+            continue;
+        }
+        let holding = holdings.get(buyer.as_str()).copied().unwrap_or(0).max(0);
+        let wallet = SuspectWallet {
+            address: buyer.clone(),
+            first_slot,
+            reasons,
+            funder: funder.cloned(),
+            holding,
+            common_funder: None,
+        };
+        if in_bundle {
+            bundle_holding += holding;
+            report.bundle.push(wallet);
+        } else {
+            sniper_holding += holding;
+            report.snipers.push(wallet);
+        }
+    }
+    report.bundle_supply_percent = supply_percent(bundle_holding);
+    report.sniper_supply_percent = supply_percent(sniper_holding);
+    report
+}
+
+/// `amount` raw tokens as a percentage of total supply.
+fn supply_percent(amount: i64) -> f64 {
+    amount as f64 / TOKEN_TOTAL_SUPPLY as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1% of supply.
+    const PERCENT: i64 = TOKEN_TOTAL_SUPPLY as i64 / 100;
+
+    fn config() -> BundleConfig {
+        BundleConfig {
+            window_slots: 3,
+            supply_percent: 20.0,
+            funding_lookups: 20,
+        }
+    }
+
+    fn launch() -> Vec<Trade> {
+        vec![
+            Trade::test("mint").by("creator").in_slot(100).buy(1_000, 10 * PERCENT),
+            Trade::test("mint").by("funded").in_slot(101).buy(1_001, 5 * PERCENT),
+            Trade::test("mint").by("cousin").in_slot(102).buy(1_002, 4 * PERCENT),
+            Trade::test("mint").by("farm_a").in_slot(101).buy(1_003, 3 * PERCENT),
+            Trade::test("mint").by("farm_b").in_slot(103).buy(1_004, 3 * PERCENT + 1),
+            Trade::test("mint").by("twin_a").in_slot(103).buy(1_005, 2 * PERCENT),
+            Trade::test("mint").by("twin_b").in_slot(102).buy(1_006, 2 * PERCENT),
+            Trade::test("mint").by("bot").in_slot(102).buy(1_007, PERCENT),
+            Trade::test("mint").by("late").in_slot(200).buy(1_008, 7 * PERCENT),
+            // The creator sold half, which lowers the bundle's holding.
+            Trade::test("mint").by("creator").in_slot(150).sell(500, 5 * PERCENT),
+        ]
+    }
+
+    fn funders() -> HashMap<String, String> {
+        [
+            ("creator", "mother"),
+            ("funded", "creator"),
+            ("cousin", "mother"),
+            ("farm_a", "farm"),
+            ("farm_b", "farm"),
+            ("bot", "exchange"),
+        ]
+        .into_iter()
+        .map(|(wallet, funder)| (wallet.to_string(), funder.to_string()))
+        .collect()
+    }
+
+    fn reasons<'a>(wallets: &'a [SuspectWallet], address: &str) -> &'a [BundleReason] {
+        &wallets.iter().find(|w| w.address == address).unwrap().reasons
+    }
+
+    #[test]
+    fn early_buyers_are_listed_in_buy_order() {
+        let buyers = early_buyers(&launch(), &config());
+        assert_eq!(buyers, ["creator", "funded", "farm_a", "cousin", "twin_b", "bot", "farm_b", "twin_a"]);
+        assert!(early_buyers(&[], &config()).is_empty());
+    }
+
+    #[test]
+    fn the_creator_and_wallets_tied_to_it_form_the_bundle() {
+        let report = detect_bundle(&launch(), Some("creator"), &funders(), &HashMap::new(), &config());
+        assert_eq!(report.launch_slot, 100);
+        assert_eq!(report.early_buyers, 8);
+
+        let bundle: Vec<&str> = report.bundle.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(bundle, ["creator", "funded", "cousin"]);
+        assert_eq!(
+            reasons(&report.bundle, "creator"),
+            [BundleReason::Creator, BundleReason::CreateSlot, BundleReason::SharedFunder]
+        );
+        assert_eq!(reasons(&report.bundle, "funded"), [BundleReason::FundedByCreator, BundleReason::SameSlot]);
+        assert_eq!(
+            reasons(&report.bundle, "cousin"),
+            [BundleReason::SharesCreatorFunder, BundleReason::SharedFunder, BundleReason::SameSlot]
+        );
+        // 5% left with the creator, 5% funded, 4% cousin.
+        assert!((report.bundle_supply_percent - 14.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shared_funders_and_identical_amounts_make_snipers() {
+        let report = detect_bundle(&launch(), Some("creator"), &funders(), &HashMap::new(), &config());
+        let snipers: Vec<&str> = report.snipers.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(snipers, ["farm_a", "twin_b", "farm_b", "twin_a"]);
+        assert_eq!(reasons(&report.snipers, "farm_a"), [BundleReason::SharedFunder, BundleReason::SameSlot]);
+        assert_eq!(reasons(&report.snipers, "farm_b"), [BundleReason::SharedFunder, BundleReason::SameSlot]);
+        assert_eq!(reasons(&report.snipers, "twin_a"), [BundleReason::IdenticalAmount, BundleReason::SameSlot]);
+        assert!((report.sniper_supply_percent - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sharing_a_slot_alone_is_not_suspicious() {
+        let report = detect_bundle(&launch(), Some("creator"), &funders(), &HashMap::new(), &config());
+        let mut flagged = report.bundle.iter().chain(&report.snipers);
+        assert!(flagged.all(|w| w.address != "bot" && w.address != "late"));
+    }
+
+    #[test]
+    fn wallets_near_the_creator_in_the_funding_graph_join_the_bundle() {
+        let relatives: HashMap<String, Relation> = [
+            ("bot", Relation::Funded),
+            ("twin_a", Relation::Funder),
+            ("twin_b", Relation::Sibling),
+        ]
+        .into_iter()
+        .map(|(wallet, relation)| (wallet.to_string(), relation))
+        .collect();
+        let report = detect_bundle(&launch(), Some("creator"), &funders(), &relatives, &config());
+        let bundle: Vec<&str> = report.bundle.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(bundle, ["creator", "funded", "cousin", "twin_b", "bot", "twin_a"]);
+        assert_eq!(reasons(&report.bundle, "bot"), [BundleReason::FundedByCreator, BundleReason::SameSlot]);
+        assert_eq!(reasons(&report.bundle, "twin_a")[0], BundleReason::FundedCreator);
+        assert_eq!(reasons(&report.bundle, "twin_b")[0], BundleReason::SharesCreatorFunder);
+        assert!(report.bundle.iter().all(|w| w.common_funder.is_none()));
+    }
+
+    #[test]
+    fn without_a_creator_only_the_create_slot_marks_the_bundle() {
+        let report = detect_bundle(&launch(), None, &HashMap::new(), &HashMap::new(), &config());
+        let bundle: Vec<&str> = report.bundle.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(bundle, ["creator"]);
+        assert_eq!(reasons(&report.bundle, "creator"), [BundleReason::CreateSlot]);
+        let snipers: Vec<&str> = report.snipers.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(snipers, ["twin_b", "twin_a"]);
+        assert_eq!(detect_bundle(&[], None, &HashMap::new(), &HashMap::new(), &config()).early_buyers, 0);
+    }
+}
@@ -0,0 +1,402 @@
+use {
+    crate::models::copycat::{CopyMatch, CopyTag, LaunchFingerprint},
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    image::imageops::FilterType,
+    std::{
+        collections::{HashMap, VecDeque},
+        env,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct CopycatConfig {
+    /// How far back a launch can be and still be copied.
+    pub window: chrono::Duration,
+    /// Most bits two image hashes may differ by and still be the same image.
+    pub max_hash_distance: u32,
+    /// Shortest normalized name or symbol that is matched on. Shorter ones
+    /// collide by chance.
+    pub min_key_len: usize,
+    /// Whether the launch pipeline filters out copies instead of evaluating
+    /// them.
+    pub filter_copies: bool,
+}
+
+impl CopycatConfig {
+    /// Reads `COPYCAT_WINDOW_SECS` (default 21600), `COPYCAT_HASH_DISTANCE`
+    /// (default 6), `COPYCAT_MIN_KEY_LEN` (default 3) and
+    /// `COPYCAT_FILTER_COPIES` (default true).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        CopycatConfig {
+            window: chrono::Duration::seconds(read("COPYCAT_WINDOW_SECS").unwrap_or(21600) as i64),
+            max_hash_distance: read("COPYCAT_HASH_DISTANCE").unwrap_or(6) as u32,
+            min_key_len: read("COPYCAT_MIN_KEY_LEN").unwrap_or(3) as usize,
+            filter_copies: env::var("COPYCAT_FILTER_COPIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// A name or symbol reduced to what a copy can't change without looking
+/// different: lowercase letters and digits, with punctuation, spacing,
+/// emoji and a leading `$` dropped.
+pub fn normalize_key(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// 64-bit difference hash of an image: the image is shrunk to 9x8
+/// grayscale and each bit records whether a pixel is darker than its right
+/// neighbour. Re-encoding, resizing and light compression leave it within a
+/// few bits.
+pub fn image_hash(bytes: &[u8]) -> Result<i64> {
+    let pixels = image::load_from_memory(bytes)?
+        .resize_exact(9, 8, FilterType::Triangle)
+        .to_luma8();
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if pixels.get_pixel(x, y)[0] < pixels.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    Ok(hash as i64)
+}
+
+/// Number of bits two image hashes differ in.
+pub fn hash_distance(a: i64, b: i64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+struct Launch {
+    mint_address: String,
+    name_key: Option<String>,
+    symbol_key: Option<String>,
+    image_hash: Option<i64>,
+    launched_at: DateTime<Utc>,
+    copy_of: Option<String>,
+}
+
+/// The launches of the last `window`, oldest first, with which of them copy
+/// which. A launch copies the oldest earlier launch that shares its name,
+/// symbol or image; copies of a copy are attributed to the first launch in
+/// the chain.
+pub struct CopycatIndex {
+    config: CopycatConfig,
+    launches: VecDeque<Launch>,
+    clone_counts: HashMap<String, usize>,
+}
+
+impl CopycatIndex {
+    pub fn new(config: CopycatConfig) -> Self {
+        CopycatIndex {
+            config,
+            launches: VecDeque::new(),
+            clone_counts: HashMap::new(),
+        }
+    }
+
+    /// Adds a launch by its on-chain name and symbol and returns what it
+    /// copies, if anything. Its image is added later with `add_image`.
+    ///
+    /// # Arguments
+    /// * `mint_address` - The new token
+    /// * `name` - Its name, if known
+    /// * `symbol` - Its symbol, if known
+    /// * `launched_at` - When it was created
+    pub fn add_launch(
+        &mut self,
+        mint_address: &str,
+        name: Option<&str>,
+        symbol: Option<&str>,
+        launched_at: DateTime<Utc>,
+    ) -> Option<CopyTag> {
+        self.prune(launched_at);
+        let mut launch = Launch {
+            mint_address: mint_address.to_string(),
+            name_key: self.key(name),
+            symbol_key: self.key(symbol),
+            image_hash: None,
+            launched_at,
+            copy_of: None,
+        };
+        let found = self.launches.iter().find_map(|earlier| {
+            let matched_on = if launch.name_key.is_some() && earlier.name_key == launch.name_key {
+                CopyMatch::Name
+            } else if launch.symbol_key.is_some() && earlier.symbol_key == launch.symbol_key {
+                CopyMatch::Symbol
+            } else {
+                return None;
+            };
+            Some((original_of(earlier).to_string(), matched_on))
+        });
+        let tag = found.map(|(original, matched_on)| {
+            launch.copy_of = Some(original.clone());
+            self.tag(original, matched_on)
+        });
+        self.launches.push_back(launch);
+        tag
+    }
+
+    /// Records a launch's image hash. Returns the launches that turn out to
+    /// copy by image: this one, if it copies an earlier launch, or later
+    /// launches whose hash arrived first and copy this one.
+    pub fn add_image(&mut self, mint_address: &str, image_hash: i64) -> Vec<(String, CopyTag)> {
+        let Some(position) = self.launches.iter().position(|l| l.mint_address == mint_address) else {
+            return Vec::new();
+        };
+        self.launches[position].image_hash = Some(image_hash);
+        // A blank image hashes to zero and matches every other blank one.
+        if image_hash == 0 {
+            return Vec::new();
+        }
+        let max_distance = self.config.max_hash_distance;
+        let matches = |launch: &Launch| {
+            launch
+                .image_hash
+                .is_some_and(|other| other != 0 && hash_distance(other, image_hash) <= max_distance)
+        };
+
+        let mut tags = Vec::new();
+        if self.launches[position].copy_of.is_none() {
+            if let Some(earlier) = self.launches.iter().take(position).find(|l| matches(l)) {
+                let original = original_of(earlier).to_string();
+                self.launches[position].copy_of = Some(original.clone());
+                tags.push((mint_address.to_string(), self.tag(original, CopyMatch::Image)));
+                return tags;
+            }
+        }
+        let original = original_of(&self.launches[position]).to_string();
+        let later: Vec<usize> = (position + 1..self.launches.len())
+            .filter(|&i| self.launches[i].copy_of.is_none() && matches(&self.launches[i]))
+            .collect();
+        for i in later {
+            self.launches[i].copy_of = Some(original.clone());
+            let copy = self.launches[i].mint_address.clone();
+            tags.push((copy, self.tag(original.clone(), CopyMatch::Image)));
+        }
+        tags
+    }
+
+    /// Puts back a launch loaded from the store, with the copy it was
+    /// already tagged as. Launches must be restored oldest first.
+    pub fn restore(&mut self, launch: &LaunchFingerprint) {
+        self.prune(launch.created_at);
+        if let Some(original) = &launch.copy_of {
+            *self.clone_counts.entry(original.clone()).or_default() += 1;
+        }
+        self.launches.push_back(Launch {
+            mint_address: launch.mint_address.clone(),
+            name_key: self.key(launch.name.as_deref()),
+            symbol_key: self.key(launch.symbol.as_deref()),
+            image_hash: launch.image_hash,
+            launched_at: launch.created_at,
+            copy_of: launch.copy_of.clone(),
+        });
+    }
+
+    /// The original a launch in the window copies, if it is a copy.
+    pub fn copy_of(&self, mint_address: &str) -> Option<&str> {
+        self.launches
+            .iter()
+            .find(|l| l.mint_address == mint_address)
+            .and_then(|l| l.copy_of.as_deref())
+    }
+
+    /// The normalized form of a name or symbol, if long enough to match on.
+    fn key(&self, text: Option<&str>) -> Option<String> {
+        text.map(normalize_key).filter(|key| key.chars().count() >= self.config.min_key_len)
+    }
+
+    fn tag(&mut self, original_mint: String, matched_on: CopyMatch) -> CopyTag {
+        let clone_count = self.clone_counts.entry(original_mint.clone()).or_default();
+        *clone_count += 1;
+        CopyTag {
+            clone_count: *clone_count,
+            original_mint,
+            matched_on,
+        }
+    }
+
+    /// Drops launches older than the window as of `now`, and the clone
+    /// counts of originals nothing left in the window refers to.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - self.config.window;
+        let mut pruned = false;
+        while self.launches.front().is_some_and(|l| l.launched_at < cutoff) {
+            self.launches.pop_front();
+            pruned = true;
+        }
+        if pruned {
+            let launches = &self.launches;
+            self.clone_counts
+                .retain(|original, _| launches.iter().any(|l| original_of(l) == original));
+        }
+    }
+}
+
+/// The first launch in `launch`'s copy chain.
+fn original_of(launch: &Launch) -> &str {
+    launch.copy_of.as_deref().unwrap_or(&launch.mint_address)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        chrono::TimeZone,
+        image::{ImageFormat, Rgb, RgbImage},
+        std::io::Cursor,
+    };
+
+    fn config() -> CopycatConfig {
+        CopycatConfig {
+            window: chrono::Duration::hours(1),
+            max_hash_distance: 6,
+            min_key_len: 3,
+            filter_copies: true,
+        }
+    }
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::minutes(minutes)
+    }
+
+    fn tag(original: &str, matched_on: CopyMatch, clone_count: usize) -> Option<CopyTag> {
+        Some(CopyTag {
+            original_mint: original.to_string(),
+            matched_on,
+            clone_count,
+        })
+    }
+
+    /// A PNG of a horizontal gradient, brightening left to right or the
+    /// other way.
+    fn gradient(width: u32, rising: bool) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, width, |x, _| {
+            let level = (x * 255 / (width - 1)) as u8;
+            let level = if rising { level } else { 255 - level };
+            Rgb([level, level, level])
+        });
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn keys_keep_only_lowercase_letters_and_digits() {
+        assert_eq!(normalize_key("$PE-PE 2.0 🐸"), "pepe20");
+        assert_eq!(normalize_key("  "), "");
+    }
+
+    #[test]
+    fn copies_by_name_or_symbol_are_tagged_with_the_original() {
+        let mut index = CopycatIndex::new(config());
+        assert_eq!(index.add_launch("pepe", Some("Pepe"), Some("PEPE"), at(0)), None);
+        assert_eq!(
+            index.add_launch("name-copy", Some("$pepe!"), Some("PEPE2"), at(1)),
+            tag("pepe", CopyMatch::Name, 1)
+        );
+        assert_eq!(
+            index.add_launch("symbol-copy", Some("Frog"), Some("pepe"), at(2)),
+            tag("pepe", CopyMatch::Symbol, 2)
+        );
+        // Copying a copy still counts against the first launch.
+        assert_eq!(
+            index.add_launch("copy-of-copy", Some("Froggy"), Some("PEPE2"), at(3)),
+            tag("pepe", CopyMatch::Symbol, 3)
+        );
+        assert_eq!(index.copy_of("copy-of-copy"), Some("pepe"));
+        assert_eq!(index.copy_of("pepe"), None);
+    }
+
+    #[test]
+    fn short_or_missing_keys_never_match() {
+        let mut index = CopycatIndex::new(config());
+        index.add_launch("a", Some("AI"), None, at(0));
+        assert_eq!(index.add_launch("b", Some("a.i."), None, at(1)), None);
+        assert_eq!(index.add_launch("c", None, None, at(2)), None);
+    }
+
+    #[test]
+    fn launches_outside_the_window_are_forgotten() {
+        let mut index = CopycatIndex::new(config());
+        index.add_launch("sun", Some("Sun"), None, at(0));
+        index.add_launch("old", Some("Moon"), None, at(0));
+        index.add_launch("copy", Some("Moon"), None, at(30));
+        assert_eq!(index.add_launch("late-sun", Some("Sun"), None, at(61)), None);
+        // "old" has left the window, but a copy of it is still there.
+        assert_eq!(
+            index.add_launch("late-moon", Some("Moon"), None, at(61)),
+            tag("old", CopyMatch::Name, 2)
+        );
+        assert_eq!(index.copy_of("sun"), None);
+    }
+
+    #[test]
+    fn similar_images_hash_within_the_distance() {
+        let small = image_hash(&gradient(32, true)).unwrap();
+        let large = image_hash(&gradient(200, true)).unwrap();
+        let flipped = image_hash(&gradient(32, false)).unwrap();
+        assert!(hash_distance(small, large) <= config().max_hash_distance);
+        assert!(hash_distance(small, flipped) > config().max_hash_distance);
+        assert!(image_hash(b"not an image").is_err());
+    }
+
+    #[test]
+    fn image_copies_are_found_whichever_hash_arrives_first() {
+        let mut index = CopycatIndex::new(config());
+        index.add_launch("original", Some("Cat"), None, at(0));
+        index.add_launch("early-copy", Some("Kitty"), None, at(1));
+        index.add_launch("late-copy", Some("Meow"), None, at(2));
+
+        // The copy's hash arrives before the original's.
+        assert!(index.add_image("late-copy", 0b1011).is_empty());
+        assert_eq!(
+            index.add_image("original", 0b1111),
+            [("late-copy".to_string(), tag("original", CopyMatch::Image, 1).unwrap())]
+        );
+        assert_eq!(
+            index.add_image("early-copy", 0b0111),
+            [("early-copy".to_string(), tag("original", CopyMatch::Image, 2).unwrap())]
+        );
+        assert!(index.add_image("unknown", 0b1111).is_empty());
+    }
+
+    #[test]
+    fn blank_and_distant_images_do_not_match() {
+        let mut index = CopycatIndex::new(config());
+        index.add_launch("a", Some("One"), None, at(0));
+        index.add_launch("b", Some("Two"), None, at(1));
+        index.add_launch("c", Some("Three"), None, at(2));
+        index.add_image("a", 0);
+        assert!(index.add_image("b", 0).is_empty());
+        index.add_image("b", 0xff);
+        assert!(index.add_image("c", 0xff00).is_empty());
+    }
+
+    #[test]
+    fn restored_launches_keep_their_tags_and_counts() {
+        let mut index = CopycatIndex::new(config());
+        let fingerprint = |mint: &str, name: &str, minutes: i64, copy_of: Option<&str>| LaunchFingerprint {
+            mint_address: mint.to_string(),
+            name: Some(name.to_string()),
+            symbol: None,
+            image_hash: None,
+            created_at: at(minutes),
+            copy_of: copy_of.map(String::from),
+        };
+        index.restore(&fingerprint("original", "Dog", 0, None));
+        index.restore(&fingerprint("copy", "Doggo", 1, Some("original")));
+        assert_eq!(index.copy_of("copy"), Some("original"));
+        assert_eq!(
+            index.add_launch("new", Some("doggo"), None, at(2)),
+            tag("original", CopyMatch::Name, 2)
+        );
+    }
+}
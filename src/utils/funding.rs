@@ -0,0 +1,102 @@
+use {
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    solana_client::{
+        nonblocking::rpc_client::RpcClient,
+        rpc_client::GetConfirmedSignaturesForAddress2Config,
+        rpc_config::RpcTransactionConfig,
+    },
+    solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature},
+    solana_transaction_status::UiTransactionEncoding,
+    std::str::FromStr,
+};
+
+/// Largest page `getSignaturesForAddress` returns.
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Where a wallet got its first SOL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Funding {
+    pub funder: String,
+    pub funded_at: DateTime<Utc>,
+}
+
+/// The wallet that sent `wallet` its first SOL and when, taken from the
+/// oldest transaction in its history. `None` if that history is longer than
+/// `max_pages` pages of signatures or its first transaction didn't credit
+/// the wallet.
+///
+/// # Arguments
+/// * `rpc` - Client used for the signature and transaction lookups
+/// * `wallet` - The address to trace
+/// * `max_pages` - Pages of 1000 signatures to walk back at most
+pub async fn first_funder(rpc: &RpcClient, wallet: &str, max_pages: usize) -> Result<Option<Funding>> {
+    let address = Pubkey::from_str(wallet)?;
+    let mut before = None;
+    let mut oldest = None;
+    for _ in 0..max_pages {
+        let page = rpc
+            .get_signatures_for_address_with_config(
+                &address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(SIGNATURE_PAGE_SIZE),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        let signature = Signature::from_str(&last.signature)?;
+        if page.len() < SIGNATURE_PAGE_SIZE {
+            oldest = Some(signature);
+            break;
+        }
+        before = Some(signature);
+    }
+    let Some(signature) = oldest else {
+        return Ok(None);
+    };
+
+    let transaction = rpc
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let meta = transaction
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow!("Transaction {signature} has no status meta"))?;
+    let decoded = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Failed to decode transaction {signature}"))?;
+    let keys: Vec<String> = decoded.message.static_account_keys().iter().map(|key| key.to_string()).collect();
+    let funded_at = transaction
+        .block_time
+        .and_then(|block_time| DateTime::from_timestamp(block_time, 0))
+        .unwrap_or_else(Utc::now);
+    Ok(funder(&keys, &meta.pre_balances, &meta.post_balances, wallet).map(|funder| Funding { funder, funded_at }))
+}
+
+/// The account that lost the most lamports in a transaction that credited
+/// `wallet`, which for a plain transfer is the sender.
+fn funder(keys: &[String], pre_balances: &[u64], post_balances: &[u64], wallet: &str) -> Option<String> {
+    let balances = || keys.iter().zip(pre_balances.iter().zip(post_balances));
+    let credited = balances().any(|(key, (pre, post))| key == wallet && post > pre);
+    if !credited {
+        return None;
+    }
+    balances()
+        .filter(|(key, (pre, post))| *key != wallet && pre > post)
+        .max_by_key(|(_, (pre, post))| *pre - *post)
+        .map(|(key, _)| key.clone())
+}
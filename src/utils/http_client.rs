@@ -0,0 +1,333 @@
+use {
+    anyhow::{anyhow, Result},
+    reqwest::{header::{HeaderMap, RETRY_AFTER}, Client, Request, StatusCode},
+    serde::de::DeserializeOwned,
+    std::{
+        collections::HashMap,
+        env,
+        sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    tokio::sync::Semaphore,
+};
+
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    pub request_timeout: Duration,
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Maximum number of in-flight requests to any single host.
+    pub per_host_concurrency: usize,
+    /// How often per-host metrics are logged; zero turns the log off.
+    pub metrics_interval: Duration,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        HttpClientConfig {
+            request_timeout: Duration::from_secs(10),
+            max_retries: 3,
+            base_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            per_host_concurrency: 8,
+            metrics_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Reads `HTTP_TIMEOUT_MS`, `HTTP_MAX_RETRIES`, `HTTP_PER_HOST_CONCURRENCY`
+    /// and `HTTP_METRICS_INTERVAL_SECS`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = HttpClientConfig::default();
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        HttpClientConfig {
+            request_timeout: read("HTTP_TIMEOUT_MS").map(Duration::from_millis).unwrap_or(defaults.request_timeout),
+            max_retries: read("HTTP_MAX_RETRIES").map(|v| v as u32).unwrap_or(defaults.max_retries),
+            per_host_concurrency: read("HTTP_PER_HOST_CONCURRENCY").map(|v| v as usize).unwrap_or(defaults.per_host_concurrency),
+            metrics_interval: read("HTTP_METRICS_INTERVAL_SECS").map(Duration::from_secs).unwrap_or(defaults.metrics_interval),
+            ..defaults
+        }
+    }
+}
+
+/// A completed HTTP exchange. Non-2xx responses are returned as-is once
+/// retries are exhausted so callers can still inspect and record them.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+#[derive(Debug, Default)]
+struct HostMetrics {
+    requests: AtomicU64,
+    retries: AtomicU64,
+    failures: AtomicU64,
+    total_latency_us: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HostMetricsSnapshot {
+    pub requests: u64,
+    pub retries: u64,
+    pub failures: u64,
+    pub average_latency: Duration,
+}
+
+/// Shared async HTTP client used by every risk provider.
+///
+/// Wraps a single pooled `reqwest::Client` and adds a per-host concurrency
+/// limit, retries with exponential backoff on 429/5xx and transport errors,
+/// `Retry-After` handling and per-host request/latency metrics.
+pub struct HttpClient {
+    inner: Client,
+    config: HttpClientConfig,
+    host_limits: Mutex<HashMap<String, Arc<Semaphore>>>,
+    metrics: Mutex<HashMap<String, Arc<HostMetrics>>>,
+}
+
+impl HttpClient {
+    pub fn new(config: HttpClientConfig) -> Result<Arc<Self>> {
+        let inner = Client::builder()
+            .timeout(config.request_timeout)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()?;
+        Ok(Arc::new(HttpClient {
+            inner,
+            config,
+            host_limits: Mutex::new(HashMap::new()),
+            metrics: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Issues a GET request.
+    ///
+    /// # Arguments
+    /// * `url` - The request URL
+    /// * `query` - Query string parameters
+    /// * `headers` - Extra request headers
+    pub async fn get(&self, url: &str, query: &[(&str, String)], headers: &[(&str, String)]) -> Result<HttpResponse> {
+        let mut builder = self.inner.get(url).query(query);
+        for (name, value) in headers {
+            builder = builder.header(*name, value);
+        }
+        self.execute(builder.build()?).await
+    }
+
+    /// Sends `request`, retrying on 429, 5xx and transport errors.
+    pub async fn execute(&self, request: Request) -> Result<HttpResponse> {
+        let host = request.url().host_str().unwrap_or_default().to_string();
+        let limit = self.host_limit(&host);
+        let metrics = self.host_metrics(&host);
+
+        let mut attempt = 0;
+        loop {
+            let attempt_request = request
+                .try_clone()
+                .ok_or_else(|| anyhow!("Request to {host} can't be retried"))?;
+            let permit = limit.acquire().await?;
+            let started = Instant::now();
+            let result = self.inner.execute(attempt_request).await;
+            let latency = started.elapsed();
+            drop(permit);
+
+            metrics.requests.fetch_add(1, Ordering::Relaxed);
+            metrics.total_latency_us.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+
+            let retry_delay = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+                    if !retryable || attempt >= self.config.max_retries {
+                        if !status.is_success() {
+                            metrics.failures.fetch_add(1, Ordering::Relaxed);
+                        }
+                        let body = response.bytes().await?.to_vec();
+                        return Ok(HttpResponse { status, body });
+                    }
+                    retry_after(response.headers()).unwrap_or_else(|| self.backoff(attempt))
+                }
+                Err(e) => {
+                    let retryable = e.is_timeout() || e.is_connect();
+                    if !retryable || attempt >= self.config.max_retries {
+                        metrics.failures.fetch_add(1, Ordering::Relaxed);
+                        return Err(e.into());
+                    }
+                    self.backoff(attempt)
+                }
+            };
+
+            metrics.retries.fetch_add(1, Ordering::Relaxed);
+            attempt += 1;
+            tokio::time::sleep(retry_delay.min(self.config.max_backoff)).await;
+        }
+    }
+
+    /// Per-host request counters since startup.
+    pub fn metrics(&self) -> HashMap<String, HostMetricsSnapshot> {
+        let metrics = self.metrics.lock().unwrap();
+        metrics
+            .iter()
+            .map(|(host, m)| {
+                let requests = m.requests.load(Ordering::Relaxed);
+                let total_latency_us = m.total_latency_us.load(Ordering::Relaxed);
+                let snapshot = HostMetricsSnapshot {
+                    requests,
+                    retries: m.retries.load(Ordering::Relaxed),
+                    failures: m.failures.load(Ordering::Relaxed),
+                    average_latency: Duration::from_micros(total_latency_us.checked_div(requests).unwrap_or(0)),
+                };
+                (host.clone(), snapshot)
+            })
+            .collect()
+    }
+
+    /// Logs every host's metrics each `metrics_interval`, until the process
+    /// exits. Returns at once if the interval is zero.
+    pub async fn log_metrics(self: Arc<Self>) {
+        if self.config.metrics_interval.is_zero() {
+            return;
+        }
+        let mut ticker = tokio::time::interval(self.config.metrics_interval);
+        // The first tick fires immediately, before any request has been made.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let mut metrics: Vec<_> = self.metrics().into_iter().collect();
+            metrics.sort_by(|a, b| a.0.cmp(&b.0));
+            for (host, m) in metrics {
+                println!(
+                    "HTTP {host}: {} requests, {} retries, {} failures, {:?} average latency",
+                    m.requests, m.retries, m.failures, m.average_latency
+                );
+            }
+        }
+    }
+
+    fn host_limit(&self, host: &str) -> Arc<Semaphore> {
+        let mut limits = self.host_limits.lock().unwrap();
+        limits
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.config.per_host_concurrency)))
+            .clone()
+    }
+
+    fn host_metrics(&self, host: &str) -> Arc<HostMetrics> {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.entry(host.to_string()).or_default().clone()
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.config
+            .base_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff)
+    }
+}
+
+/// Parses a `Retry-After` header given either as delay-seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::utils::test_server::{in_order, serve, Reply},
+        reqwest::header::HeaderValue,
+    };
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    fn client(max_retries: u32) -> Arc<HttpClient> {
+        HttpClient::new(HttpClientConfig {
+            max_retries,
+            base_backoff: Duration::from_millis(1),
+            ..HttpClientConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        assert_eq!(retry_after(&headers("7")), Some(Duration::from_secs(7)));
+        assert_eq!(retry_after(&headers(" 0 ")), Some(Duration::ZERO));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc2822();
+        let delay = retry_after(&headers(&later)).unwrap();
+        assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30));
+        // A date in the past or an unparseable value means no hint.
+        let earlier = (chrono::Utc::now() - chrono::Duration::seconds(30)).to_rfc2822();
+        assert_eq!(retry_after(&headers(&earlier)), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let client = HttpClient::new(HttpClientConfig::default()).unwrap();
+        assert_eq!(client.backoff(0), Duration::from_millis(250));
+        assert_eq!(client.backoff(2), Duration::from_secs(1));
+        assert_eq!(client.backoff(10), Duration::from_secs(10));
+        assert_eq!(client.backoff(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_counts_them() {
+        let url = serve(in_order(vec![
+            Reply::new(503, "").header("Retry-After", "0"),
+            Reply::new(200, r#"{"ok":true}"#),
+        ]))
+        .await;
+        let client = client(3);
+        let response = client.get(&url, &[], &[]).await.unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.json::<serde_json::Value>().unwrap()["ok"], true);
+
+        let metrics = client.metrics()["127.0.0.1"];
+        assert_eq!((metrics.requests, metrics.retries, metrics.failures), (2, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_error_response_once_retries_run_out() {
+        let url = serve(in_order(vec![
+            Reply::new(429, "").header("Retry-After", "0"),
+            Reply::new(429, "slow"),
+        ]))
+        .await;
+        let client = client(1);
+        let response = client.get(&url, &[], &[]).await.unwrap();
+        assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.body, b"slow");
+        assert_eq!(client.metrics()["127.0.0.1"].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let url = serve(|_: &str| Reply::new(404, "")).await;
+        let client = client(3);
+        assert_eq!(client.get(&url, &[], &[]).await.unwrap().status, StatusCode::NOT_FOUND);
+        assert_eq!(client.metrics()["127.0.0.1"].retries, 0);
+    }
+}
@@ -0,0 +1,434 @@
+use {
+    anyhow::{anyhow, Result},
+    reqwest::{redirect, Client, Url},
+    serde_json::Value,
+    std::{
+        env,
+        net::{IpAddr, SocketAddr},
+        time::Duration,
+    },
+};
+
+const DEFAULT_IPFS_GATEWAYS: &str = "https://ipfs.io/ipfs/,https://cloudflare-ipfs.com/ipfs/,https://gateway.pinata.cloud/ipfs/";
+const DEFAULT_ARWEAVE_GATEWAYS: &str = "https://arweave.net/";
+
+#[derive(Debug, Clone)]
+pub struct MetadataConfig {
+    /// Longest a single gateway may take to return the whole document.
+    pub timeout: Duration,
+    /// Largest document accepted; pump.fun metadata is well under 1 KiB.
+    pub max_bytes: usize,
+    /// Largest image downloaded for the copycat image hash.
+    pub max_image_bytes: usize,
+    /// IPFS gateway prefixes, tried in order. Each is followed directly by
+    /// the CID and path.
+    pub ipfs_gateways: Vec<String>,
+    /// Arweave gateway prefixes, tried in order. Each is followed directly
+    /// by the transaction id.
+    pub arweave_gateways: Vec<String>,
+}
+
+impl MetadataConfig {
+    /// Reads `METADATA_TIMEOUT_MS` (default 5000), `METADATA_MAX_BYTES`
+    /// (default 65536), `METADATA_MAX_IMAGE_BYTES` (default 4194304),
+    /// `METADATA_IPFS_GATEWAYS` and `METADATA_ARWEAVE_GATEWAYS`
+    /// (comma-separated prefixes; the defaults are public gateways).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let gateways = |name: &str, default: &str| -> Vec<String> {
+            env::var(name)
+                .unwrap_or_else(|_| default.to_string())
+                .split(',')
+                .map(str::trim)
+                .filter(|gateway| !gateway.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        MetadataConfig {
+            timeout: Duration::from_millis(read("METADATA_TIMEOUT_MS").unwrap_or(5000)),
+            max_bytes: read("METADATA_MAX_BYTES").unwrap_or(65536) as usize,
+            max_image_bytes: read("METADATA_MAX_IMAGE_BYTES").unwrap_or(4 * 1024 * 1024) as usize,
+            ipfs_gateways: gateways("METADATA_IPFS_GATEWAYS", DEFAULT_IPFS_GATEWAYS),
+            arweave_gateways: gateways("METADATA_ARWEAVE_GATEWAYS", DEFAULT_ARWEAVE_GATEWAYS),
+        }
+    }
+}
+
+/// The fields we use from a token's metadata JSON. Blank strings are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataJson {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+/// Fetches metadata JSON from IPFS, Arweave or plain HTTP, falling back
+/// across gateways.
+///
+/// Unlike `HttpClient` it doesn't retry a gateway: the next gateway is the
+/// retry. Bodies are streamed so an oversized document is dropped as soon as
+/// it passes `max_bytes`.
+///
+/// URIs come from whoever created the token, so any URL outside the
+/// configured gateways is only fetched from public addresses, and redirects
+/// are never followed.
+pub struct MetadataFetcher {
+    client: Client,
+    config: MetadataConfig,
+}
+
+impl MetadataFetcher {
+    pub fn new(config: MetadataConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(config.timeout)
+            .redirect(redirect::Policy::none())
+            .build()?;
+        Ok(MetadataFetcher { client, config })
+    }
+
+    /// Fetches and parses the document at `uri` from the first gateway that
+    /// returns valid metadata.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI from the create instruction
+    pub async fn fetch(&self, uri: &str) -> Result<MetadataJson> {
+        self.fetch_first(uri, self.config.max_bytes, parse_metadata).await
+    }
+
+    /// Downloads the image at `uri`, which may be on IPFS or Arweave like
+    /// the metadata, up to `max_image_bytes`.
+    pub async fn fetch_image(&self, uri: &str) -> Result<Vec<u8>> {
+        self.fetch_first(uri, self.config.max_image_bytes, |body| Ok(body.to_vec())).await
+    }
+
+    /// The first candidate URL of `uri` whose body `parse` accepts.
+    async fn fetch_first<T>(&self, uri: &str, max_bytes: usize, parse: impl Fn(&[u8]) -> Result<T>) -> Result<T> {
+        let urls = self.candidate_urls(uri);
+        if urls.is_empty() {
+            return Err(anyhow!("Unsupported URI: {uri}"));
+        }
+        let mut errors = Vec::new();
+        for url in urls {
+            match self.fetch_body(&url, max_bytes).await.and_then(|body| parse(&body)) {
+                Ok(parsed) => return Ok(parsed),
+                Err(e) => errors.push(format!("{url}: {e}")),
+            }
+        }
+        Err(anyhow!("Failed to fetch {uri}: {}", errors.join("; ")))
+    }
+
+    /// Every URL `uri` can be fetched from, in the order to try them: the
+    /// URI itself if it is HTTP, then the configured gateways for its
+    /// network.
+    pub fn candidate_urls(&self, uri: &str) -> Vec<String> {
+        let uri = uri.trim();
+        let mut urls = Vec::new();
+        if uri.starts_with("http://") || uri.starts_with("https://") {
+            urls.push(uri.to_string());
+        }
+        if let Some(path) = ipfs_path(uri) {
+            urls.extend(self.config.ipfs_gateways.iter().map(|gateway| format!("{gateway}{path}")));
+        } else if let Some(id) = arweave_id(uri) {
+            urls.extend(self.config.arweave_gateways.iter().map(|gateway| format!("{gateway}{id}")));
+        }
+        let mut unique = Vec::new();
+        for url in urls {
+            if !unique.contains(&url) {
+                unique.push(url);
+            }
+        }
+        unique
+    }
+
+    fn is_gateway(&self, url: &str) -> bool {
+        let mut gateways = self.config.ipfs_gateways.iter().chain(&self.config.arweave_gateways);
+        gateways.any(|gateway| url.starts_with(gateway.as_str()))
+    }
+
+    /// A client for a creator-supplied `url` that connects only to the
+    /// public addresses its host resolves to. Pinning them stops the host
+    /// from resolving somewhere else by the time the request connects.
+    async fn public_client(&self, url: &str) -> Result<Client> {
+        let url = Url::parse(url)?;
+        let host = url.host_str().ok_or_else(|| anyhow!("No host in {url}"))?;
+        let builder = Client::builder().timeout(self.config.timeout).redirect(redirect::Policy::none());
+        if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            if !is_public(ip) {
+                return Err(anyhow!("Refusing non-public address {ip}"));
+            }
+            return Ok(builder.build()?);
+        }
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+        if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+            return Err(anyhow!("Refusing {host}, which resolves to non-public address {}", addr.ip()));
+        }
+        Ok(builder.resolve_to_addrs(host, &addrs).build()?)
+    }
+
+    async fn fetch_body(&self, url: &str, max_bytes: usize) -> Result<Vec<u8>> {
+        let client = if self.is_gateway(url) {
+            self.client.clone()
+        } else {
+            self.public_client(url).await?
+        };
+        let mut response = client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status()));
+        }
+        if response.content_length().is_some_and(|length| length as usize > max_bytes) {
+            return Err(anyhow!("Document larger than {max_bytes} bytes"));
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() > max_bytes {
+                return Err(anyhow!("Document larger than {max_bytes} bytes"));
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// Whether `ip` is reachable on the public internet: not loopback, private,
+/// link-local (cloud metadata services live there), shared, multicast or
+/// otherwise reserved.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// The CID and path of an IPFS URI, either `ipfs://` or a gateway URL.
+fn ipfs_path(uri: &str) -> Option<&str> {
+    if let Some(path) = uri.strip_prefix("ipfs://") {
+        return Some(path.strip_prefix("ipfs/").unwrap_or(path));
+    }
+    let (_, path) = uri.split_once("/ipfs/")?;
+    Some(path).filter(|path| !path.is_empty())
+}
+
+/// The transaction id of an Arweave URI, either `ar://` or an arweave.net URL.
+fn arweave_id(uri: &str) -> Option<&str> {
+    if let Some(id) = uri.strip_prefix("ar://") {
+        return Some(id);
+    }
+    let rest = uri.strip_prefix("https://").or_else(|| uri.strip_prefix("http://"))?;
+    let (host, id) = rest.split_once('/')?;
+    (host == "arweave.net" || host.ends_with(".arweave.net")).then_some(id).filter(|id| !id.is_empty())
+}
+
+/// Parses metadata JSON. Social links are read from the top level, where
+/// pump.fun puts them, or from the `extensions` object some tools use.
+pub fn parse_metadata(body: &[u8]) -> Result<MetadataJson> {
+    let json: Value = serde_json::from_slice(body)?;
+    if !json.is_object() {
+        return Err(anyhow!("Metadata is not a JSON object"));
+    }
+    let text = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(String::from)
+    };
+    let link = |key: &str| text(&json, key).or_else(|| json.get("extensions").and_then(|ext| text(ext, key)));
+    Ok(MetadataJson {
+        name: text(&json, "name"),
+        symbol: text(&json, "symbol"),
+        description: text(&json, "description"),
+        image: text(&json, "image"),
+        twitter: link("twitter"),
+        telegram: link("telegram"),
+        website: link("website"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::utils::test_server::{in_order, serve, Reply},
+        serde_json::json,
+    };
+
+    const CID: &str = "QmTestCid/metadata.json";
+
+    /// A fetcher whose only gateways are `gateways`, each a local server.
+    fn fetcher(gateways: &[&str]) -> MetadataFetcher {
+        MetadataFetcher::new(MetadataConfig {
+            timeout: Duration::from_millis(200),
+            max_bytes: 1024,
+            max_image_bytes: 1024,
+            ipfs_gateways: gateways.iter().map(|base| format!("{base}/ipfs/")).collect(),
+            arweave_gateways: gateways.iter().map(|base| format!("{base}/")).collect(),
+        })
+        .unwrap()
+    }
+
+    fn document() -> Reply {
+        Reply::json(
+            200,
+            &json!({
+                "name": "Token",
+                "symbol": "TKN",
+                "description": "A token",
+                "image": "ipfs://QmImage",
+                "twitter": "https://x.com/token",
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_next_gateway() {
+        let down = serve(|_| Reply::new(502, "bad gateway")).await;
+        let up = serve(|path| {
+            assert_eq!(path, format!("/ipfs/{CID}"));
+            document()
+        })
+        .await;
+        let json = fetcher(&[&down, &up]).fetch(&format!("ipfs://{CID}")).await.unwrap();
+        assert_eq!(json.symbol.as_deref(), Some("TKN"));
+        assert_eq!(json.twitter.as_deref(), Some("https://x.com/token"));
+    }
+
+    #[tokio::test]
+    async fn slow_gateways_time_out() {
+        let slow = serve(|_| document().delay(Duration::from_secs(2))).await;
+        let error = fetcher(&[&slow]).fetch(&format!("ipfs://{CID}")).await.unwrap_err();
+        assert!(error.to_string().starts_with("Failed to fetch"), "{error}");
+
+        let fast = serve(|_| document()).await;
+        assert!(fetcher(&[&slow, &fast]).fetch(&format!("ipfs://{CID}")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn oversized_documents_are_dropped() {
+        let large = serve(|_| Reply::new(200, vec![b' '; 2048])).await;
+        let error = fetcher(&[&large]).fetch(&format!("ipfs://{CID}")).await.unwrap_err();
+        assert!(error.to_string().contains("larger than 1024 bytes"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn invalid_json_moves_on_to_the_next_gateway() {
+        let broken = serve(in_order(vec![Reply::new(200, "<html>"), Reply::json(200, &json!([1, 2]))])).await;
+        let up = serve(|_| document()).await;
+        let fetcher = fetcher(&[&broken, &up]);
+        assert_eq!(fetcher.fetch("ar://tx").await.unwrap().name.as_deref(), Some("Token"));
+        assert_eq!(fetcher.fetch("ar://tx").await.unwrap().name.as_deref(), Some("Token"));
+
+        let error = self::fetcher(&[&broken]).fetch("ar://tx").await.unwrap_err();
+        assert!(error.to_string().contains("not a JSON object"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn creator_urls_only_reach_public_addresses() {
+        let local = serve(|_| document()).await;
+        let fetcher = fetcher(&[]);
+        let error = fetcher.fetch(&format!("{local}/token.json")).await.unwrap_err();
+        assert!(error.to_string().contains("Refusing non-public address 127.0.0.1"), "{error}");
+        let port = local.rsplit(':').next().unwrap();
+        let error = fetcher.fetch(&format!("http://localhost:{port}/token.json")).await.unwrap_err();
+        assert!(error.to_string().contains("Refusing localhost"), "{error}");
+        assert!(fetcher.fetch("http://169.254.169.254/latest/meta-data/").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn redirects_are_not_followed() {
+        let target = serve(|_| document()).await;
+        let redirecting = serve(move |path| Reply::new(302, "").header("Location", format!("{target}{path}"))).await;
+        let error = fetcher(&[&redirecting]).fetch(&format!("ipfs://{CID}")).await.unwrap_err();
+        assert!(error.to_string().contains("HTTP 302"), "{error}");
+    }
+
+    #[test]
+    fn private_and_reserved_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "104.16.0.1", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn candidate_urls_try_the_uri_then_its_gateways() {
+        let fetcher = fetcher(&["http://a", "http://b"]);
+        assert_eq!(
+            fetcher.candidate_urls(&format!("https://ipfs.io/ipfs/{CID}")),
+            [
+                format!("https://ipfs.io/ipfs/{CID}"),
+                format!("http://a/ipfs/{CID}"),
+                format!("http://b/ipfs/{CID}")
+            ]
+        );
+        assert_eq!(fetcher.candidate_urls(&format!("http://a/ipfs/{CID}")).len(), 2);
+        assert_eq!(fetcher.candidate_urls("ar://tx"), ["http://a/tx", "http://b/tx"]);
+        assert_eq!(fetcher.candidate_urls("https://arweave.net/tx")[1], "http://a/tx");
+        assert_eq!(fetcher.candidate_urls("https://example.com/token.json"), ["https://example.com/token.json"]);
+        assert!(fetcher.candidate_urls("data:application/json,{}").is_empty());
+    }
+
+    #[test]
+    fn parses_links_from_the_top_level_or_extensions() {
+        let json = parse_metadata(
+            br#"{
+                "name": " Token ",
+                "symbol": "",
+                "description": "   ",
+                "twitter": "https://x.com/top",
+                "extensions": {"twitter": "https://x.com/ext", "telegram": "https://t.me/token"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            MetadataJson {
+                name: Some("Token".to_string()),
+                twitter: Some("https://x.com/top".to_string()),
+                telegram: Some("https://t.me/token".to_string()),
+                ..MetadataJson::default()
+            }
+        );
+        assert!(parse_metadata(b"not json").is_err());
+        assert!(parse_metadata(b"\"a string\"").is_err());
+    }
+}
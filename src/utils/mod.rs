@@ -1,2 +1,16 @@
 pub mod token_filter;
-pub mod rug_check;
\ No newline at end of file
+pub mod rug_check;
+pub mod onchain_check;
+pub mod http_client;
+pub mod pump_fun;
+pub mod archive;
+pub mod risk_model;
+pub mod funding;
+pub mod bundle_check;
+pub mod wash_check;
+pub mod metadata_fetch;
+pub mod copycat;
+pub mod hash;
+
+#[cfg(test)]
+pub mod test_server;
@@ -0,0 +1,280 @@
+use {
+    crate::utils::rug_check::{RiskFactor, TokenMetadata},
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::{commitment_config::CommitmentConfig, program_pack::Pack, pubkey, pubkey::Pubkey},
+    spl_token::state::Mint,
+    std::{error::Error, sync::Arc},
+};
+
+/// Metaplex Token Metadata program.
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// The parts of a Metaplex metadata account that matter for rug checks.
+#[derive(Debug, Clone)]
+pub struct MetaplexMetadata {
+    pub update_authority: Pubkey,
+    pub is_mutable: bool,
+}
+
+/// Reads mint and metadata accounts straight from the chain, so safety
+/// decisions don't depend on third-party APIs being up.
+pub struct OnChainChecker {
+    rpc: Arc<RpcClient>,
+}
+
+impl OnChainChecker {
+    pub fn new(rpc: Arc<RpcClient>) -> Self {
+        OnChainChecker { rpc }
+    }
+
+    /// Fetches the mint account and its Metaplex metadata account in a single
+    /// RPC round-trip and builds a `TokenMetadata` from them.
+    ///
+    /// # Arguments
+    /// * `mint` - The token mint to inspect
+    pub async fn fetch_metadata(&self, mint: &Pubkey) -> Result<TokenMetadata, Box<dyn Error + Send + Sync>> {
+        let metadata_address = metadata_pda(mint);
+        let accounts = self.rpc
+            .get_multiple_accounts_with_commitment(&[*mint, metadata_address], CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        let mint_account = accounts
+            .first()
+            .cloned()
+            .flatten()
+            .ok_or_else(|| format!("Mint account {mint} not found"))?;
+        if mint_account.data.len() < Mint::LEN {
+            return Err(format!("Account {mint} is not a mint").into());
+        }
+        // Token-2022 mints carry extensions after the base layout, so only the
+        // first `Mint::LEN` bytes are unpacked. Unchecked so an uninitialized
+        // mint is reported rather than rejected.
+        let mint_state = Mint::unpack_unchecked(&mint_account.data[..Mint::LEN])?;
+
+        let metaplex = accounts
+            .get(1)
+            .cloned()
+            .flatten()
+            .and_then(|account| parse_metaplex_metadata(&account.data));
+
+        Ok(TokenMetadata {
+            mint_authority: Option::<Pubkey>::from(mint_state.mint_authority).map(|pk| pk.to_string()),
+            freeze_authority: Option::<Pubkey>::from(mint_state.freeze_authority).map(|pk| pk.to_string()),
+            supply: mint_state.supply,
+            decimals: mint_state.decimals,
+            is_initialized: mint_state.is_initialized,
+            metadata_mutable: metaplex.as_ref().map(|m| m.is_mutable),
+            update_authority: metaplex.as_ref().map(|m| m.update_authority.to_string()),
+        })
+    }
+
+    /// Fetches the on-chain metadata for `mint` and evaluates it.
+    pub async fn check(&self, mint: &Pubkey) -> Result<(TokenMetadata, Vec<RiskFactor>), Box<dyn Error + Send + Sync>> {
+        let metadata = self.fetch_metadata(mint).await?;
+        let risk_factors = assess_metadata(&metadata);
+        Ok((metadata, risk_factors))
+    }
+}
+
+/// Derives the risk factors implied by a token's on-chain metadata.
+pub fn assess_metadata(metadata: &TokenMetadata) -> Vec<RiskFactor> {
+    let mut risk_factors = Vec::new();
+    if !metadata.is_initialized {
+        risk_factors.push(RiskFactor::MintNotInitialized);
+    }
+    if metadata.mint_authority.is_some() {
+        risk_factors.push(RiskFactor::MintAuthorityEnabled);
+    }
+    if metadata.freeze_authority.is_some() {
+        risk_factors.push(RiskFactor::FreezeAuthorityEnabled);
+    }
+    match metadata.metadata_mutable {
+        Some(true) => risk_factors.push(RiskFactor::MutableMetadata),
+        Some(false) => {}
+        None => risk_factors.push(RiskFactor::MissingMetadata),
+    }
+    risk_factors
+}
+
+/// Address of the Metaplex metadata account for `mint`.
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Parses the leading fields of a Metaplex `Metadata` account, up to and
+/// including `is_mutable`. Returns `None` if the data is truncated or isn't a
+/// metadata account.
+pub fn parse_metaplex_metadata(data: &[u8]) -> Option<MetaplexMetadata> {
+    // Key::MetadataV1
+    const METADATA_V1_KEY: u8 = 4;
+    const CREATOR_LEN: usize = 32 + 1 + 1;
+
+    let mut reader = BorshReader::new(data);
+    if reader.u8()? != METADATA_V1_KEY {
+        return None;
+    }
+    let update_authority = reader.pubkey()?;
+    let _mint = reader.pubkey()?;
+    // Name, symbol and URI; the launch's create event already carries them.
+    for _ in 0..3 {
+        reader.string()?;
+    }
+    let _seller_fee_basis_points = reader.bytes(2)?;
+    if reader.u8()? == 1 {
+        let creators = reader.u32()? as usize;
+        reader.bytes(creators.checked_mul(CREATOR_LEN)?)?;
+    }
+    let _primary_sale_happened = reader.u8()?;
+    let is_mutable = reader.u8()? != 0;
+
+    Some(MetaplexMetadata {
+        update_authority,
+        is_mutable,
+    })
+}
+
+/// Minimal cursor over borsh-encoded account data.
+pub(crate) struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        BorshReader { data, offset: 0 }
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let slice = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(slice)
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.bytes(8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn i64(&mut self) -> Option<i64> {
+        self.bytes(8).map(|b| i64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn bool(&mut self) -> Option<bool> {
+        self.u8().map(|b| b != 0)
+    }
+
+    pub(crate) fn pubkey(&mut self) -> Option<Pubkey> {
+        self.bytes(32).and_then(|b| Pubkey::try_from(b).ok())
+    }
+
+    /// Metaplex pads names/symbols/uris with NUL bytes, which are stripped.
+    pub(crate) fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let raw = self.bytes(len)?;
+        Some(String::from_utf8_lossy(raw).trim_end_matches('\0').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh_string(out: &mut Vec<u8>, value: &str, padded_len: usize) {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(padded_len.max(bytes.len()), 0);
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+
+    fn metadata_account(update_authority: &Pubkey, creators: usize, is_mutable: bool) -> Vec<u8> {
+        let mut data = vec![4];
+        data.extend_from_slice(update_authority.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        borsh_string(&mut data, "Doge", 32);
+        borsh_string(&mut data, "DOGE", 10);
+        borsh_string(&mut data, "https://ipfs.io/ipfs/Qm", 200);
+        data.extend_from_slice(&500u16.to_le_bytes());
+        if creators > 0 {
+            data.push(1);
+            data.extend_from_slice(&(creators as u32).to_le_bytes());
+            for _ in 0..creators {
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.extend_from_slice(&[1, 100]);
+            }
+        } else {
+            data.push(0);
+        }
+        data.push(0);
+        data.push(is_mutable as u8);
+        // Collection, uses and the rest follow in real accounts.
+        data.extend_from_slice(&[0; 64]);
+        data
+    }
+
+    #[test]
+    fn parses_metadata_with_and_without_creators() {
+        let authority = Pubkey::new_unique();
+        let parsed = parse_metaplex_metadata(&metadata_account(&authority, 0, true)).unwrap();
+        assert_eq!(parsed.update_authority, authority);
+        assert!(parsed.is_mutable);
+
+        let parsed = parse_metaplex_metadata(&metadata_account(&authority, 3, false)).unwrap();
+        assert_eq!(parsed.update_authority, authority);
+        assert!(!parsed.is_mutable);
+    }
+
+    #[test]
+    fn rejects_other_accounts_and_truncated_data() {
+        let mut data = metadata_account(&Pubkey::new_unique(), 2, true);
+        data[0] = 6;
+        assert!(parse_metaplex_metadata(&data).is_none());
+
+        let data = metadata_account(&Pubkey::new_unique(), 2, true);
+        // Cut inside the last creator, before the flags.
+        let flags_end = data.len() - 64;
+        assert!(parse_metaplex_metadata(&data[..flags_end - 12]).is_none());
+        assert!(parse_metaplex_metadata(&data[..flags_end]).is_some());
+        assert!(parse_metaplex_metadata(&[]).is_none());
+    }
+
+    #[test]
+    fn strips_nul_padding_from_strings() {
+        let mut data = Vec::new();
+        borsh_string(&mut data, "PEPE", 10);
+        assert_eq!(BorshReader::new(&data).string().as_deref(), Some("PEPE"));
+    }
+
+    #[test]
+    fn assesses_authorities_and_mutability() {
+        let mut metadata = TokenMetadata {
+            mint_authority: None,
+            freeze_authority: None,
+            supply: 1_000_000_000,
+            decimals: 6,
+            is_initialized: true,
+            metadata_mutable: Some(false),
+            update_authority: None,
+        };
+        assert!(assess_metadata(&metadata).is_empty());
+
+        metadata.mint_authority = Some(Pubkey::new_unique().to_string());
+        metadata.metadata_mutable = None;
+        assert_eq!(
+            assess_metadata(&metadata),
+            vec![RiskFactor::MintAuthorityEnabled, RiskFactor::MissingMetadata]
+        );
+    }
+}
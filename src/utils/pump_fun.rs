@@ -0,0 +1,366 @@
+use {
+    super::onchain_check::BorshReader,
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey, pubkey::Pubkey},
+};
+
+/// The pump.fun bonding curve program.
+pub const PUMP_FUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+/// The pump.fun AMM that completed curves migrate to.
+pub const PUMP_AMM_PROGRAM_ID: Pubkey = pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+
+/// Tokens a fresh curve can sell before it completes (793.1M with 6 decimals).
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+
+/// Every pump.fun token mints 1B tokens with 6 decimals.
+pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+/// Virtual reserves every curve starts with.
+pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
+
+/// Pump.fun tokens all use 6 decimals.
+pub const TOKEN_DECIMALS: u32 = 6;
+
+// Anchor discriminators: the first 8 bytes of sha256("account:<Name>") / sha256("event:<Name>").
+const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+const AMM_BUY_EVENT_DISCRIMINATOR: [u8; 8] = [103, 244, 82, 31, 44, 245, 119, 119];
+const AMM_SELL_EVENT_DISCRIMINATOR: [u8; 8] = [62, 47, 55, 10, 165, 3, 220, 42];
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Address of the bonding curve account for `mint`.
+pub fn bonding_curve_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &PUMP_FUN_PROGRAM_ID).0
+}
+
+/// State of a pump.fun bonding curve account. Reserves are in base units.
+#[derive(Debug, Clone, Copy)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    /// Set once the curve has sold out and the token migrates to an AMM.
+    pub complete: bool,
+}
+
+impl BondingCurve {
+    /// Parses a bonding curve account. The token supply is always
+    /// `TOKEN_TOTAL_SUPPLY` and is skipped. Newer curves append fields after
+    /// `complete`; those are ignored.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = BorshReader::new(data);
+        if reader.bytes(8)? != BONDING_CURVE_DISCRIMINATOR {
+            return None;
+        }
+        let virtual_token_reserves = reader.u64()?;
+        let virtual_sol_reserves = reader.u64()?;
+        let real_token_reserves = reader.u64()?;
+        let real_sol_reserves = reader.u64()?;
+        reader.u64()?;
+        Some(BondingCurve {
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves,
+            complete: reader.bool()?,
+        })
+    }
+
+    /// Reconstructs a curve from the virtual reserves a `TradeEvent` reports.
+    /// The virtual reserves are the real ones plus a fixed offset.
+    pub fn from_virtual_reserves(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> Self {
+        let real_token_reserves =
+            virtual_token_reserves.saturating_sub(INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES);
+        BondingCurve {
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves: virtual_sol_reserves.saturating_sub(INITIAL_VIRTUAL_SOL_RESERVES),
+            complete: real_token_reserves == 0,
+        }
+    }
+
+    /// How much of the sellable supply has been bought, in percent.
+    pub fn progress_percent(&self) -> f64 {
+        let remaining = self.real_token_reserves as f64 / INITIAL_REAL_TOKEN_RESERVES as f64;
+        ((1.0 - remaining) * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// SOL deposited into the curve by buyers.
+    pub fn liquidity_sol(&self) -> f64 {
+        self.real_sol_reserves as f64 / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Raw tokens a buy of `lamports` returns, after the program takes its
+    /// `fee_bps` fee out of the SOL. Capped at the tokens left on the curve.
+    pub fn buy_quote(&self, lamports: u64, fee_bps: u64) -> u64 {
+        if self.complete || self.virtual_sol_reserves == 0 {
+            return 0;
+        }
+        let sol_in = lamports as u128 * 10_000 / (10_000 + fee_bps as u128);
+        let product = self.virtual_sol_reserves as u128 * self.virtual_token_reserves as u128;
+        let remaining = product / (self.virtual_sol_reserves as u128 + sol_in) + 1;
+        let tokens = (self.virtual_token_reserves as u128).saturating_sub(remaining) as u64;
+        tokens.min(self.real_token_reserves)
+    }
+
+    /// Lamports a sell of `tokens` raw tokens returns, after the program
+    /// takes its `fee_bps` fee.
+    pub fn sell_quote(&self, tokens: u64, fee_bps: u64) -> u64 {
+        if self.complete || self.virtual_token_reserves == 0 {
+            return 0;
+        }
+        let sol_out = tokens as u128 * self.virtual_sol_reserves as u128
+            / (self.virtual_token_reserves as u128 + tokens as u128);
+        let fee = sol_out * fee_bps as u128 / 10_000;
+        (sol_out - fee) as u64
+    }
+}
+
+/// Emitted when a token is launched.
+#[derive(Debug, Clone)]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+}
+
+/// Emitted for every buy and sell against a curve.
+#[derive(Debug, Clone)]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    /// Unix timestamp of the block.
+    pub timestamp: i64,
+    /// Curve reserves after the trade.
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+}
+
+/// A swap on a pump.fun AMM pool, from its `BuyEvent` or `SellEvent`.
+/// Base is the token, quote is usually wrapped SOL.
+///
+/// The event names the pool but not the token; callers resolve the mint
+/// from the pool's token balances.
+#[derive(Debug, Clone)]
+pub struct AmmSwapEvent {
+    /// Unix timestamp of the block.
+    pub timestamp: i64,
+    pub is_buy: bool,
+    pub base_amount: u64,
+    /// Quote paid or received by the user, after fees.
+    pub quote_amount: u64,
+    /// Pool reserves before the swap.
+    pub pool_base_reserves: u64,
+    pub pool_quote_reserves: u64,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+}
+
+#[derive(Debug, Clone)]
+pub enum PumpEvent {
+    Create(CreateEvent),
+    Trade(TradeEvent),
+    AmmSwap(AmmSwapEvent),
+}
+
+impl PumpEvent {
+    /// Decodes an Anchor event payload. Returns `None` for events we don't
+    /// track or payloads that don't parse. Fields appended by newer program
+    /// versions are ignored.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut reader = BorshReader::new(data);
+        let discriminator: [u8; 8] = reader.bytes(8)?.try_into().ok()?;
+        match discriminator {
+            CREATE_EVENT_DISCRIMINATOR => Some(PumpEvent::Create(CreateEvent {
+                name: reader.string()?,
+                symbol: reader.string()?,
+                uri: reader.string()?,
+                mint: reader.pubkey()?,
+                bonding_curve: reader.pubkey()?,
+                user: reader.pubkey()?,
+            })),
+            TRADE_EVENT_DISCRIMINATOR => Some(PumpEvent::Trade(TradeEvent {
+                mint: reader.pubkey()?,
+                sol_amount: reader.u64()?,
+                token_amount: reader.u64()?,
+                is_buy: reader.bool()?,
+                user: reader.pubkey()?,
+                timestamp: reader.i64()?,
+                virtual_sol_reserves: reader.u64()?,
+                virtual_token_reserves: reader.u64()?,
+            })),
+            AMM_BUY_EVENT_DISCRIMINATOR | AMM_SELL_EVENT_DISCRIMINATOR => {
+                let is_buy = discriminator == AMM_BUY_EVENT_DISCRIMINATOR;
+                let timestamp = reader.i64()?;
+                // base_amount_out / base_amount_in, then the user's slippage limit.
+                let base_amount = reader.u64()?;
+                reader.u64()?;
+                // The user's own reserves.
+                reader.bytes(16)?;
+                let pool_base_reserves = reader.u64()?;
+                let pool_quote_reserves = reader.u64()?;
+                // quote_amount_in / quote_amount_out, the fees, and the
+                // fee-adjusted amount.
+                reader.bytes(48)?;
+                let quote_amount = reader.u64()?;
+                Some(PumpEvent::AmmSwap(AmmSwapEvent {
+                    timestamp,
+                    is_buy,
+                    base_amount,
+                    quote_amount,
+                    pool_base_reserves,
+                    pool_quote_reserves,
+                    pool: reader.pubkey()?,
+                    user: reader.pubkey()?,
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Every pump.fun event in a transaction's log messages, in emission order.
+///
+/// # Arguments
+/// * `logs` - The transaction's log messages
+pub fn parse_events(logs: &[String]) -> Vec<PumpEvent> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|payload| STANDARD.decode(payload.trim()).ok())
+        .filter_map(|data| PumpEvent::decode(&data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh_string(data: &mut Vec<u8>, value: &str) {
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(value.as_bytes());
+    }
+
+    #[test]
+    fn parses_curve_accounts_skipping_the_supply() {
+        let mut data = BONDING_CURVE_DISCRIMINATOR.to_vec();
+        for value in [INITIAL_VIRTUAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, 500, 7, TOKEN_TOTAL_SUPPLY] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(1);
+        // Fields newer curves append.
+        data.extend_from_slice(&[0; 32]);
+
+        let curve = BondingCurve::parse(&data).unwrap();
+        assert_eq!(curve.virtual_token_reserves, INITIAL_VIRTUAL_TOKEN_RESERVES);
+        assert_eq!(curve.virtual_sol_reserves, INITIAL_VIRTUAL_SOL_RESERVES);
+        assert_eq!((curve.real_token_reserves, curve.real_sol_reserves), (500, 7));
+        assert!(curve.complete);
+
+        assert!(BondingCurve::parse(&data[..data.len() - 33]).is_none());
+        data[0] ^= 1;
+        assert!(BondingCurve::parse(&data).is_none());
+    }
+
+    #[test]
+    fn quotes_follow_the_constant_product() {
+        let fresh = BondingCurve::from_virtual_reserves(INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES);
+        // 1 SOL less the 1% fee is 990099009 lamports into the curve.
+        let tokens = fresh.buy_quote(LAMPORTS_PER_SOL, 100);
+        assert_eq!(tokens, 34_281_150_129_545);
+        assert_eq!(fresh.buy_quote(LAMPORTS_PER_SOL, 0), 34_612_903_225_806);
+
+        // Selling the tokens straight back returns what went in, less
+        // rounding and the fee on the way out.
+        let after = BondingCurve::from_virtual_reserves(
+            INITIAL_VIRTUAL_SOL_RESERVES + 990_099_009,
+            INITIAL_VIRTUAL_TOKEN_RESERVES - tokens,
+        );
+        assert_eq!(after.sell_quote(tokens, 0), 990_099_008);
+        assert_eq!(after.sell_quote(tokens, 100), 980_198_018);
+    }
+
+    #[test]
+    fn buys_are_capped_at_the_tokens_left() {
+        let nearly_done = BondingCurve::from_virtual_reserves(
+            INITIAL_VIRTUAL_SOL_RESERVES + 80 * LAMPORTS_PER_SOL,
+            INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES + 1_000,
+        );
+        assert_eq!(nearly_done.buy_quote(100 * LAMPORTS_PER_SOL, 100), 1_000);
+        let complete = BondingCurve { complete: true, ..nearly_done };
+        assert_eq!((complete.buy_quote(LAMPORTS_PER_SOL, 100), complete.sell_quote(1_000, 100)), (0, 0));
+    }
+
+    #[test]
+    fn curves_from_virtual_reserves_start_empty() {
+        let fresh = BondingCurve::from_virtual_reserves(INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES);
+        assert_eq!(fresh.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES);
+        assert_eq!(fresh.real_sol_reserves, 0);
+        assert_eq!(fresh.progress_percent(), 0.0);
+        assert!(!fresh.complete);
+
+        let sold_out = BondingCurve::from_virtual_reserves(
+            INITIAL_VIRTUAL_SOL_RESERVES + 85 * LAMPORTS_PER_SOL,
+            INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES,
+        );
+        assert_eq!(sold_out.progress_percent(), 100.0);
+        assert_eq!(sold_out.liquidity_sol(), 85.0);
+        assert!(sold_out.complete);
+    }
+
+    #[test]
+    fn decodes_create_and_trade_events_from_logs() {
+        let (mint, curve, user) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut create = CREATE_EVENT_DISCRIMINATOR.to_vec();
+        borsh_string(&mut create, "Token");
+        borsh_string(&mut create, "TKN");
+        borsh_string(&mut create, "https://example.com/token.json");
+        for key in [mint, curve, user] {
+            create.extend_from_slice(key.as_ref());
+        }
+
+        let mut trade = TRADE_EVENT_DISCRIMINATOR.to_vec();
+        trade.extend_from_slice(mint.as_ref());
+        trade.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+        trade.extend_from_slice(&35_000_000_000u64.to_le_bytes());
+        trade.push(1);
+        trade.extend_from_slice(user.as_ref());
+        trade.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        trade.extend_from_slice(&31_000_000_000u64.to_le_bytes());
+        trade.extend_from_slice(&1_038_000_000_000_000u64.to_le_bytes());
+
+        let logs = vec![
+            "Program log: Instruction: Create".to_string(),
+            format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode(&create)),
+            format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode(&trade)),
+            format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode([0u8; 16])),
+        ];
+        let events = parse_events(&logs);
+        assert_eq!(events.len(), 2);
+        let PumpEvent::Create(create) = &events[0] else {
+            panic!("expected a create event, got {:?}", events[0]);
+        };
+        assert_eq!((create.name.as_str(), create.symbol.as_str()), ("Token", "TKN"));
+        assert_eq!(create.uri, "https://example.com/token.json");
+        assert_eq!((create.mint, create.bonding_curve, create.user), (mint, curve, user));
+        let PumpEvent::Trade(trade) = &events[1] else {
+            panic!("expected a trade event, got {:?}", events[1]);
+        };
+        assert_eq!((trade.mint, trade.user), (mint, user));
+        assert_eq!((trade.sol_amount, trade.token_amount), (1_000_000_000, 35_000_000_000));
+        assert!(trade.is_buy);
+        assert_eq!(trade.timestamp, 1_700_000_000);
+        assert_eq!(trade.virtual_token_reserves, 1_038_000_000_000_000);
+    }
+}
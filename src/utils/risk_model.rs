@@ -0,0 +1,409 @@
+use {
+    crate::{models::token::ModelTrainingData, utils::hash::fnv1a},
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    std::{env, fs, path::Path},
+};
+
+#[derive(Debug, Clone)]
+pub struct TrainingConfig {
+    /// Full-batch gradient descent passes.
+    pub epochs: usize,
+    pub learning_rate: f32,
+    /// L2 penalty on the weights.
+    pub l2: f32,
+    /// Percent of tokens held out for evaluation.
+    pub test_percent: u64,
+}
+
+impl TrainingConfig {
+    /// Reads `MODEL_EPOCHS` (default 500), `MODEL_LEARNING_RATE` (default
+    /// 0.1), `MODEL_L2` (default 0.001) and `MODEL_TEST_PERCENT` (default 20).
+    pub fn from_env() -> Self {
+        TrainingConfig {
+            epochs: env::var("MODEL_EPOCHS").ok().and_then(|v| v.parse().ok()).unwrap_or(500),
+            learning_rate: env::var("MODEL_LEARNING_RATE").ok().and_then(|v| v.parse().ok()).unwrap_or(0.1),
+            l2: env::var("MODEL_L2").ok().and_then(|v| v.parse().ok()).unwrap_or(0.001),
+            test_percent: env::var("MODEL_TEST_PERCENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20)
+                .min(90),
+        }
+    }
+}
+
+/// How a model did on the held-out split.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub samples: usize,
+    pub positives: usize,
+    pub precision: f32,
+    pub recall: f32,
+    pub auc: f32,
+}
+
+/// Logistic regression over the `analysis_manager::feature_vector` layout.
+///
+/// Scores are the probability that buying the token pays off.
+/// `FeatureExtractor` stores one minus the score as
+/// `TokenAnalysis::risk_score`, which is not itself a feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskModel {
+    pub schema_version: i32,
+    pub feature_names: Vec<String>,
+    /// Standardization applied before the weights.
+    pub means: Vec<f32>,
+    pub stds: Vec<f32>,
+    pub weights: Vec<f32>,
+    pub bias: f32,
+    /// Scores at or above this count as a buy.
+    pub threshold: f32,
+    pub trained_at: DateTime<Utc>,
+    pub evaluation: Option<Evaluation>,
+}
+
+impl RiskModel {
+    /// Loads a model saved by `save`, refusing one trained on a different
+    /// feature layout.
+    ///
+    /// # Arguments
+    /// * `path` - The model file
+    /// * `schema_version` - The feature layout the caller will score with
+    /// * `feature_names` - The names of that layout's columns
+    pub fn load(path: &Path, schema_version: i32, feature_names: &[String]) -> Result<Self> {
+        let model: RiskModel = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if model.schema_version != schema_version || model.feature_names != feature_names {
+            return Err(anyhow!(
+                "Model {} was trained on feature schema {}, expected {}",
+                path.display(),
+                model.schema_version,
+                schema_version
+            ));
+        }
+        let width = feature_names.len();
+        if model.weights.len() != width || model.means.len() != width || model.stds.len() != width {
+            return Err(anyhow!("Model {} has the wrong number of weights", path.display()));
+        }
+        Ok(model)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Probability that the token is a good buy, 0-1.
+    pub fn score(&self, features: &[f32]) -> f32 {
+        let z = self.bias
+            + features
+                .iter()
+                .zip(&self.weights)
+                .zip(self.means.iter().zip(&self.stds))
+                .map(|((x, w), (mean, std))| w * (x - mean) / std)
+                .sum::<f32>();
+        sigmoid(z)
+    }
+
+    pub fn is_buy(&self, score: f32) -> bool {
+        score >= self.threshold
+    }
+
+    /// Trains on `rows` and evaluates on a held-out split.
+    ///
+    /// Rows are split by token, not by row, so snapshots of one token never
+    /// land on both sides. The threshold is the one that maximizes F1 on the
+    /// training split.
+    ///
+    /// # Arguments
+    /// * `rows` - Labelled snapshots, all with `schema_version`
+    /// * `schema_version` - The feature layout of `rows`
+    /// * `feature_names` - The names of that layout's columns
+    /// * `config` - Optimizer settings and the held-out share
+    pub fn train(
+        rows: &[ModelTrainingData],
+        schema_version: i32,
+        feature_names: &[String],
+        config: &TrainingConfig,
+    ) -> Result<Self> {
+        let width = feature_names.len();
+        if let Some(row) = rows.iter().find(|row| row.features.len() != width) {
+            return Err(anyhow!("Training row for {} has {} features, expected {width}", row.mint_address, row.features.len()));
+        }
+        let (test, train): (Vec<&ModelTrainingData>, Vec<&ModelTrainingData>) =
+            rows.iter().partition(|row| fnv1a(row.mint_address.as_bytes()) % 100 < config.test_percent);
+        let positives = train.iter().filter(|row| row.outcome >= 0.5).count();
+        if positives == 0 || positives == train.len() {
+            return Err(anyhow!("Training split needs both positive and negative rows"));
+        }
+
+        // Accumulate in f64 so constant columns come out with exactly zero
+        // variance.
+        let n = train.len() as f64;
+        let mut sums = vec![0.0f64; width];
+        for row in &train {
+            for (sum, x) in sums.iter_mut().zip(&row.features) {
+                *sum += *x as f64;
+            }
+        }
+        let means: Vec<f32> = sums.iter().map(|sum| (sum / n) as f32).collect();
+        let mut variances = vec![0.0f64; width];
+        for row in &train {
+            for ((variance, mean), x) in variances.iter_mut().zip(&means).zip(&row.features) {
+                *variance += ((x - mean) as f64).powi(2) / n;
+            }
+        }
+        // Constant columns get a unit scale so they contribute nothing.
+        let stds: Vec<f32> = variances
+            .into_iter()
+            .map(|variance| if variance > 1e-12 { variance.sqrt() as f32 } else { 1.0 })
+            .collect();
+
+        let inputs: Vec<Vec<f32>> = train
+            .iter()
+            .map(|row| row.features.iter().zip(&means).zip(&stds).map(|((x, m), s)| (x - m) / s).collect())
+            .collect();
+        // Weight classes so the rarer one counts as much as the common one.
+        let positive_weight = (train.len() - positives) as f32 / positives as f32;
+
+        let mut weights = vec![0.0f32; width];
+        let mut bias = 0.0f32;
+        let total_weight = train
+            .iter()
+            .map(|row| if row.outcome >= 0.5 { positive_weight } else { 1.0 })
+            .sum::<f32>();
+        for _ in 0..config.epochs {
+            let mut weight_grad = vec![0.0f32; width];
+            let mut bias_grad = 0.0f32;
+            for (x, row) in inputs.iter().zip(&train) {
+                let sample_weight = if row.outcome >= 0.5 { positive_weight } else { 1.0 };
+                let z = bias + x.iter().zip(&weights).map(|(x, w)| x * w).sum::<f32>();
+                let error = (sigmoid(z) - row.outcome) * sample_weight;
+                for (grad, x) in weight_grad.iter_mut().zip(x) {
+                    *grad += error * x;
+                }
+                bias_grad += error;
+            }
+            for (w, grad) in weights.iter_mut().zip(&weight_grad) {
+                *w -= config.learning_rate * (grad / total_weight + config.l2 * *w);
+            }
+            bias -= config.learning_rate * bias_grad / total_weight;
+        }
+
+        let mut model = RiskModel {
+            schema_version,
+            feature_names: feature_names.to_vec(),
+            means,
+            stds,
+            weights,
+            bias,
+            threshold: 0.5,
+            trained_at: Utc::now(),
+            evaluation: None,
+        };
+        let train_scores: Vec<(f32, bool)> = train
+            .iter()
+            .map(|row| (model.score(&row.features), row.outcome >= 0.5))
+            .collect();
+        model.threshold = best_f1_threshold(&train_scores);
+        if !test.is_empty() {
+            let test_scores: Vec<(f32, bool)> = test
+                .iter()
+                .map(|row| (model.score(&row.features), row.outcome >= 0.5))
+                .collect();
+            model.evaluation = Some(evaluate(&test_scores, model.threshold));
+        }
+        Ok(model)
+    }
+}
+
+/// Precision and recall at `threshold`, and the threshold-free AUC.
+pub fn evaluate(scores: &[(f32, bool)], threshold: f32) -> Evaluation {
+    let positives = scores.iter().filter(|(_, positive)| *positive).count();
+    let true_positives = scores.iter().filter(|(score, positive)| *positive && *score >= threshold).count();
+    let predicted = scores.iter().filter(|(score, _)| *score >= threshold).count();
+    Evaluation {
+        samples: scores.len(),
+        positives,
+        precision: if predicted > 0 { true_positives as f32 / predicted as f32 } else { 0.0 },
+        recall: if positives > 0 { true_positives as f32 / positives as f32 } else { 0.0 },
+        auc: auc(scores),
+    }
+}
+
+/// Area under the ROC curve: the chance a random positive scores above a
+/// random negative, counting ties as half.
+fn auc(scores: &[(f32, bool)]) -> f32 {
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let positives = sorted.iter().filter(|(_, positive)| *positive).count() as f64;
+    let negatives = sorted.len() as f64 - positives;
+    if positives == 0.0 || negatives == 0.0 {
+        return 0.5;
+    }
+    // Sum of positive ranks, with tied scores sharing their average rank.
+    let mut rank_sum = 0.0;
+    let mut i = 0;
+    while i < sorted.len() {
+        let mut j = i;
+        while j < sorted.len() && sorted[j].0 == sorted[i].0 {
+            j += 1;
+        }
+        let average_rank = (i + 1 + j) as f64 / 2.0;
+        rank_sum += average_rank * sorted[i..j].iter().filter(|(_, positive)| *positive).count() as f64;
+        i = j;
+    }
+    ((rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives)) as f32
+}
+
+/// Sweeps thresholds from the highest score down, keeping the best F1.
+fn best_f1_threshold(scores: &[(f32, bool)]) -> f32 {
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let positives = sorted.iter().filter(|(_, positive)| *positive).count() as f32;
+    let (mut best_f1, mut best_threshold) = (0.0f32, 0.5f32);
+    let mut true_positives = 0.0f32;
+    for (i, (score, positive)) in sorted.iter().enumerate() {
+        if *positive {
+            true_positives += 1.0;
+        }
+        // Only evaluate once every row with this score is predicted positive.
+        if sorted.get(i + 1).is_some_and(|next| next.0 == *score) {
+            continue;
+        }
+        let precision = true_positives / (i + 1) as f32;
+        let recall = true_positives / positives;
+        if precision + recall > 0.0 {
+            let f1 = 2.0 * precision * recall / (precision + recall);
+            if f1 > best_f1 {
+                (best_f1, best_threshold) = (f1, *score);
+            }
+        }
+    }
+    best_threshold
+}
+
+fn sigmoid(z: f32) -> f32 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        ["signal", "noise", "constant"].map(String::from).to_vec()
+    }
+
+    fn row(i: usize, features: Vec<f32>, positive: bool) -> ModelTrainingData {
+        ModelTrainingData {
+            id: i as i64,
+            mint_address: format!("mint{i}"),
+            age_secs: 10,
+            schema_version: 1,
+            features,
+            outcome: if positive { 1.0 } else { 0.0 },
+            outcome_label: String::new(),
+            peak_multiple: 1.0,
+            max_drawdown: 0.0,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Positive exactly when `signal` is above zero.
+    fn separable_rows() -> Vec<ModelTrainingData> {
+        (0..400)
+            .map(|i| {
+                let signal = (i % 20) as f32 - 9.5;
+                let noise = ((i * 7) % 13) as f32;
+                row(i, vec![signal, noise, 3.0], signal > 0.0)
+            })
+            .collect()
+    }
+
+    fn config() -> TrainingConfig {
+        TrainingConfig {
+            epochs: 300,
+            learning_rate: 0.5,
+            l2: 0.001,
+            test_percent: 25,
+        }
+    }
+
+    #[test]
+    fn auc_counts_ranked_pairs() {
+        let perfect = [(0.1, false), (0.3, false), (0.6, true), (0.9, true)];
+        assert_eq!(auc(&perfect), 1.0);
+        let reversed = [(0.1, true), (0.3, true), (0.6, false), (0.9, false)];
+        assert_eq!(auc(&reversed), 0.0);
+        let mixed = [(0.2, true), (0.4, false), (0.6, true), (0.8, false)];
+        assert_eq!(auc(&mixed), 0.25);
+        let tied = [(0.5, true), (0.5, false), (0.5, true), (0.5, false)];
+        assert_eq!(auc(&tied), 0.5);
+        assert_eq!(auc(&[(0.5, true)]), 0.5);
+    }
+
+    #[test]
+    fn threshold_maximizes_f1() {
+        let scores = [(0.9, true), (0.8, true), (0.7, false), (0.6, true), (0.2, false)];
+        assert_eq!(best_f1_threshold(&scores), 0.6);
+
+        let evaluation = evaluate(&scores, 0.75);
+        assert_eq!((evaluation.samples, evaluation.positives), (5, 3));
+        assert_eq!(evaluation.precision, 1.0);
+        assert!((evaluation.recall - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn training_learns_a_separable_feature() {
+        let rows = separable_rows();
+        let model = RiskModel::train(&rows, 1, &names(), &config()).unwrap();
+
+        let evaluation = model.evaluation.clone().unwrap();
+        assert!(evaluation.samples > 0 && evaluation.samples < rows.len());
+        assert!(evaluation.auc > 0.99, "{evaluation:?}");
+        assert!(evaluation.precision > 0.9 && evaluation.recall > 0.9, "{evaluation:?}");
+        assert!(model.weights[0] > 0.0);
+        // The constant column is scaled by one and learns nothing useful.
+        assert_eq!(model.stds[2], 1.0);
+        assert!(model.is_buy(model.score(&[9.5, 6.0, 3.0])));
+        assert!(!model.is_buy(model.score(&[-9.5, 6.0, 3.0])));
+    }
+
+    #[test]
+    fn snapshots_of_a_token_stay_on_one_side_of_the_split() {
+        let mut rows = separable_rows();
+        // A second snapshot of every token.
+        let copies: Vec<ModelTrainingData> = rows
+            .iter()
+            .map(|r| ModelTrainingData { age_secs: 60, ..r.clone() })
+            .collect();
+        rows.extend(copies);
+        let model = RiskModel::train(&rows, 1, &names(), &config()).unwrap();
+        // Held-out rows come in whole tokens, so the count stays even.
+        assert_eq!(model.evaluation.unwrap().samples % 2, 0);
+    }
+
+    #[test]
+    fn training_needs_both_classes_and_the_full_layout() {
+        let negatives: Vec<_> = (0..10).map(|i| row(i, vec![0.0, 0.0, 0.0], false)).collect();
+        assert!(RiskModel::train(&negatives, 1, &names(), &config()).is_err());
+
+        let short = vec![row(0, vec![1.0], true), row(1, vec![0.0], false)];
+        assert!(RiskModel::train(&short, 1, &names(), &config()).is_err());
+    }
+
+    #[test]
+    fn models_only_load_for_their_schema() {
+        let model = RiskModel::train(&separable_rows(), 1, &names(), &config()).unwrap();
+        let path = env::temp_dir().join(format!("risk_model_{}.json", std::process::id()));
+        model.save(&path).unwrap();
+
+        let loaded = RiskModel::load(&path, 1, &names()).unwrap();
+        assert_eq!(loaded.score(&[1.0, 2.0, 3.0]), model.score(&[1.0, 2.0, 3.0]));
+        assert!(RiskModel::load(&path, 2, &names()).is_err());
+        assert!(RiskModel::load(&path, 1, &names()[..2]).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
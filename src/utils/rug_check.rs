@@ -1,25 +1,13 @@
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{commitment_config::CommitmentConfig, program_pack::Pack, pubkey::Pubkey};
-use spl_token::state::Mint;
-use reqwest::blocking::Client;
+use crate::providers::{Finding, RiskAggregator, Severity};
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::task;
-use std::{collections::HashMap, env, error::Error, str::FromStr};
+use std::{collections::HashMap, error::Error, str::FromStr};
 
-// API Response Structures
-#[derive(Debug, Deserialize)]
-struct MoralisResponse {
-    possible_spam: bool,
-    holder_distribution: HolderDistribution,
-}
-
-#[derive(Debug, Deserialize)]
-struct HolderDistribution {
-    top10: f64,
-    top25: f64,
-    total: u64,
-}
+// API Response Structures. Only the fields the providers read are
+// declared; serde skips the rest, so changes to unused parts of a response
+// don't break parsing.
 
 /// Updated response for parsing GoPlus Security API responses.
 ///
@@ -27,45 +15,18 @@ struct HolderDistribution {
 /// a `result` field (a map from token mint addresses to their details).
 #[derive(Debug, Deserialize)]
 pub struct GoPlusResponse {
-    pub code: u32,
     pub message: String,
     pub result: HashMap<String, GoPlusTokenSecurity>,
 }
 
-/// Detailed security information for a single token, as returned by GoPlus.
-/// Fields not guaranteed to be present in every response (such as `creators` or `dex`)
-/// are marked as `Option` with a default.
+/// Security information for a single token, as returned by GoPlus.
+/// `lp_holders` is only present for tokens with a pool.
 #[derive(Debug, Deserialize)]
 pub struct GoPlusTokenSecurity {
-    pub balance_mutable_authority: AuthorityStatus,
-    pub closable: AuthorityStatus,
-    #[serde(rename = "default_account_state")]
-    pub default_account_state: String,
-    pub default_account_state_upgradable: AuthorityStatus,
     pub freezable: AuthorityStatus,
     pub mintable: AuthorityStatus,
-    #[serde(rename = "non_transferable")]
-    pub non_transferable: String,
-    #[serde(rename = "total_supply")]
-    pub total_supply: String,
-    pub transfer_fee: Value,
-    pub transfer_fee_upgradable: AuthorityStatus,
-    pub transfer_hook: Vec<Value>,
-    pub transfer_hook_upgradable: AuthorityStatus,
-    pub trusted_token: i32,
-    // Additional fields available in some responses
-    #[serde(default)]
-    pub creators: Option<Vec<Value>>,
-    #[serde(default)]
-    pub dex: Option<Vec<DexInfo>>,
-    #[serde(default)]
-    pub holders: Option<Vec<Holder>>,
     #[serde(rename = "lp_holders", default)]
     pub lp_holders: Option<Vec<Holder>>,
-    #[serde(default)]
-    pub metadata: Option<Metadata>,
-    #[serde(rename = "metadata_mutable", default)]
-    pub metadata_mutable: Option<MetadataMutable>,
 }
 
 /// Represents the authority and status for a specific token capability.
@@ -75,73 +36,90 @@ pub struct AuthorityStatus {
     pub status: String,
 }
 
-/// Details for a decentralized exchange (DEX) listed in the GoPlus API response.
-#[derive(Debug, Deserialize)]
-pub struct DexInfo {
-    pub day: PriceData,
-    pub dex_name: String,
-    pub fee_rate: String,
-    pub id: String,
-    pub lp_amount: Option<String>,
-    pub month: PriceData,
-    pub open_time: String,
-    pub price: String,
-    pub tvl: String,
-    #[serde(rename = "type")]
-    pub type_field: String,
-    pub week: PriceData,
-}
-
-/// Price and volume data over a specific time period.
-#[derive(Debug, Deserialize)]
-pub struct PriceData {
-    pub price_max: String,
-    pub price_min: String,
-    pub volume: String,
-}
-
 /// Information about a token holder.
 #[derive(Debug, Deserialize)]
 pub struct Holder {
-    pub account: String,
-    pub balance: String,
     pub is_locked: i32,
-    pub locked_detail: Vec<Value>,
-    pub percent: String,
-    pub tag: String,
-    pub token_account: String,
 }
 
-/// Metadata details (if available) for a token.
-#[derive(Debug, Deserialize)]
-pub struct Metadata {
-    pub description: String,
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-}
-
-/// Metadata mutability details (if available) for a token.
-#[derive(Debug, Deserialize)]
-pub struct MetadataMutable {
-    pub metadata_upgrade_authority: Vec<String>,
-    pub status: String,
-}
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum RugStatus {
     Rug,
     NotRug,
     InsufficientData,
 }
 
+impl RugStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RugStatus::Rug => "rug",
+            RugStatus::NotRug => "not_rug",
+            RugStatus::InsufficientData => "insufficient_data",
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct RugCheckResult {
+    pub mint_address: String,
+    pub checked_at: DateTime<Utc>,
     pub token_status: RugStatus,
-    pub risk_factors: Vec<String>,
+    pub risk_factors: Vec<Finding>,
     pub confidence: f64,
-    pub metadata: TokenMetadata,
+    /// `None` when the on-chain check failed.
+    pub metadata: Option<TokenMetadata>,
     pub liquidity: LiquidityAnalysis,
+    /// Sources that could not be queried for this check.
+    pub failures: Vec<SourceFailure>,
+    /// What every provider returned, kept so verdicts can be audited and re-scored.
+    pub responses: Vec<ProviderResponse>,
+}
+
+/// A single provider call made during a rug check.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderResponse {
+    pub source: RiskSource,
+    pub http_status: Option<u16>,
+    pub latency_ms: u64,
+    pub body: Option<Value>,
+    pub error: Option<String>,
+}
+
+/// Where a risk factor or failure came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum RiskSource {
+    OnChain,
+    Shyft,
+    RugCheck,
+    GoPlus,
+    SolSniffer,
+    /// The token creator's track record.
+    Creator,
+    /// Coordinated buying in the first slots after launch.
+    Bundle,
+    /// Volume manufactured by wash trading.
+    Wash,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceFailure {
+    pub source: RiskSource,
+    pub error: String,
+}
+
+impl RiskSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RiskSource::OnChain => "onchain",
+            RiskSource::Shyft => "shyft",
+            RiskSource::RugCheck => "rugcheck",
+            RiskSource::GoPlus => "goplus",
+            RiskSource::SolSniffer => "solsniffer",
+            RiskSource::Creator => "creator",
+            RiskSource::Bundle => "bundle",
+            RiskSource::Wash => "wash",
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -151,9 +129,96 @@ pub struct TokenMetadata {
     pub supply: u64,
     pub decimals: u8,
     pub is_initialized: bool,
+    /// `None` when the token has no Metaplex metadata account.
+    pub metadata_mutable: Option<bool>,
+    pub update_authority: Option<String>,
+}
+
+/// A single reason a token is considered risky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum RiskFactor {
+    MintNotInitialized,
+    MintAuthorityEnabled,
+    FreezeAuthorityEnabled,
+    MutableMetadata,
+    MissingMetadata,
+    NoLiquidityPool,
+    LiquidityNotLocked,
+    HighRugCheckScore,
+    RugCheckDanger,
+    NoGoPlusData,
+    LowSolSnifferScore,
+    ConcentratedHolders,
+    CreatorRugHistory,
+    SerialLauncher,
+    CreatorSellsEarly,
+    BundledLaunch,
+    SniperCluster,
+    WashTrading,
+    ConcentratedVolume,
+}
+
+impl RiskFactor {
+    pub fn description(&self) -> &'static str {
+        match self {
+            RiskFactor::MintNotInitialized => "Mint account is not initialized",
+            RiskFactor::MintAuthorityEnabled => "Mutable mint authority",
+            RiskFactor::FreezeAuthorityEnabled => "Mutable freeze authority",
+            RiskFactor::MutableMetadata => "Mutable token metadata",
+            RiskFactor::MissingMetadata => "No Metaplex metadata account",
+            RiskFactor::NoLiquidityPool => "No liquidity pool found",
+            RiskFactor::LiquidityNotLocked => "Unlocked liquidity pool",
+            RiskFactor::HighRugCheckScore => "High rugcheck.xyz risk score",
+            RiskFactor::RugCheckDanger => "rugcheck.xyz reported a danger-level risk",
+            RiskFactor::NoGoPlusData => "No security information found via GoPlus",
+            RiskFactor::LowSolSnifferScore => "Low SolSniffer safety score",
+            RiskFactor::ConcentratedHolders => "Top 10 holders own a large share of supply",
+            RiskFactor::CreatorRugHistory => "Most of the creator's earlier tokens rugged",
+            RiskFactor::SerialLauncher => "Creator launched many tokens and none graduated",
+            RiskFactor::CreatorSellsEarly => "Creator usually sells within minutes of launch",
+            RiskFactor::BundledLaunch => "Wallets tied to the creator bought a large share of supply at launch",
+            RiskFactor::SniperCluster => "Coordinated early buyers hold a large share of supply",
+            RiskFactor::WashTrading => "Most of the volume is wallets cycling buys and sells",
+            RiskFactor::ConcentratedVolume => "A handful of wallets make most of the volume",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self.weight() {
+            w if w >= 50.0 => Severity::Critical,
+            w if w >= 25.0 => Severity::High,
+            w if w >= 15.0 => Severity::Medium,
+            _ => Severity::Low,
+        }
+    }
+
+    /// Contribution of this factor to the overall rug confidence (0-100).
+    pub fn weight(&self) -> f64 {
+        match self {
+            RiskFactor::MintNotInitialized => 50.0,
+            RiskFactor::MintAuthorityEnabled => 25.0,
+            RiskFactor::FreezeAuthorityEnabled => 15.0,
+            RiskFactor::MutableMetadata => 10.0,
+            RiskFactor::MissingMetadata => 10.0,
+            RiskFactor::NoLiquidityPool => 50.0,
+            RiskFactor::LiquidityNotLocked => 35.0,
+            RiskFactor::HighRugCheckScore => 50.0,
+            RiskFactor::RugCheckDanger => 20.0,
+            RiskFactor::NoGoPlusData => 10.0,
+            RiskFactor::LowSolSnifferScore => 20.0,
+            RiskFactor::ConcentratedHolders => 20.0,
+            RiskFactor::CreatorRugHistory => 35.0,
+            RiskFactor::SerialLauncher => 15.0,
+            RiskFactor::CreatorSellsEarly => 15.0,
+            RiskFactor::BundledLaunch => 30.0,
+            RiskFactor::SniperCluster => 20.0,
+            RiskFactor::WashTrading => 20.0,
+            RiskFactor::ConcentratedVolume => 10.0,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct LiquidityAnalysis {
     pub pool_locked: bool,
     pub liquidity_amount: f64,
@@ -163,307 +228,152 @@ pub struct LiquidityAnalysis {
 
 #[derive(Debug, Deserialize)]
 pub struct ShyftLiquidityResponse {
-    pub success: Option<bool>,
-    pub message: Option<String>,
     pub result: Option<LiquidityResult>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct LiquidityResult {
-    pub address: Option<String>,
-    pub dex: Option<String>,
-    #[serde(rename = "programId")]
-    pub program_id: Option<String>,
     pub liquidity: Option<Liquidity>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Liquidity {
-    pub tokenA: Option<TokenLiquidity>,
-    pub tokenB: Option<TokenLiquidity>,
+    #[serde(rename = "tokenA")]
+    pub token_a: Option<TokenLiquidity>,
+    #[serde(rename = "tokenB")]
+    pub token_b: Option<TokenLiquidity>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TokenLiquidity {
     pub address: Option<String>,
-    pub name: Option<String>,
-    pub symbol: Option<String>,
     pub decimals: Option<u8>,
-    pub imageUri: Option<String>,
     pub amount: Option<u64>,
 }
 
-/// Check liquidity pool status using Raydium protocol analysis
-pub async fn check_liquidity_pools(mint: &str) -> Result<ShyftLiquidityResponse, Box<dyn Error + Send + Sync>> {
-    // Implementation would use get_account_data and parse Raydium pool state
-    // Placeholder implementation:
-    let mint_owned = mint.to_owned();
-    let api_key = env::var("SHYFT_API_KEY").expect("Missing SHYFT API Key for LP analysis");
-    let result = task::spawn_blocking(move || {
-        let client = Client::new();
-        let response : ShyftLiquidityResponse = client
-        .get("https://defi.shyft.to/v0/pools/get_liquidity_details")
-        .query(&[("address", &mint_owned)])
-        .header("x-api-key", api_key)
-        .send()?
-        .json()?;
-        Ok::<_, Box<dyn Error + Send + Sync>>(response)
-    }).await??;
-    Ok(result)
-}
-
-// /// Query Moralis API for spam detection
-// async fn check_moralis_api(mint_address: &str) -> Result<MoralisResponse, Box<dyn Error>> {
-//     let client = Client::new();
-//     let response = client
-//         .get("https://deep-index.moralis.io/api/v2/spl/token/metadata")
-//         .query(&[("chain", "sol"), ("addresses", mint_address)])
-//         .header("X-API-Key", "")
-//         .send()?
-//         .json()?;
-    
-//     Ok(response)
-// }
-
-async fn sol_sniffer_call(mint_address: &str) -> Result<SolSnifferResponse, Box<dyn Error + Send + Sync>> {
-    let mint_address_owned = mint_address.to_owned();
-    let api_key = env::var("TOKEN_SNIFFER_KEY").expect("Missing TOKEN_SNIFFER_KEY environment variable");
-    let url = format!("https://solsniffer.com/api/v2/token/{m}", m = mint_address_owned);
-    let result = task::spawn_blocking(move || {
-        let client = Client::new();
-        let response: SolSnifferResponse = client
-        .get(url)
-        .header("X-API-KEY", api_key.to_string())
-        .send()?
-        .json()?;
-        Ok::<_, Box<dyn Error + Send + Sync>>(response)
-    }).await??;
- Ok(result)   
-}
-
-
-
-pub async fn rugcheck_api(mint_address: &str) -> Result<RugCheckApiResponse, Box<dyn Error + Send + Sync>> {
-    let mint_address_owned = mint_address.to_owned();
-    let url = format!("https://api.rugcheck.xyz/v1/tokens/{n}/report/summary", n = mint_address_owned);
-    let result: RugCheckApiResponse = task::spawn_blocking(move || {
-        let client = Client::new();
-        let response = client
-        .get(url)
-        .header("accept", "application/json")
-        .send()?
-        .json()?;
-    Ok::<_, Box<dyn Error + Send + Sync>>(response)
-    }).await??;
-    Ok(result)
-}
-/// Query GoPlus Security API using the async reqwest client.
-pub async fn check_goplus_api(mint_address: &str) -> Result<GoPlusResponse, Box<dyn Error + Send + Sync>> {
-    let mint_address_owned = mint_address.to_owned();
-    let result = task::spawn_blocking(move || {
-        let client = Client::new();
-        let response: GoPlusResponse = client
-            .get("https://api.gopluslabs.io/api/v1/solana/token_security")
-            .query(&[("contract_addresses", &mint_address_owned)])
-            .send()?
-            .json()?;
-        Ok::<_, Box<dyn Error + Send + Sync>>(response)
-    }).await??;
-    Ok(result)
-}
-
-/// Comprehensive rug check for Solana SPL tokens
-pub async fn check_solana_rug(mint_address: &str) -> Result<RugStatus, Box<dyn Error>> {
-    // let client = RpcClient::new_with_commitment(RPC_URL, CommitmentConfig::confirmed());
-    // let mint_pubkey = Pubkey::from_str(mint_address)?;
-    
-    // // 1. On-chain metadata analysis
-    // let mint_data = client.get_account_data(&mint_pubkey)?;
-    // let mint_info = Mint::unpack(&mint_data)?;
-    // let metadata = TokenMetadata {
-    //     mint_authority: mint_info.mint_authority.map(|pk| pk.to_string()).into(),
-    //     freeze_authority: mint_info.freeze_authority.map(|pk| pk.to_string()).into(),
-    //     supply: mint_info.supply,
-    //     decimals: mint_info.decimals,
-    //     is_initialized: mint_info.is_initialized,
-    // };
-    
-
-
-    // let liquidity = check_liquidity_pools(&client, &mint_pubkey).await?;
-
-    // let goplus_result = check_goplus_api(mint_address).await.expect("Error in goplus api request");
-
+/// Confidence at or above which a token is classified as a rug.
+const RUG_CONFIDENCE_THRESHOLD: f64 = 50.0;
 
-
-    // 4. Risk factor aggregation
-    let liqudity_response: ShyftLiquidityResponse = check_liquidity_pools(mint_address).await.expect("msg");
-    println!("Token: {mint_address:?}");
-    println!("Liquidity response: {liqudity_response:?}");
-    if let Some(_) = liqudity_response.result {
-    } else {
-        return  Ok(RugStatus::Rug);
-    }
-    
-    let rugcheck_result = rugcheck_api(mint_address).await.expect("Error in rug check API");
-    if let Some(num) = rugcheck_result.score {
-        if num > 400 {
-            println!("Rug status failed for {:?}", mint_address);
-            return  Ok(RugStatus::Rug);
+/// Comprehensive rug check for Solana SPL tokens.
+///
+/// Every enabled provider is run concurrently. A provider that fails or times
+/// out is recorded in `RugCheckResult::failures` and the verdict is built from
+/// whatever the remaining providers returned.
+///
+/// # Arguments
+/// * `aggregator` - The configured set of risk providers
+/// * `mint_address` - The token mint to check
+pub async fn check_solana_rug(aggregator: &RiskAggregator, mint_address: &str) -> Result<RugCheckResult, Box<dyn Error + Send + Sync>> {
+    let mint_pubkey = Pubkey::from_str(mint_address)?;
+    let outcomes = aggregator.run(&mint_pubkey).await;
+
+    let mut risk_factors: Vec<Finding> = Vec::new();
+    let mut failures: Vec<SourceFailure> = Vec::new();
+    let mut metadata = None;
+    let mut liquidity = LiquidityAnalysis::default();
+    // A signal reported by several providers only counts once, at its highest weight.
+    let mut weights: HashMap<RiskFactor, f64> = HashMap::new();
+
+    let mut responses: Vec<ProviderResponse> = Vec::new();
+
+    for outcome in outcomes {
+        let latency_ms = outcome.latency.as_millis() as u64;
+        match outcome.result {
+            Ok(report) => {
+                responses.push(ProviderResponse {
+                    source: outcome.source,
+                    http_status: report.http_status,
+                    latency_ms,
+                    body: Some(report.raw.clone()),
+                    error: None,
+                });
+                for finding in &report.findings {
+                    let weight = finding.signal.weight() * outcome.weight;
+                    let entry = weights.entry(finding.signal).or_insert(0.0);
+                    *entry = entry.max(weight);
+                }
+                risk_factors.extend(report.findings);
+                liquidity.merge(&report.liquidity);
+                if report.metadata.is_some() {
+                    metadata = report.metadata;
+                }
+            }
+            Err(failure) => {
+                responses.push(ProviderResponse {
+                    source: outcome.source,
+                    http_status: failure.http_status,
+                    latency_ms,
+                    body: failure.body,
+                    error: Some(failure.error.clone()),
+                });
+                failures.push(SourceFailure {
+                    source: outcome.source,
+                    error: failure.error,
+                });
+            }
         }
     }
-    // let solsniffer_response = sol_sniffer_call(mint_address).await.expect("Error calling sol sniffer api");
-
-    // // Mint authority checks
-    // if metadata.mint_authority.is_some() {
-    //     risk_factors.push("Mutable mint authority".into());
-    //     confidence += 25.0;
-    // }
-
-    // // Freeze authority checks
-    // if metadata.freeze_authority.is_some() {
-    //     risk_factors.push("Mutable freeze authority".into());
-    //     confidence += 15.0;
-    // }
-
-    // Moralis API evaluation
-    // if moralis_result.possible_spam {
-    //     risk_factors.push("Moralis spam flag".into());
-    //     confidence += 30.0;
-    // }
-
- 
-    // // GoPlus API evaluation
-    // if goplus_result.result.is_empty() {
-    //     risk_factors.push("No security information found via GoPlus".into());
-    //     confidence += 10.0;
-    // }
-
-    // // Liquidity checks
-    // if !liquidity.pool_locked {
-    //     risk_factors.push("Unlocked liquidity pool".into());
-    //     confidence += 35.0;
-    // }
-
-    // Determine token status based on liquidity and overall confidence.
-    // Here we use a minimal liquidity threshold (e.g. 1.0) to decide if there is enough trading data.
-    // const MINIMUM_LIQUIDITY: f64 = 5.0;
-    // let token_status = if liquidity.liquidity_amount < MINIMUM_LIQUIDITY {
-    //     RugStatus::InsufficientData
-    // } else if confidence >= 50.0 {
-    //     RugStatus::Rug
-    // } else {
-    //     RugStatus::NotRug
-    // };
-
-    Ok(RugStatus::InsufficientData)
+
+    let confidence = weights.values().sum::<f64>().min(100.0);
+    let token_status = if confidence >= RUG_CONFIDENCE_THRESHOLD {
+        RugStatus::Rug
+    } else if failures.len() == aggregator.provider_count() {
+        RugStatus::InsufficientData
+    } else {
+        RugStatus::NotRug
+    };
+
+    Ok(RugCheckResult {
+        mint_address: mint_address.to_string(),
+        checked_at: Utc::now(),
+        token_status,
+        risk_factors,
+        confidence,
+        metadata,
+        liquidity,
+        failures,
+        responses,
+    })
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SolSnifferResponse {
     #[serde(rename = "tokenData")]
     pub token_data: TokenData,
-    #[serde(rename = "tokenInfo")]
-    pub token_info: TokenInfo,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TokenData {
-    #[serde(rename = "indicatorData")]
-    pub indicator_data: IndicatorData,
     #[serde(rename = "tokenOverview")]
     pub token_overview: TokenOverview,
-    pub address: String,
-    #[serde(rename = "deployTime")]
-    pub deploy_time: String,
-    pub externals: String,
-    #[serde(rename = "liquidityList")]
-    pub liquidity_list: Vec<LiquidityEntry>,
-    #[serde(rename = "marketCap")]
-    pub market_cap: f64,
     #[serde(rename = "ownersList")]
     pub owners_list: Vec<Owner>,
     pub score: i32,
-    #[serde(rename = "tokenImg")]
-    pub token_img: String,
-    #[serde(rename = "tokenName")]
-    pub token_name: String,
-    #[serde(rename = "tokenSymbol")]
-    pub token_symbol: String,
     #[serde(rename = "auditRisk")]
     pub audit_risk: AuditRisk,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct IndicatorData {
-    pub high: IndicatorRating,
-    pub moderate: IndicatorRating,
-    pub low: IndicatorRating,
-    pub specific: IndicatorRating,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct IndicatorRating {
-    pub count: i32,
-    pub details: String, // Contains a JSON string with more details.
-}
-
 #[derive(Debug, Deserialize)]
 pub struct TokenOverview {
     pub deployer: String,
-    pub mint: String,
-    pub address: String,
-    #[serde(rename = "type")]
-    pub type_field: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct LiquidityEntry {
-    pub pumpfun: PumpfunLiquidity,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct PumpfunLiquidity {
-    pub address: String,
-    pub amount: f64,
-    #[serde(rename = "lpPair")]
-    pub lp_pair: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Owner {
     pub address: String,
-    pub amount: String,
     pub percentage: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AuditRisk {
-    #[serde(rename = "mintDisabled")]
-    pub mint_disabled: bool,
-    #[serde(rename = "freezeDisabled")]
-    pub freeze_disabled: bool,
     #[serde(rename = "lpBurned")]
     pub lp_burned: bool,
     #[serde(rename = "top10Holders")]
     pub top10_holders: bool,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct TokenInfo {
-    pub price: String,
-    #[serde(rename = "supplyAmount")]
-    pub supply_amount: u64,
-    #[serde(rename = "mktCap")]
-    pub mkt_cap: f64,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct RugCheckApiResponse {
-    #[serde(rename = "tokenProgram")]
-    pub token_program: Option<String>,
-    #[serde(rename = "tokenType")]
-    pub token_type: Option<String>,
     pub risks: Option<Vec<RugRisk>>,
     pub score: Option<u32>,
 }
@@ -471,8 +381,128 @@ pub struct RugCheckApiResponse {
 #[derive(Debug, Deserialize)]
 pub struct RugRisk {
     pub name: String,
-    pub value: String,
-    pub description: String,
-    pub score: u32,
     pub level: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::providers::{ProviderConfig, ProviderError, ProviderReport, RiskProvider},
+        async_trait::async_trait,
+        serde_json::json,
+        std::{sync::Arc, time::Duration},
+    };
+
+    struct StubProvider {
+        source: RiskSource,
+        signals: Vec<RiskFactor>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl RiskProvider for StubProvider {
+        fn source(&self) -> RiskSource {
+            self.source
+        }
+
+        async fn assess(&self, _mint: &Pubkey) -> Result<ProviderReport, ProviderError> {
+            if self.fail {
+                return Err("provider down".into());
+            }
+            Ok(ProviderReport {
+                findings: self
+                    .signals
+                    .iter()
+                    .map(|signal| Finding::new(self.source, *signal, Value::Null, Value::Null))
+                    .collect(),
+                http_status: Some(200),
+                raw: json!({ "ok": true }),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn provider(source: RiskSource, signals: &[RiskFactor], fail: bool) -> Arc<dyn RiskProvider> {
+        Arc::new(StubProvider {
+            source,
+            signals: signals.to_vec(),
+            fail,
+        })
+    }
+
+    fn config(weight: f64) -> ProviderConfig {
+        ProviderConfig {
+            enabled: true,
+            timeout: Duration::from_secs(1),
+            weight,
+        }
+    }
+
+    async fn check(aggregator: RiskAggregator) -> RugCheckResult {
+        check_solana_rug(&aggregator, &Pubkey::new_unique().to_string()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn counts_a_repeated_signal_once_at_its_highest_weight() {
+        let aggregator = RiskAggregator::new()
+            .with_provider(provider(RiskSource::Shyft, &[RiskFactor::MintAuthorityEnabled], false), config(1.0))
+            .with_provider(
+                provider(
+                    RiskSource::GoPlus,
+                    &[RiskFactor::MintAuthorityEnabled, RiskFactor::FreezeAuthorityEnabled],
+                    false,
+                ),
+                config(1.5),
+            );
+        let result = check(aggregator).await;
+        // 25 * 1.5 for the mint authority plus 15 * 1.5 for the freeze authority.
+        assert!((result.confidence - 60.0).abs() < 1e-9);
+        assert_eq!(result.token_status, RugStatus::Rug);
+        assert_eq!(result.risk_factors.len(), 3);
+        assert_eq!(result.responses.len(), 2);
+        assert!(result.failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn caps_confidence_at_100() {
+        let aggregator = RiskAggregator::new().with_provider(
+            provider(
+                RiskSource::OnChain,
+                &[RiskFactor::MintNotInitialized, RiskFactor::NoLiquidityPool, RiskFactor::HighRugCheckScore],
+                false,
+            ),
+            config(1.0),
+        );
+        assert_eq!(check(aggregator).await.confidence, 100.0);
+    }
+
+    #[tokio::test]
+    async fn keeps_failures_and_scores_the_rest() {
+        let aggregator = RiskAggregator::new()
+            .with_provider(provider(RiskSource::Shyft, &[], true), config(1.0))
+            .with_provider(provider(RiskSource::OnChain, &[RiskFactor::MutableMetadata], false), config(1.0));
+        let result = check(aggregator).await;
+        assert_eq!(result.token_status, RugStatus::NotRug);
+        assert_eq!(result.confidence, 10.0);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].source, RiskSource::Shyft);
+        let failed = result.responses.iter().find(|r| r.source == RiskSource::Shyft).unwrap();
+        assert_eq!(failed.error.as_deref(), Some("provider down"));
+    }
+
+    #[tokio::test]
+    async fn reports_insufficient_data_when_every_provider_fails() {
+        let aggregator = RiskAggregator::new()
+            .with_provider(provider(RiskSource::Shyft, &[], true), config(1.0))
+            .with_provider(provider(RiskSource::GoPlus, &[], true), config(1.0))
+            .with_provider(
+                provider(RiskSource::RugCheck, &[RiskFactor::HighRugCheckScore], false),
+                ProviderConfig { enabled: false, ..config(1.0) },
+            );
+        let result = check(aggregator).await;
+        assert_eq!(result.token_status, RugStatus::InsufficientData);
+        assert_eq!(result.failures.len(), 2);
+        assert_eq!(result.confidence, 0.0);
+    }
+}
@@ -1,25 +1,11 @@
 use {
-    anyhow::Result, solana_client::nonblocking::rpc_client::RpcClient, solana_sdk::pubkey::Pubkey, spl_token::instruction::TokenInstruction, std::sync::Arc, yellowstone_grpc_proto::
+    solana_sdk::pubkey::Pubkey, spl_token::instruction::TokenInstruction, yellowstone_grpc_proto::
         prelude::Message
     ,
 };
 
 
 
-pub async fn process_new_token_launch(_token_mint: Pubkey, _rpc_client: Arc<RpcClient>) -> Result<()> {
-    // let token_info = tokio::task::spawn_blocking({
-    //      let rpc_client = rpc_client.clone();
-    //      move || {
-    //          // Call the synchronous version of fetch_token_details (which returns a Result<TokenInfo, anyhow::Error>)
-    //          fetch_token_details(rpc_client, token_mint)
-    //      }
-    //  })
-    //  .await??;
-    //  store_token_info(&db_pool, &token_info).await?;
-    //  println!("Stored details for token: {}", token_info.mint_address);
-     Ok(())
- }
- 
 pub fn matches_pump_fun_pattern(message: &Message) -> bool {
      let instructions = &message.instructions;
      
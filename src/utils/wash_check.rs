@@ -0,0 +1,282 @@
+use {
+    crate::models::trade::Trade,
+    chrono::{DateTime, Utc},
+    serde::Serialize,
+    std::{
+        collections::{HashMap, VecDeque},
+        env,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct WashConfig {
+    /// Longest a buy may be held and still count as cycled when the same
+    /// wallet sells it.
+    pub round_trip_window: chrono::Duration,
+    /// Round trips a wallet must make before its cycled volume is treated as
+    /// wash trading. A single quick flip is ordinary trading.
+    pub min_round_trips: usize,
+    /// Wallets counted in the top-trader volume share.
+    pub top_traders: usize,
+    /// Trades needed before the rug check judges a token's volume.
+    pub min_trades: usize,
+    /// Share of volume, 0-100, wash trading must reach to be flagged.
+    pub wash_percent: f64,
+    /// Share of volume, 0-100, the top traders must reach to be flagged.
+    pub concentration_percent: f64,
+}
+
+impl WashConfig {
+    /// Reads `WASH_ROUND_TRIP_SECS` (default 300), `WASH_MIN_ROUND_TRIPS`
+    /// (default 2), `WASH_TOP_TRADERS` (default 5), `WASH_MIN_TRADES`
+    /// (default 20), `WASH_VOLUME_PERCENT` (default 50) and
+    /// `WASH_CONCENTRATION_PERCENT` (default 70).
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let percent = |name: &str| env::var(name).ok().and_then(|v| v.parse::<f64>().ok());
+        WashConfig {
+            round_trip_window: chrono::Duration::seconds(read("WASH_ROUND_TRIP_SECS").unwrap_or(300) as i64),
+            min_round_trips: read("WASH_MIN_ROUND_TRIPS").unwrap_or(2).max(1) as usize,
+            top_traders: read("WASH_TOP_TRADERS").unwrap_or(5).max(1) as usize,
+            min_trades: read("WASH_MIN_TRADES").unwrap_or(20) as usize,
+            wash_percent: percent("WASH_VOLUME_PERCENT").unwrap_or(50.0),
+            concentration_percent: percent("WASH_CONCENTRATION_PERCENT").unwrap_or(70.0),
+        }
+    }
+}
+
+/// A wallet that kept buying and selling the same tokens.
+#[derive(Debug, Clone, Serialize)]
+pub struct WashWallet {
+    pub address: String,
+    /// Sells that closed, at least in part, a buy made within the window.
+    pub round_trips: usize,
+    /// Lamports traded on both legs of those round trips.
+    pub cycled_volume: i64,
+    /// Lamports the wallet traded in total.
+    pub volume: i64,
+}
+
+/// How much of a token's volume looks manufactured. Volumes are lamports.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WashReport {
+    pub trade_count: usize,
+    pub unique_traders: usize,
+    /// Trades per distinct wallet; high when a few wallets make the volume.
+    pub trades_per_trader: f64,
+    pub volume: i64,
+    /// Volume left after the wash wallets' round trips are taken out.
+    pub organic_volume: i64,
+    pub wash_volume: i64,
+    pub wash_wallets: Vec<WashWallet>,
+    /// Share of volume, 0-100, traded by the `top_traders` busiest wallets.
+    pub top_trader_share: f64,
+}
+
+impl WashReport {
+    /// Share of volume, 0-100, that was cycled by wash wallets.
+    pub fn wash_percent(&self) -> f64 {
+        if self.volume == 0 {
+            return 0.0;
+        }
+        self.wash_volume as f64 / self.volume as f64 * 100.0
+    }
+}
+
+/// A buy not yet matched against a sell.
+struct OpenBuy {
+    traded_at: DateTime<Utc>,
+    token_amount: i64,
+    remaining: i64,
+    sol_amount: i64,
+}
+
+#[derive(Default)]
+struct WalletActivity {
+    open_buys: VecDeque<OpenBuy>,
+    round_trips: usize,
+    cycled_volume: f64,
+    volume: i64,
+}
+
+/// Looks for volume inflated by wallets cycling buys and sells. Each sell is
+/// matched, oldest first, against the same wallet's buys from the last
+/// `round_trip_window`; both legs of the matched part count as cycled. Once a
+/// wallet has made `min_round_trips` round trips, its cycled volume is
+/// treated as wash trading and taken out of the organic volume.
+///
+/// # Arguments
+/// * `trades` - The token's trades, in any order
+/// * `config` - Detection settings
+pub fn analyze_wash_trading(trades: &[Trade], config: &WashConfig) -> WashReport {
+    let mut ordered: Vec<&Trade> = trades.iter().collect();
+    ordered.sort_by_key(|t| (t.traded_at, t.slot, t.event_index));
+
+    let mut wallets: HashMap<&str, WalletActivity> = HashMap::new();
+    for trade in ordered {
+        let wallet = wallets.entry(trade.trader.as_str()).or_default();
+        wallet.volume += trade.sol_amount;
+        if trade.is_buy {
+            wallet.open_buys.push_back(OpenBuy {
+                traded_at: trade.traded_at,
+                token_amount: trade.token_amount,
+                remaining: trade.token_amount,
+                sol_amount: trade.sol_amount,
+            });
+            continue;
+        }
+        // Buys held past the window are positions, not round trips.
+        let cutoff = trade.traded_at - config.round_trip_window;
+        while wallet.open_buys.front().is_some_and(|buy| buy.traded_at < cutoff) {
+            wallet.open_buys.pop_front();
+        }
+        let mut unmatched = trade.token_amount;
+        let mut cycled = 0.0;
+        while unmatched > 0 {
+            let Some(buy) = wallet.open_buys.front_mut() else {
+                break;
+            };
+            let matched = unmatched.min(buy.remaining);
+            cycled += buy.sol_amount as f64 * matched as f64 / buy.token_amount.max(1) as f64;
+            cycled += trade.sol_amount as f64 * matched as f64 / trade.token_amount.max(1) as f64;
+            buy.remaining -= matched;
+            unmatched -= matched;
+            if buy.remaining == 0 {
+                wallet.open_buys.pop_front();
+            }
+        }
+        if cycled > 0.0 {
+            wallet.round_trips += 1;
+            wallet.cycled_volume += cycled;
+        }
+    }
+
+    let volume: i64 = wallets.values().map(|wallet| wallet.volume).sum();
+    let mut wash_wallets: Vec<WashWallet> = wallets
+        .iter()
+        .filter(|(_, wallet)| wallet.round_trips >= config.min_round_trips)
+        .map(|(address, wallet)| WashWallet {
+            address: address.to_string(),
+            round_trips: wallet.round_trips,
+            cycled_volume: wallet.cycled_volume.round() as i64,
+            volume: wallet.volume,
+        })
+        .collect();
+    wash_wallets.sort_by(|a, b| b.cycled_volume.cmp(&a.cycled_volume).then_with(|| a.address.cmp(&b.address)));
+    let wash_volume = wash_wallets.iter().map(|wallet| wallet.cycled_volume).sum::<i64>().min(volume);
+
+    let mut volumes: Vec<i64> = wallets.values().map(|wallet| wallet.volume).collect();
+    volumes.sort_unstable_by(|a, b| b.cmp(a));
+    let top_volume: i64 = volumes.iter().take(config.top_traders).sum();
+
+    WashReport {
+        trade_count: trades.len(),
+        unique_traders: wallets.len(),
+        trades_per_trader: if wallets.is_empty() { 0.0 } else { trades.len() as f64 / wallets.len() as f64 },
+        volume,
+        organic_volume: volume - wash_volume,
+        wash_volume,
+        wash_wallets,
+        top_trader_share: if volume == 0 { 0.0 } else { top_volume as f64 / volume as f64 * 100.0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::TimeZone};
+
+    fn config() -> WashConfig {
+        WashConfig {
+            round_trip_window: chrono::Duration::minutes(5),
+            min_round_trips: 2,
+            top_traders: 1,
+            min_trades: 0,
+            wash_percent: 50.0,
+            concentration_percent: 70.0,
+        }
+    }
+
+    fn trade(trader: &str, secs: i64, is_buy: bool, token_amount: i64, sol_amount: i64) -> Trade {
+        let trade = Trade::test("mint")
+            .by(trader)
+            .in_slot(secs)
+            .at(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::seconds(secs));
+        if is_buy {
+            trade.buy(sol_amount, token_amount)
+        } else {
+            trade.sell(sol_amount, token_amount)
+        }
+    }
+
+    #[test]
+    fn sells_close_the_oldest_buys_first() {
+        // Given out of order: analysis sorts by time.
+        let trades = [
+            trade("washer", 60, false, 150, 3_000),
+            trade("washer", 0, true, 100, 1_000),
+            trade("washer", 10, true, 100, 2_000),
+            trade("washer", 90, false, 50, 1_000),
+        ];
+        let report = analyze_wash_trading(&trades, &config());
+        let washer = &report.wash_wallets[0];
+        assert_eq!(washer.round_trips, 2);
+        // First sell: all of the first buy (1000 + 2000 of the sell) and half
+        // of the second (1000 + 1000). Second sell: the rest (1000 + 1000).
+        assert_eq!(washer.cycled_volume, 7_000);
+        assert_eq!((report.volume, report.wash_volume, report.organic_volume), (7_000, 7_000, 0));
+        assert_eq!(report.wash_percent(), 100.0);
+    }
+
+    #[test]
+    fn buys_held_past_the_window_are_positions() {
+        let trades = [
+            trade("holder", 0, true, 100, 1_000),
+            trade("holder", 400, false, 50, 600),
+            trade("holder", 410, true, 100, 1_000),
+            trade("holder", 420, false, 100, 1_200),
+        ];
+        let report = analyze_wash_trading(&trades, &config());
+        // Only the last sell closes a buy from the window.
+        assert!(report.wash_wallets.is_empty());
+        assert_eq!(report.organic_volume, report.volume);
+    }
+
+    #[test]
+    fn a_single_flip_is_not_wash_trading() {
+        let trades = [
+            trade("flipper", 0, true, 100, 1_000),
+            trade("flipper", 30, false, 100, 1_100),
+            trade("buyer", 40, true, 100, 1_000),
+        ];
+        let report = analyze_wash_trading(&trades, &WashConfig { min_round_trips: 2, ..config() });
+        assert!(report.wash_wallets.is_empty());
+        assert_eq!((report.wash_volume, report.organic_volume), (0, 3_100));
+
+        let report = analyze_wash_trading(&trades, &WashConfig { min_round_trips: 1, ..config() });
+        assert_eq!(report.wash_wallets[0].address, "flipper");
+        assert_eq!(report.wash_volume, 2_100);
+    }
+
+    #[test]
+    fn sells_of_tokens_never_bought_match_nothing() {
+        let trades = [
+            trade("airdropped", 0, false, 100, 1_000),
+            trade("airdropped", 10, false, 100, 1_000),
+            trade("other", 20, true, 100, 1_000),
+            trade("other", 30, false, 100, 1_000),
+        ];
+        let report = analyze_wash_trading(&trades, &WashConfig { min_round_trips: 1, ..config() });
+        let washers: Vec<&str> = report.wash_wallets.iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(washers, ["other"]);
+        // Each of the two wallets made two trades and half the volume.
+        assert_eq!((report.trade_count, report.unique_traders), (4, 2));
+        assert_eq!(report.trades_per_trader, 2.0);
+        assert_eq!(report.top_trader_share, 50.0);
+    }
+
+    #[test]
+    fn no_trades_means_no_volume() {
+        let report = analyze_wash_trading(&[], &config());
+        assert_eq!((report.volume, report.top_trader_share, report.wash_percent()), (0, 0.0, 0.0));
+    }
+}